@@ -0,0 +1,45 @@
+//! Per-stage compiler benchmarks over generated modules of increasing size.
+//!
+//! Run with `cargo bench -p grey_backends`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use grey_backends::betti_rdl::BettiRdlBackend;
+use grey_backends::CodeGenerator;
+use grey_ir::IrBuilder;
+use grey_lang::samples::scaling_module_source;
+
+const PROCESS_COUNTS: [usize; 3] = [10, 100, 500];
+
+fn bench_stages(c: &mut Criterion) {
+    for &processes in &PROCESS_COUNTS {
+        let source = scaling_module_source(processes);
+        let tokens = grey_lang::lexer::lex(&source).unwrap();
+        let program = grey_lang::parser::parse_program(&tokens).unwrap();
+        let typed = grey_lang::type_check_program(&program).unwrap();
+        let ir = IrBuilder::new().build_program("scaling", &typed).unwrap().clone();
+
+        c.bench_with_input(BenchmarkId::new("lex", processes), &source, |b, source| {
+            b.iter(|| grey_lang::lexer::lex(black_box(source)).unwrap())
+        });
+
+        c.bench_with_input(BenchmarkId::new("parse_program", processes), &tokens, |b, tokens| {
+            b.iter(|| grey_lang::parser::parse_program(black_box(tokens)).unwrap())
+        });
+
+        c.bench_with_input(BenchmarkId::new("type_check_program", processes), &program, |b, program| {
+            b.iter(|| grey_lang::type_check_program(black_box(program)).unwrap())
+        });
+
+        c.bench_with_input(BenchmarkId::new("build_program", processes), &typed, |b, typed| {
+            b.iter(|| IrBuilder::new().build_program("scaling", black_box(typed)).unwrap().clone())
+        });
+
+        let backend = BettiRdlBackend::new_with_defaults();
+        c.bench_with_input(BenchmarkId::new("generate_code", processes), &ir, |b, ir| {
+            b.iter(|| backend.generate_code(black_box(ir)).unwrap())
+        });
+    }
+}
+
+criterion_group!(benches, bench_stages);
+criterion_main!(benches);
@@ -4,20 +4,25 @@
 //! providing process allocation, deterministic event ordering, and bounded resource
 //! metadata as required for the Grey-to-Betti compilation pipeline.
 
-use std::collections::HashMap;
+#[cfg(feature = "betti-runtime")]
+use std::borrow::BorrowMut;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::PathBuf;
 use anyhow::Result;
 use log::{info, debug};
 
 use grey_ir::{
-    Coord, IrProgram, IrValue,
+    Coord, IrAction, IrCoordExpression, IrProcess, IrProgram,
 };
 use crate::{
     CodeGenerator, CodeGenOutput, RuntimeConfig, ProcessPlacement, 
     EventOrdering, ExecutionTelemetry, BackendError, 
-    CodeGenMetadata, ConfigOption
+    CodeGenMetadata, CodeGenPlan, ConfigOption
+};
+use crate::interpreter::Interpreter;
+use crate::utils::{
+    declared_runtime_processes, event_budget, grid_coords, process_footprints, validate_program,
 };
-use crate::utils::{validate_program, generate_process_coords};
 
 /// Betti RDL Backend implementation
 pub struct BettiRdlBackend {
@@ -35,21 +40,44 @@ pub struct BettiConfig {
     /// Seed used for deterministic injection patterns.
     pub seed: u64,
 
+    /// Seed for breaking same-time ties when the interpreter orders events;
+    /// `None` uses `seed`
+    pub ordering_seed: Option<u64>,
+
     /// Enable detailed telemetry collection
     pub telemetry_enabled: bool,
 
     /// Coordinate bounds checking
     pub validate_coordinates: bool,
+
+    /// Reject placements, injection targets and literal `SendEvent` targets with a
+    /// component outside `0..=31` instead of letting the kernel wrap them onto
+    /// its toroidal lattice
+    pub strict_coords: bool,
+
+    /// Estimated cost of one event, used for `expected_execution_time`
+    pub per_event_ns: u64,
+
+    /// Estimated cost of spawning one runtime process
+    pub per_process_ns: u64,
+
+    /// Events to inject instead of the seeded pattern, as `(target, value)` pairs
+    pub injection_plan: Option<Vec<(Coord, i32)>>,
 }
 
 impl Default for BettiConfig {
     fn default() -> Self {
         Self {
-            process_placement: ProcessPlacement::GridLayout { spacing: 1 },
+            process_placement: ProcessPlacement::GridLayout { spacing: 1, columns: None },
             max_events: 1000,
             seed: 42,
+            ordering_seed: None,
             telemetry_enabled: true,
             validate_coordinates: true,
+            strict_coords: false,
+            per_event_ns: 1000,
+            per_process_ns: 500,
+            injection_plan: None,
         }
     }
 }
@@ -63,43 +91,129 @@ impl BettiRdlBackend {
         Self::new(BettiConfig::default())
     }
 
-    fn estimate_execution_time_ns(&self, program: &IrProgram, runtime_process_count: usize) -> u64 {
-        let event_count = program.events.len() as u64;
-        let max_events = if self.config.max_events > 0 {
-            self.config.max_events as u64
-        } else {
-            event_count
-        };
-        let bounded_events = if max_events == 0 { event_count } else { event_count.min(max_events) };
-        let per_event_ns = 1000u64;
-        let per_process_ns = 500u64;
-        bounded_events
-            .saturating_mul(per_event_ns)
-            .saturating_add((runtime_process_count as u64).saturating_mul(per_process_ns))
+    /// Execute `output` `runs` times and check that every run reports the same telemetry.
+    ///
+    /// Wall-clock execution time is ignored. On mismatch the error names the run and
+    /// the first telemetry field that differs from the first run.
+    pub fn verify_determinism(&self, output: &CodeGenOutput, runs: usize) -> Result<(), BackendError> {
+        if runs < 2 {
+            return Ok(());
+        }
+
+        let baseline = self.execute(output)?;
+        for run in 2..=runs {
+            let telemetry = self.execute(output)?;
+            if let Some(difference) = first_telemetry_difference(&baseline, &telemetry) {
+                return Err(BackendError::ValidationError(format!(
+                    "Nondeterministic execution: run {} differs from run 1 in {}",
+                    run, difference
+                )));
+            }
+        }
+
+        Ok(())
     }
-}
 
-impl CodeGenerator for BettiRdlBackend {
-    fn generate_code(&self, program: &IrProgram) -> Result<CodeGenOutput, BackendError> {
-        info!("Generating Betti RDL code for program: {}", program.name);
-        
+    /// Execute `output` once per seed and return each seed's telemetry, in order.
+    pub fn execute_seed_sweep(
+        &self,
+        output: &CodeGenOutput,
+        seeds: &[u64],
+    ) -> Result<Vec<(u64, ExecutionTelemetry)>, BackendError> {
+        seeds
+            .iter()
+            .map(|&seed| {
+                let backend = Self::new(BettiConfig {
+                    seed,
+                    ..self.config.clone()
+                });
+                backend.execute(output).map(|telemetry| (seed, telemetry))
+            })
+            .collect()
+    }
+
+    /// Table of where each runtime process of `output` is placed: name, coordinate
+    /// and kernel node id, the `pid` keying `process_states`. Rows are sorted by
+    /// node id, then name.
+    pub fn placement_report(&self, output: &CodeGenOutput) -> String {
+        let placement = output
+            .runtime_config
+            .process_placement
+            .resolve()
+            .unwrap_or_else(|_| output.runtime_config.process_placement.clone());
+        let mut rows: Vec<(usize, String, Coord)> =
+            named_coords(&placement, output.metadata.runtime_process_count)
+                .into_iter()
+                .map(|(name, coord)| (coord.to_node_id(), name, coord))
+                .collect();
+        rows.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+
+        let width = rows.iter().map(|(_, name, _)| name.len()).max().unwrap_or(0).max(7);
+        let mut report = format!("{:<width$}  {:<15}  node_id\n", "process", "coord");
+        for (node_id, name, coord) in rows {
+            let coord = format!("({}, {}, {})", coord.x, coord.y, coord.z);
+            report.push_str(&format!("{:<width$}  {:<15}  {}\n", name, coord, node_id));
+        }
+        report
+    }
+
+    /// Validate `program` and report what `generate_code` would produce, without
+    /// generating any code.
+    ///
+    /// `files` lists the executable followed by the validation module.
+    pub fn plan(&self, program: &IrProgram) -> Result<CodeGenPlan, BackendError> {
         // Validate program for backend compatibility
         validate_program(program)?;
+        event_budget(self.config.max_events)?;
 
-        let runtime_process_count = match &self.config.process_placement {
-            ProcessPlacement::Custom(coords) => coords.len().max(1),
-            ProcessPlacement::SingleNode => 1,
-            ProcessPlacement::GridLayout { .. } => {
-                let from_constants = program
-                    .constants
-                    .get("RUNTIME_PROCESSES")
-                    .or_else(|| program.constants.get("MAX_PROCESSES"))
-                    .and_then(|v| match v {
-                        IrValue::Integer(i) if *i > 0 => Some(*i as usize),
-                        _ => None,
+        if self.config.validate_coordinates {
+            let max = program.resources.max_coordinate_value;
+            for (coord, _) in self.config.injection_plan.iter().flatten() {
+                if !coord.is_valid_within(max) {
+                    return Err(BackendError::ValidationError(format!(
+                        "Injection target {:?} out of bounds (max component {})",
+                        coord, max
+                    )));
+                }
+            }
+        }
+
+        let placement = self.config.process_placement.resolve()?;
+        if let ProcessPlacement::Custom(mapping) = &placement {
+            let mut names: Vec<_> = mapping.keys().collect();
+            names.sort();
+            let mut placed: HashMap<usize, &String> = HashMap::new();
+            for name in names {
+                let coord = &mapping[name];
+                let max = program.resources.max_coordinate_value;
+                if self.config.validate_coordinates && !coord.is_valid_within(max) {
+                    return Err(BackendError::CoordinateOutOfBounds {
+                        process: name.clone(),
+                        coord: coord.clone(),
+                        max,
+                    });
+                }
+                if let Some(first) = placed.insert(coord.to_node_id(), name) {
+                    return Err(BackendError::CoordinateCollision {
+                        first: first.clone(),
+                        second: name.clone(),
+                        coord: coord.wrapped(),
                     });
+                }
+            }
+        }
 
-                from_constants.unwrap_or(program.processes.len().max(1))
+        let runtime_process_count = match &placement {
+            ProcessPlacement::Custom(coords) if coords.is_empty() => {
+                return Err(BackendError::ValidationError(
+                    "Custom placement has no processes".to_string(),
+                ));
+            }
+            ProcessPlacement::Custom(coords) => coords.len(),
+            ProcessPlacement::SingleNode => 1,
+            ProcessPlacement::FromFile(_) => unreachable!("resolved above"),
+            ProcessPlacement::GridLayout { .. } => {
+                declared_runtime_processes(program).unwrap_or(program.processes.len())
             }
         };
 
@@ -118,55 +232,256 @@ impl CodeGenerator for BettiRdlBackend {
             )));
         }
 
-        // Generate process placement coordinates
-        let process_coords = match &self.config.process_placement {
-            ProcessPlacement::SingleNode => {
-                let mut coords = HashMap::new();
-                coords.insert("p0".to_string(), Coord::new(0, 0, 0));
-                coords
+        if self.config.strict_coords {
+            self.check_strict_coords(program, &placement, runtime_process_count)?;
+        }
+
+        Ok(CodeGenPlan {
+            files: vec![
+                PathBuf::from(format!("{}_betti.rs", program.name)),
+                PathBuf::from(format!("{}_validation.rs", program.name)),
+            ],
+            runtime_config: RuntimeConfig {
+                max_events: self.config.max_events,
+                process_placement: placement,
+                event_ordering: EventOrdering::Deterministic,
+            },
+            metadata: CodeGenMetadata {
+                source_name: program.name.clone(),
+                process_count: program.processes.len(),
+                runtime_process_count,
+                event_count: program.events.len(),
+                expected_execution_time: Some(self.estimate_execution_time_ns(program, runtime_process_count)),
+                process_footprints: process_footprints(program),
+            },
+        })
+    }
+
+    /// Under `strict_coords`, check that nothing `program` addresses statically would
+    /// be wrapped: runtime placements, injection targets and literal send targets.
+    /// Computed send targets are only known at run time and are not checked.
+    fn check_strict_coords(
+        &self,
+        program: &IrProgram,
+        placement: &ProcessPlacement,
+        runtime_process_count: usize,
+    ) -> Result<(), BackendError> {
+        let mut placed: Vec<_> = named_coords(placement, runtime_process_count).into_iter().collect();
+        placed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        if let Some((process, coord)) = placed.into_iter().find(|(_, coord)| !coord.is_valid()) {
+            return Err(BackendError::CoordinateOutOfBounds { process, coord, max: 31 });
+        }
+
+        for (coord, _) in self.config.injection_plan.iter().flatten() {
+            if !coord.is_valid() {
+                return Err(BackendError::ValidationError(format!(
+                    "Injection target {:?} would wrap onto the 32-node lattice (strict_coords)",
+                    coord
+                )));
             }
-            ProcessPlacement::GridLayout { spacing } => {
-                let mut coords = HashMap::new();
-                let grid_size = ((runtime_process_count as f32).sqrt().ceil() as i32).max(1);
-
-                for i in 0..runtime_process_count {
-                    let x = (i as i32) % grid_size;
-                    let y = (i as i32) / grid_size;
-                    let z = 0;
-                    coords.insert(
-                        format!("p{}", i),
-                        Coord::new(x * spacing, y * spacing, z * spacing),
-                    );
+        }
+
+        for process in &program.processes {
+            for transition in &process.transitions {
+                for action in transition.flattened_actions() {
+                    if let IrAction::SendEvent {
+                        event_type,
+                        target: IrCoordExpression::Literal(coord),
+                        ..
+                    } = action
+                    {
+                        if !coord.is_valid() {
+                            return Err(BackendError::ValidationError(format!(
+                                "Process '{}' sends {} to {:?}, which would wrap onto the 32-node lattice (strict_coords)",
+                                process.name, event_type, coord
+                            )));
+                        }
+                    }
                 }
-                coords
             }
-            ProcessPlacement::Custom(coords) => coords.clone(),
+        }
+
+        Ok(())
+    }
+
+    /// Create a kernel with `output`'s processes spawned but no events injected.
+    ///
+    /// The returned handle lets callers inject and step the simulation themselves
+    /// instead of running it to completion in one `execute` call.
+    #[cfg(feature = "betti-runtime")]
+    pub fn prepare(&self, output: &CodeGenOutput) -> Result<RunningSim, BackendError> {
+        let kernel = betti_rdl::Kernel::try_new()
+            .map_err(|e| BackendError::RuntimeError(e.to_string()))?;
+        self.prepare_on(output, kernel)
+    }
+
+    /// Like [`prepare`](Self::prepare), but on a caller-supplied kernel such as one
+    /// checked out of a `KernelPool`. The kernel must be freshly created or reset.
+    #[cfg(feature = "betti-runtime")]
+    pub fn prepare_on<K: BorrowMut<betti_rdl::Kernel>>(
+        &self,
+        output: &CodeGenOutput,
+        mut kernel: K,
+    ) -> Result<RunningSim<K>, BackendError> {
+        let started = std::time::Instant::now();
+        let process_coords = self.spawn_processes(kernel.borrow_mut(), output)?;
+
+        Ok(RunningSim {
+            kernel,
+            process_coords,
+            telemetry_enabled: self.config.telemetry_enabled,
+            started,
+        })
+    }
+
+    /// Inject the seeded initial events into a prepared simulation and run it up
+    /// to `max_events`, as `execute` does.
+    #[cfg(feature = "betti-runtime")]
+    fn run_prepared<K: BorrowMut<betti_rdl::Kernel>>(
+        &self,
+        mut sim: RunningSim<K>,
+        output: &CodeGenOutput,
+    ) -> Result<ExecutionTelemetry, BackendError> {
+        let budget = event_budget(output.runtime_config.max_events)?;
+        let initial_events = self.initial_events(sim.process_coords());
+        for (coord, value) in &initial_events {
+            sim.inject(coord, *value);
+        }
+        debug!("Injected {} initial event(s)", initial_events.len());
+
+        sim.step(budget);
+        let telemetry = sim.telemetry();
+
+        info!("Execution completed: {} events processed in {:?}",
+              telemetry.events_processed, sim.started.elapsed());
+
+        Ok(telemetry)
+    }
+
+    /// Like [`execute_seed_sweep`](Self::execute_seed_sweep), but runs the seeds on
+    /// parallel threads, each on a kernel checked out of `pool`.
+    ///
+    /// At most `pool.capacity()` runs are in flight at once. Results are returned
+    /// in seed order and match a sequential sweep.
+    #[cfg(feature = "betti-runtime")]
+    pub fn execute_seed_sweep_pooled(
+        &self,
+        output: &CodeGenOutput,
+        seeds: &[u64],
+        pool: &betti_rdl::KernelPool,
+    ) -> Result<Vec<(u64, ExecutionTelemetry)>, BackendError> {
+        std::thread::scope(|scope| {
+            let runs: Vec<_> = seeds
+                .iter()
+                .map(|&seed| {
+                    scope.spawn(move || {
+                        let backend = Self::new(BettiConfig {
+                            seed,
+                            ..self.config.clone()
+                        });
+                        let kernel = pool
+                            .checkout()
+                            .map_err(|e| BackendError::RuntimeError(e.to_string()))?;
+                        let sim = backend.prepare_on(output, kernel)?;
+                        backend.run_prepared(sim, output).map(|telemetry| (seed, telemetry))
+                    })
+                })
+                .collect();
+
+            runs.into_iter()
+                .map(|run| {
+                    run.join()
+                        .map_err(|_| BackendError::RuntimeError("seed run panicked".to_string()))?
+                })
+                .collect()
+        })
+    }
+
+    fn estimate_execution_time_ns(&self, program: &IrProgram, runtime_process_count: usize) -> u64 {
+        let event_count = program.events.len() as u64;
+        let max_events = if self.config.max_events > 0 {
+            self.config.max_events as u64
+        } else {
+            event_count
         };
-        
-        // Generate runtime configuration
-        let runtime_config = RuntimeConfig {
-            max_events: self.config.max_events,
-            process_placement: self.config.process_placement.clone(),
-            event_ordering: EventOrdering::Deterministic,
+        let bounded_events = if max_events == 0 { event_count } else { event_count.min(max_events) };
+        bounded_events
+            .saturating_mul(self.config.per_event_ns)
+            .saturating_add((runtime_process_count as u64).saturating_mul(self.config.per_process_ns))
+    }
+}
+
+/// A live Betti RDL kernel driven step by step, created by [`BettiRdlBackend::prepare`]
+/// or, on a borrowed or pooled kernel, [`BettiRdlBackend::prepare_on`]
+#[cfg(feature = "betti-runtime")]
+pub struct RunningSim<K = betti_rdl::Kernel> {
+    kernel: K,
+    process_coords: Vec<Coord>,
+    telemetry_enabled: bool,
+    started: std::time::Instant,
+}
+
+#[cfg(feature = "betti-runtime")]
+impl<K: BorrowMut<betti_rdl::Kernel>> RunningSim<K> {
+    /// Coordinates of the spawned processes, in spawn order
+    pub fn process_coords(&self) -> &[Coord] {
+        &self.process_coords
+    }
+
+    /// Queue an event with `value` for the process at `coord`
+    pub fn inject(&mut self, coord: &Coord, value: i32) {
+        self.kernel.borrow_mut().inject_event(coord.x, coord.y, coord.z, value);
+    }
+
+    /// Process at most `max_events` queued events and return how many ran
+    pub fn step(&mut self, max_events: i32) -> i32 {
+        self.kernel.borrow_mut().run(max_events)
+    }
+
+    /// Process events until logical time reaches `target_time` and return how many
+    /// ran; stops short of the target if the event queue drains first
+    pub fn step_until_time(&mut self, target_time: u64) -> u64 {
+        self.kernel.borrow_mut().step_until_time(target_time)
+    }
+
+    /// Telemetry for everything processed so far; execution time counts from `prepare`
+    pub fn telemetry(&self) -> ExecutionTelemetry {
+        let kernel = self.kernel.borrow();
+        let process_states = if self.telemetry_enabled {
+            let pids: Vec<usize> = self.process_coords.iter().map(Coord::to_node_id).collect();
+            let states = kernel.process_states_bulk(&pids);
+            pids.into_iter().zip(states).collect()
+        } else {
+            BTreeMap::new()
         };
+
+        ExecutionTelemetry {
+            events_processed: kernel.events_processed(),
+            current_time: kernel.current_time(),
+            execution_time_ns: self.started.elapsed().as_nanos() as u64,
+            memory_usage_kb: None,
+            process_states,
+            quiescent: kernel.events_pending() == 0,
+        }
+    }
+}
+
+impl CodeGenerator for BettiRdlBackend {
+    fn generate_code(&self, program: &IrProgram) -> Result<CodeGenOutput, BackendError> {
+        info!("Generating Betti RDL code for program: {}", program.name);
+
+        let CodeGenPlan { files: paths, runtime_config, metadata } = self.plan(program)?;
+
+        // Generate process placement coordinates
+        let process_coords =
+            named_coords(&runtime_config.process_placement, metadata.runtime_process_count);
         
-        // Generate executable code
-        let mut files = HashMap::new();
-        let executable_code = self.generate_executable_code(program, &process_coords)?;
-        files.insert(PathBuf::from(format!("{}_betti.rs", program.name)), executable_code);
-        
-        // Generate validation code
-        let validation_code = self.generate_validation_code(program)?;
-        files.insert(PathBuf::from(format!("{}_validation.rs", program.name)), validation_code);
-        
-        // Generate metadata
-        let metadata = CodeGenMetadata {
-            source_name: program.name.clone(),
-            process_count: program.processes.len(),
-            runtime_process_count,
-            event_count: program.events.len(),
-            expected_execution_time: Some(self.estimate_execution_time_ns(program, runtime_process_count)),
-        };
+        // Generate executable and validation code, in the order `plan` lists them
+        let contents = [
+            self.generate_executable_code(program, &process_coords)?,
+            self.generate_validation_code(program)?,
+        ];
+        let files: HashMap<PathBuf, String> = paths.into_iter().zip(contents).collect();
         
         debug!("Generated {} files for Betti RDL backend", files.len());
         
@@ -177,43 +492,17 @@ impl CodeGenerator for BettiRdlBackend {
         })
     }
     
+    #[cfg(feature = "betti-runtime")]
     fn execute(&self, output: &CodeGenOutput) -> Result<ExecutionTelemetry, BackendError> {
         info!("Executing Betti RDL workload");
-        
-        let start_time = std::time::Instant::now();
-        
-        // Create Betti kernel
-        let mut kernel = betti_rdl::Kernel::new();
-        
-        // Spawn processes according to placement configuration
-        let process_coords = self.spawn_processes(&mut kernel, output)?;
-
-        // Inject initial events
-        self.inject_initial_events(&mut kernel, output, &process_coords)?;
-
-        // Run the kernel
-        let _events_in_run = kernel.run(output.runtime_config.max_events);
 
-        let execution_time = start_time.elapsed();
-        let execution_time_ns = execution_time.as_nanos() as u64;
+        let sim = self.prepare(output)?;
+        self.run_prepared(sim, output)
+    }
 
-        // Collect telemetry
-        let telemetry = if self.config.telemetry_enabled {
-            self.collect_telemetry(&kernel, &process_coords, execution_time_ns)?
-        } else {
-            ExecutionTelemetry {
-                events_processed: kernel.events_processed(),
-                current_time: kernel.current_time(),
-                execution_time_ns,
-                memory_usage_kb: None,
-                process_states: HashMap::new(),
-            }
-        };
-        
-        info!("Execution completed: {} events processed in {:?}",
-              telemetry.events_processed, execution_time);
-        
-        Ok(telemetry)
+    #[cfg(not(feature = "betti-runtime"))]
+    fn execute(&self, _output: &CodeGenOutput) -> Result<ExecutionTelemetry, BackendError> {
+        Err(BackendError::RuntimeError("runtime feature disabled".to_string()))
     }
     
     fn config_options(&self) -> HashMap<String, ConfigOption> {
@@ -239,7 +528,28 @@ impl CodeGenerator for BettiRdlBackend {
             default: "42".to_string(),
             allowed_values: vec!["0".to_string(), "1".to_string(), "42".to_string(), "123".to_string()],
         });
+
+        options.insert("per_event_ns".to_string(), ConfigOption {
+            name: "per_event_ns".to_string(),
+            description: "Estimated nanoseconds per event for the execution time estimate".to_string(),
+            default: "1000".to_string(),
+            allowed_values: vec![],
+        });
+
+        options.insert("per_process_ns".to_string(), ConfigOption {
+            name: "per_process_ns".to_string(),
+            description: "Estimated nanoseconds per runtime process for the execution time estimate".to_string(),
+            default: "500".to_string(),
+            allowed_values: vec![],
+        });
         
+        options.insert("strict_coords".to_string(), ConfigOption {
+            name: "strict_coords".to_string(),
+            description: "Reject coordinates outside 0..=31 instead of wrapping them".to_string(),
+            default: "false".to_string(),
+            allowed_values: vec!["true".to_string(), "false".to_string()],
+        });
+
         options.insert("telemetry_enabled".to_string(), ConfigOption {
             name: "telemetry_enabled".to_string(),
             description: "Enable detailed telemetry collection".to_string(),
@@ -286,7 +596,7 @@ impl {0}Executable {{
         
         // Generate coordinate initialization
         let mut process_entries: Vec<_> = process_coords.iter().collect();
-        process_entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        process_entries.sort_by_key(|(name, _)| *name);
 
         for (process_name, coord) in process_entries {
             code.push_str(&format!(
@@ -299,12 +609,12 @@ impl {0}Executable {{
         code.push_str("    }\n\n");
         
         // Generate process spawning
-        code.push_str(&format!(
-            "    pub fn spawn_processes(&mut self) -> Result<(), Box<dyn std::error::Error>> {{\n"
-        ));
+        code.push_str(
+            "    pub fn spawn_processes(&mut self) -> Result<(), Box<dyn std::error::Error>> {\n",
+        );
         
         let mut spawn_entries: Vec<_> = process_coords.iter().collect();
-        spawn_entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        spawn_entries.sort_by_key(|(name, _)| *name);
 
         for (process_name, coord) in spawn_entries {
             code.push_str(&format!(
@@ -317,25 +627,70 @@ impl {0}Executable {{
         code.push_str("    }\n\n");
         
         // Generate event injection methods
-        code.push_str(&format!(
-            "    pub fn inject_events(&mut self) -> Result<(), Box<dyn std::error::Error>> {{\n"
-        ));
+        code.push_str(
+            "    pub fn inject_events(&mut self) -> Result<(), Box<dyn std::error::Error>> {\n",
+        );
         
-        // Generate event injection based on program events and process coordinates
-        if !process_coords.is_empty() {
-            code.push_str("        // Inject initial events to first process\n");
-            code.push_str("        if let Some((x, y, z)) = self.process_coords.get(\"p0\") {\n");
-            code.push_str("            // Inject seed events to trigger process execution\n");
-            code.push_str("            self.kernel.inject_event(*x, *y, *z, 1);\n");
-            code.push_str("        }\n");
+        // Deliver one event per event type, tagged with its 1-based declaration index,
+        // to an instance of the first process type that handles it. Only a custom
+        // placement can name an instance after its process type; grid and
+        // single-node slots carry no type, so there the first instance stands in
+        // for every handler and the choice is nominal.
+        let mut instance_names: Vec<_> = process_coords.keys().collect();
+        instance_names.sort();
+        if !instance_names.is_empty() {
+            if program.events.is_empty() {
+                code.push_str("        // No events declared; inject a seed event to trigger execution\n");
+                code.push_str(&format!(
+                    "        if let Some((x, y, z)) = self.process_coords.get(\"{}\") {{\n",
+                    instance_names[0]
+                ));
+                code.push_str("            self.kernel.inject_event(*x, *y, *z, 1);\n");
+                code.push_str("        }\n");
+            }
+
+            for (index, event) in program.events.iter().enumerate() {
+                let handler = program
+                    .processes
+                    .iter()
+                    .find(|process| process.transitions.iter().any(|t| t.event_type == event.name));
+                let Some(process) = handler else {
+                    code.push_str(&format!(
+                        "        // `{}` has no handler; not injected\n",
+                        event.name
+                    ));
+                    continue;
+                };
+
+                let instance = if process_coords.contains_key(&process.name) {
+                    &process.name
+                } else {
+                    instance_names[0]
+                };
+                code.push_str(&format!(
+                    "        // `{}` (tag {}) handled by `{}`\n",
+                    event.name,
+                    index + 1,
+                    process.name
+                ));
+                code.push_str(&format!(
+                    "        if let Some((x, y, z)) = self.process_coords.get(\"{}\") {{\n",
+                    instance
+                ));
+                code.push_str(&format!(
+                    "            self.kernel.inject_event(*x, *y, *z, {});\n",
+                    index + 1
+                ));
+                code.push_str("        }\n");
+            }
         }
         code.push_str("        Ok(())\n");
         code.push_str("    }\n\n");
         
         // Generate execution method
-        code.push_str(&format!(
-            "    pub fn run(&mut self, max_events: i32) -> Result<HashMap<String, u64>, Box<dyn std::error::Error>> {{\n"
-        ));
+        code.push_str(
+            "    pub fn run(&mut self, max_events: i32) -> Result<HashMap<String, u64>, Box<dyn std::error::Error>> {\n",
+        );
         code.push_str("        let events_in_run = self.kernel.run(max_events);\n\n");
         code.push_str("        let mut results = HashMap::new();\n");
         code.push_str("        results.insert(\"events_in_run\".to_string(), events_in_run as u64);\n");
@@ -378,15 +733,8 @@ mod tests {{
     fn generate_validation_code(&self, program: &IrProgram) -> Result<String, BackendError> {
         let mut code = String::new();
 
-        let expected_processes = program
-            .constants
-            .get("RUNTIME_PROCESSES")
-            .or_else(|| program.constants.get("MAX_PROCESSES"))
-            .and_then(|v| match v {
-                IrValue::Integer(i) if *i > 0 => Some(*i as usize),
-                _ => None,
-            })
-            .unwrap_or(program.processes.len().max(1));
+        let expected_processes =
+            declared_runtime_processes(program).unwrap_or(program.processes.len());
         
         code.push_str(&format!(
             r#"//! Validation code for {} Betti RDL program
@@ -442,26 +790,32 @@ mod tests {{
         Ok(code)
     }
     
+    #[cfg(feature = "betti-runtime")]
     fn spawn_processes(
         &self,
         kernel: &mut betti_rdl::Kernel,
         output: &CodeGenOutput,
     ) -> Result<Vec<Coord>, BackendError> {
+        let coords = Self::runtime_coords(output);
+
+        debug!("Spawning {} processes", coords.len());
+
+        for coord in &coords {
+            kernel.spawn_process(coord.x, coord.y, coord.z);
+        }
+
+        info!("Spawned {} processes successfully", coords.len());
+        Ok(coords)
+    }
+
+    /// Coordinates of the process instances `output` runs, in spawn order
+    pub fn runtime_coords(output: &CodeGenOutput) -> Vec<Coord> {
         let process_count = output.metadata.runtime_process_count;
 
-        let coords: Vec<Coord> = match &output.runtime_config.process_placement {
+        match &output.runtime_config.process_placement {
             ProcessPlacement::SingleNode => vec![Coord::new(0, 0, 0)],
-            ProcessPlacement::GridLayout { spacing } => {
-                let grid_size = ((process_count as f32).sqrt().ceil() as i32).max(1);
-
-                (0..process_count)
-                    .map(|i| {
-                        let x = (i as i32) % grid_size;
-                        let y = (i as i32) / grid_size;
-                        let z = 0;
-                        Coord::new(x * spacing, y * spacing, z * spacing)
-                    })
-                    .collect()
+            ProcessPlacement::GridLayout { spacing, columns } => {
+                grid_coords(process_count, *spacing, *columns)
             }
             ProcessPlacement::Custom(mapping) => {
                 let mut keys: Vec<_> = mapping.keys().cloned().collect();
@@ -470,26 +824,98 @@ mod tests {{
                     .filter_map(|k| mapping.get(&k).cloned())
                     .collect()
             }
-        };
+            // Only hand-built outputs still hold a path; `plan` resolves it
+            ProcessPlacement::FromFile(_) => match output.runtime_config.process_placement.resolve() {
+                Ok(ProcessPlacement::Custom(mapping)) => {
+                    let mut entries: Vec<_> = mapping.into_iter().collect();
+                    entries.sort_by(|a, b| a.0.cmp(&b.0));
+                    entries.into_iter().map(|(_, coord)| coord).collect()
+                }
+                _ => Vec::new(),
+            },
+        }
+    }
 
-        debug!("Spawning {} processes", coords.len());
+    /// Run `program` on the pure-Rust [`Interpreter`] instead of the Betti kernel.
+    ///
+    /// Instances sit where `execute` would spawn them, cycling through the
+    /// program's process types, and receive the same seeded injections; same-time
+    /// ties are broken with the ordering seed. Each
+    /// injection is delivered as the first event its target handles, with every
+    /// event field at its default value. `process_states` is left empty because
+    /// interpreter state is a set of fields, not a kernel value.
+    pub fn execute_interpreted(
+        &self,
+        program: &IrProgram,
+        output: &CodeGenOutput,
+    ) -> Result<ExecutionTelemetry, BackendError> {
+        let started = std::time::Instant::now();
+        let coords = Self::runtime_coords(output);
 
-        for coord in &coords {
-            kernel.spawn_process(coord.x, coord.y, coord.z);
+        let mut instances = program.clone();
+        instances.processes = coords
+            .iter()
+            .zip(program.processes.iter().cycle())
+            .map(|(coord, process)| IrProcess { coord: coord.clone(), ..process.clone() })
+            .collect();
+
+        let max_events = event_budget(output.runtime_config.max_events)? as u64;
+        let ordering_seed = self.config.ordering_seed.unwrap_or(self.config.seed);
+        let mut interpreter =
+            Interpreter::new(&instances, output.runtime_config.event_ordering.clone(), max_events)
+                .with_ordering_seed(ordering_seed);
+
+        for (coord, _value) in self.initial_events(&coords) {
+            let Some(process) = instances.processes.iter().find(|p| p.coord == coord) else {
+                continue;
+            };
+            let event_type = process
+                .transitions
+                .iter()
+                .min_by_key(|t| t.priority)
+                .map(|t| t.event_type.as_str())
+                .or_else(|| program.events.first().map(|e| e.name.as_str()));
+            let Some(event_type) = event_type else {
+                continue;
+            };
+
+            let fields = program
+                .events
+                .iter()
+                .find(|e| e.name == event_type)
+                .map(|e| {
+                    e.fields
+                        .iter()
+                        .map(|(name, ty)| (name.clone(), ty.default_value()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            interpreter.inject(event_type, coord, fields);
         }
 
-        info!("Spawned {} processes successfully", coords.len());
-        Ok(coords)
+        let summary = interpreter.run()?;
+        Ok(ExecutionTelemetry {
+            events_processed: summary.events_processed,
+            current_time: summary.current_time,
+            execution_time_ns: started.elapsed().as_nanos() as u64,
+            memory_usage_kb: None,
+            process_states: BTreeMap::new(),
+            quiescent: !summary.truncated,
+        })
     }
 
-    fn inject_initial_events(
-        &self,
-        kernel: &mut betti_rdl::Kernel,
-        _output: &CodeGenOutput,
-        process_coords: &[Coord],
-    ) -> Result<(), BackendError> {
+    /// Seeded initial injections for `process_coords`, as `(target, value)` pairs.
+    ///
+    /// `execute` injects exactly these before running, so replaying them through a
+    /// `RunningSim` reproduces a batch run. A configured `injection_plan` is
+    /// returned as-is.
+    pub fn initial_events(&self, process_coords: &[Coord]) -> Vec<(Coord, i32)> {
+        if let Some(plan) = &self.config.injection_plan {
+            return plan.clone();
+        }
+
         if process_coords.is_empty() {
-            return Ok(());
+            return Vec::new();
         }
 
         struct XorShift64 {
@@ -514,74 +940,67 @@ mod tests {{
         let mut rng = XorShift64::new(self.config.seed);
         let injections = 4.min(process_coords.len());
 
-        for _ in 0..injections {
-            let idx = (rng.next_u64() as usize) % process_coords.len();
-            let value = (rng.next_u64() % 5) as i32 + 1;
-            let coord = &process_coords[idx];
-            kernel.inject_event(coord.x, coord.y, coord.z, value);
-        }
-
-        debug!("Injected {} initial event(s)", injections);
-        Ok(())
+        (0..injections)
+            .map(|_| {
+                let idx = (rng.next_u64() as usize) % process_coords.len();
+                let value = (rng.next_u64() % 5) as i32 + 1;
+                (process_coords[idx].clone(), value)
+            })
+            .collect()
     }
+}
 
-    fn collect_telemetry(
-        &self,
-        kernel: &betti_rdl::Kernel,
-        process_coords: &[Coord],
-        execution_time_ns: u64,
-    ) -> Result<ExecutionTelemetry, BackendError> {
-        let mut process_states = HashMap::new();
-
-        for coord in process_coords {
-            let pid = Self::node_id(coord) as usize;
-            process_states.insert(pid, kernel.process_state(pid as i32));
+/// Runtime process names and coordinates for `placement`, as `generate_code`
+/// spawns them. Grid and single-node processes are named `p0`, `p1`, ...; an
+/// unresolved `FromFile` placement yields nothing.
+fn named_coords(placement: &ProcessPlacement, runtime_process_count: usize) -> HashMap<String, Coord> {
+    match placement {
+        ProcessPlacement::SingleNode => HashMap::from([("p0".to_string(), Coord::new(0, 0, 0))]),
+        ProcessPlacement::GridLayout { spacing, columns } => {
+            grid_coords(runtime_process_count, *spacing, *columns)
+                .into_iter()
+                .enumerate()
+                .map(|(i, coord)| (format!("p{}", i), coord))
+                .collect()
         }
+        ProcessPlacement::Custom(coords) => coords.clone(),
+        ProcessPlacement::FromFile(_) => HashMap::new(),
+    }
+}
 
-        Ok(ExecutionTelemetry {
-            events_processed: kernel.events_processed(),
-            current_time: kernel.current_time(),
-            execution_time_ns,
-            memory_usage_kb: None,
-            process_states,
-        })
+/// Describe the first deterministic telemetry field that differs between two runs.
+fn first_telemetry_difference(a: &ExecutionTelemetry, b: &ExecutionTelemetry) -> Option<String> {
+    if a.events_processed != b.events_processed {
+        return Some(format!("events_processed: {} vs {}", a.events_processed, b.events_processed));
     }
 
-    fn node_id(coord: &Coord) -> i32 {
-        fn wrap(v: i32) -> i32 {
-            let m = v % 32;
-            if m < 0 { m + 32 } else { m }
-        }
+    if a.current_time != b.current_time {
+        return Some(format!("current_time: {} vs {}", a.current_time, b.current_time));
+    }
 
-        let x = wrap(coord.x);
-        let y = wrap(coord.y);
-        let z = wrap(coord.z);
-        x * 1024 + y * 32 + z
+    if a.quiescent != b.quiescent {
+        return Some(format!("quiescent: {} vs {}", a.quiescent, b.quiescent));
     }
+
+    let pids: BTreeSet<_> = a.process_states.keys().chain(b.process_states.keys()).collect();
+
+    pids.into_iter().find_map(|pid| {
+        let (left, right) = (a.process_states.get(pid), b.process_states.get(pid));
+        (left != right).then(|| format!("process_states[{}]: {:?} vs {:?}", pid, left, right))
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use grey_ir::{IrProgram, IrProcess, IrResourceBounds};
+    use grey_ir::{IrProgram, IrProgramBuilder};
     use std::collections::HashMap;
     
     fn create_test_program() -> IrProgram {
-        IrProgram {
-            name: "test_program".to_string(),
-            processes: vec![IrProcess {
-                name: "test_process".to_string(),
-                coord: Coord::new(0, 0, 0),
-                fields: HashMap::new(),
-                initial_state: grey_ir::IrState {
-                    values: HashMap::new(),
-                },
-                transitions: vec![],
-            }],
-            events: vec![],
-            constants: HashMap::new(),
-            resources: IrResourceBounds::default(),
-        }
+        IrProgramBuilder::new("test_program")
+            .process("test_process", Coord::new(0, 0, 0))
+            .build()
+            .unwrap()
     }
     
     #[test]
@@ -598,9 +1017,487 @@ mod tests {
         let output = backend.generate_code(&program).unwrap();
         assert!(!output.files.is_empty());
         assert!(output.metadata.process_count > 0);
+        assert_eq!(output.metadata.process_footprints["test_process"], 0);
     }
     
     #[test]
+    fn test_execution_estimate_scales_with_cost_constants() {
+        let mut program = create_test_program();
+        program.events.push(grey_ir::IrEvent {
+            name: "Tick".to_string(),
+            fields: HashMap::new(),
+        });
+
+        let base = BettiRdlBackend::new_with_defaults();
+        let doubled = BettiRdlBackend::new(BettiConfig {
+            per_event_ns: 2000,
+            per_process_ns: 1000,
+            ..BettiConfig::default()
+        });
+
+        let base_estimate = base.estimate_execution_time_ns(&program, 4);
+        assert_eq!(base_estimate, 1000 + 4 * 500);
+        assert_eq!(doubled.estimate_execution_time_ns(&program, 4), 2 * base_estimate);
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_verify_determinism_passes_for_seeded_injection() {
+        let backend = BettiRdlBackend::new_with_defaults();
+        let output = backend.generate_code(&create_test_program()).unwrap();
+
+        assert!(backend.verify_determinism(&output, 3).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_seed_sweep_returns_telemetry_per_seed() {
+        let backend = BettiRdlBackend::new_with_defaults();
+        let output = backend.generate_code(&create_test_program()).unwrap();
+
+        let sweep = backend.execute_seed_sweep(&output, &[1, 2, 3]).unwrap();
+        let seeds: Vec<_> = sweep.iter().map(|(seed, _)| *seed).collect();
+        assert_eq!(seeds, vec![1, 2, 3]);
+
+        let repeated = backend.execute_seed_sweep(&output, &[7, 7]).unwrap();
+        assert_eq!(first_telemetry_difference(&repeated[0].1, &repeated[1].1), None);
+    }
+
+    #[test]
+    fn test_telemetry_difference_names_first_field() {
+        let telemetry = |states: &[(usize, i32)]| ExecutionTelemetry {
+            events_processed: 4,
+            current_time: 2,
+            execution_time_ns: 0,
+            memory_usage_kb: None,
+            process_states: states.iter().copied().collect(),
+            quiescent: true,
+        };
+
+        let a = telemetry(&[(0, 1), (1, 3)]);
+        let b = telemetry(&[(0, 1), (1, 4)]);
+
+        assert_eq!(first_telemetry_difference(&a, &a), None);
+        assert_eq!(
+            first_telemetry_difference(&a, &b).unwrap(),
+            "process_states[1]: Some(3) vs Some(4)"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_deterministic_runs_produce_equal_telemetry() {
+        use std::hash::{BuildHasher, RandomState};
+
+        let backend = BettiRdlBackend::new_with_defaults();
+        let output = backend.generate_code(&create_test_program()).unwrap();
+        let first = backend.execute(&output).unwrap();
+        let second = backend.execute(&output).unwrap();
+
+        assert_eq!(first, second);
+        let hasher = RandomState::new();
+        assert_eq!(hasher.hash_one(&first), hasher.hash_one(&second));
+
+        let other_seed = BettiRdlBackend::new(BettiConfig { seed: 7, ..BettiConfig::default() });
+        assert_ne!(first, other_seed.execute(&output).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_quiescent_only_when_queue_drains() {
+        let run = |max_events| {
+            let backend = BettiRdlBackend::new(BettiConfig {
+                max_events,
+                ..BettiConfig::default()
+            });
+            let output = backend.generate_code(&create_test_program()).unwrap();
+            backend.execute(&output).unwrap()
+        };
+
+        assert!(!run(1).quiescent);
+        assert!(run(100_000).quiescent);
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_pooled_seed_sweeps_match_sequential_runs() {
+        let backend = BettiRdlBackend::new_with_defaults();
+        let output = backend.generate_code(&create_test_program()).unwrap();
+        let seeds = [1, 2, 3, 42, 7, 7];
+        let expected = backend.execute_seed_sweep(&output, &seeds).unwrap();
+
+        let pool = betti_rdl::KernelPool::new(2);
+        std::thread::scope(|scope| {
+            let sweeps: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| backend.execute_seed_sweep_pooled(&output, &seeds, &pool).unwrap()))
+                .collect();
+            for sweep in sweeps {
+                assert_eq!(sweep.join().unwrap(), expected);
+            }
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_stepped_simulation_matches_execute() {
+        let backend = BettiRdlBackend::new_with_defaults();
+        let output = backend.generate_code(&create_test_program()).unwrap();
+        let batch = backend.execute(&output).unwrap();
+
+        let mut sim = backend.prepare(&output).unwrap();
+        for (coord, value) in backend.initial_events(sim.process_coords()) {
+            sim.inject(&coord, value);
+        }
+        let mut steps = 0;
+        while steps < output.runtime_config.max_events && sim.step(1) == 1 {
+            steps += 1;
+        }
+
+        assert_eq!(first_telemetry_difference(&batch, &sim.telemetry()), None);
+        assert!(sim.telemetry().events_processed > 0);
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_stepping_until_time_advances_logical_clock() {
+        let backend = BettiRdlBackend::new_with_defaults();
+        let output = backend.generate_code(&create_test_program()).unwrap();
+        let mut sim = backend.prepare(&output).unwrap();
+        sim.inject(&sim.process_coords()[0].clone(), 4);
+
+        let processed = sim.step_until_time(2);
+        let telemetry = sim.telemetry();
+        assert!(telemetry.current_time >= 2);
+        assert_eq!(processed, telemetry.events_processed);
+    }
+
+    fn injection_plan_backend() -> BettiRdlBackend {
+        let plan: Vec<(Coord, i32)> = serde_json::from_str(
+            r#"[[{"x":2,"y":0,"z":0},1],[{"x":0,"y":0,"z":0},1],[{"x":1,"y":0,"z":0},1]]"#,
+        )
+        .unwrap();
+        BettiRdlBackend::new(BettiConfig {
+            process_placement: ProcessPlacement::Custom(
+                (0..3).map(|x| (format!("p{x}"), Coord::new(x, 0, 0))).collect(),
+            ),
+            injection_plan: Some(plan),
+            ..BettiConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_injection_plan_replaces_seeded_events() {
+        let backend = injection_plan_backend();
+        let targets: Vec<(i32, i32)> = backend
+            .initial_events(&[Coord::new(5, 5, 5)])
+            .iter()
+            .map(|(coord, value)| (coord.x, *value))
+            .collect();
+        assert_eq!(targets, vec![(2, 1), (0, 1), (1, 1)]);
+
+        let mut program = create_test_program();
+        program.resources.max_coordinate_value = 1;
+        let err = backend.generate_code(&program).unwrap_err();
+        assert!(err.to_string().contains("Injection target"));
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_kernel_receives_injection_plan_in_order() {
+        let backend = injection_plan_backend();
+        let output = backend.generate_code(&create_test_program()).unwrap();
+        assert_eq!(backend.execute(&output).unwrap().events_processed, 3);
+
+        let mut sim = backend.prepare(&output).unwrap();
+        for (coord, value) in backend.initial_events(sim.process_coords()) {
+            sim.inject(&coord, value);
+        }
+        let mut received = Vec::new();
+        while sim.step(1) == 1 {
+            let states = sim.telemetry().process_states;
+            let coord = sim
+                .process_coords()
+                .iter()
+                .find(|c| states[&c.to_node_id()] == 1 && !received.contains(&c.x))
+                .unwrap();
+            received.push(coord.x);
+        }
+        assert_eq!(received, vec![2, 0, 1]);
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_telemetry_lists_process_states_by_ascending_pid() {
+        // Spawned in name order, so "a" (pid 3072) is spawned before "b" (pid 32)
+        let backend = BettiRdlBackend::new(BettiConfig {
+            process_placement: ProcessPlacement::Custom(HashMap::from([
+                ("a".to_string(), Coord::new(3, 0, 0)),
+                ("b".to_string(), Coord::new(0, 1, 0)),
+            ])),
+            ..BettiConfig::default()
+        });
+        let output = backend.generate_code(&create_test_program()).unwrap();
+        let sim = backend.prepare(&output).unwrap();
+
+        let pids: Vec<usize> = sim.telemetry().process_states.into_keys().collect();
+        assert_eq!(pids, vec![32, 3072]);
+    }
+
+    #[test]
+    fn test_strict_coords_rejects_wrapping_coordinates() {
+        let strict = |process_placement| {
+            BettiRdlBackend::new(BettiConfig {
+                process_placement,
+                strict_coords: true,
+                ..BettiConfig::default()
+            })
+        };
+        let wide_grid = ProcessPlacement::GridLayout { spacing: 40, columns: None };
+        let two_processes = IrProgramBuilder::new("wide")
+            .process("left", Coord::new(0, 0, 0))
+            .process("right", Coord::new(1, 0, 0))
+            .build()
+            .unwrap();
+
+        // By default the second grid process at x = 40 is wrapped onto x = 8
+        let lenient = BettiRdlBackend::new(BettiConfig {
+            process_placement: wide_grid.clone(),
+            ..BettiConfig::default()
+        });
+        assert!(lenient.plan(&two_processes).is_ok());
+        let err = strict(wide_grid).plan(&two_processes).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Process 'p1' has invalid coordinate (40, 0, 0) (max component 31)"
+        );
+
+        let sender = IrProgramBuilder::new("sender")
+            .event("Ping", &[])
+            .process("node", Coord::new(0, 0, 0))
+            .transition(
+                "Ping",
+                vec![IrAction::SendEvent {
+                    event_type: "Ping".to_string(),
+                    target: IrCoordExpression::Literal(Coord::new(40, 0, 0)),
+                    fields: HashMap::new(),
+                }],
+            )
+            .build()
+            .unwrap();
+        assert!(BettiRdlBackend::new_with_defaults().plan(&sender).is_ok());
+        let err = strict(ProcessPlacement::SingleNode).plan(&sender).unwrap_err();
+        assert!(err.to_string().contains("sends Ping to"), "{err}");
+    }
+
+    #[test]
+    fn test_colliding_custom_placement_rejected() {
+        let backend = BettiRdlBackend::new(BettiConfig {
+            process_placement: ProcessPlacement::Custom(HashMap::from([
+                ("left".to_string(), Coord::new(1, 2, 3)),
+                ("right".to_string(), Coord::new(33, 2, 3)),
+            ])),
+            // Out-of-range coordinates only reach the collision check unvalidated
+            validate_coordinates: false,
+            ..BettiConfig::default()
+        });
+
+        let err = backend.plan(&create_test_program()).unwrap_err();
+        assert!(matches!(err, BackendError::CoordinateCollision { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Processes 'left' and 'right' are both placed at (1, 2, 3)"
+        );
+    }
+
+    #[test]
+    fn test_generated_code_injects_each_handled_event() {
+        let typed = grey_lang::compile(
+            r#"module M {
+                event Ping { n: Int }
+                event Pong { n: Int }
+                event Unused { n: Int }
+                process Pinger {
+                    count: Int,
+                    method handle_pong(event: Pong) { this.count = this.count + 1; }
+                }
+                process Ponger {
+                    count: Int,
+                    method handle_ping(event: Ping) { this.count = this.count + 1; }
+                }
+            }"#,
+        )
+        .unwrap();
+        let program = grey_ir::IrBuilder::new().build_program("pingpong", &typed).unwrap().clone();
+
+        let output = BettiRdlBackend::new_with_defaults().generate_code(&program).unwrap();
+        let code = &output.files[&PathBuf::from("pingpong_betti.rs")];
+
+        assert!(code.contains("// `Ping` (tag 1) handled by `Ponger`"));
+        assert!(code.contains("// `Pong` (tag 2) handled by `Pinger`"));
+        assert!(code.contains("// `Unused` has no handler; not injected"));
+        assert_eq!(code.matches("self.kernel.inject_event(").count(), 2);
+        assert!(code.contains("self.kernel.inject_event(*x, *y, *z, 2);"));
+        assert_eq!(code.matches("self.process_coords.get(\"p0\")").count(), 2);
+
+        let backend = BettiRdlBackend::new(BettiConfig {
+            process_placement: ProcessPlacement::Custom(HashMap::from([
+                ("Pinger".to_string(), Coord::new(1, 0, 0)),
+                ("Ponger".to_string(), Coord::new(2, 0, 0)),
+            ])),
+            ..BettiConfig::default()
+        });
+        let output = backend.generate_code(&program).unwrap();
+        let code = &output.files[&PathBuf::from("pingpong_betti.rs")];
+
+        assert!(code.contains(
+            "self.process_coords.get(\"Ponger\") {\n            self.kernel.inject_event(*x, *y, *z, 1);"
+        ));
+        assert!(code.contains(
+            "self.process_coords.get(\"Pinger\") {\n            self.kernel.inject_event(*x, *y, *z, 2);"
+        ));
+    }
+
+    #[test]
+    fn test_placement_file_resolved_during_codegen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("placement.json");
+        std::fs::write(
+            &path,
+            r#"{"hub": {"x": 1, "y": 2, "z": 3}, "edge": {"x": 4, "y": 0, "z": 0}}"#,
+        )
+        .unwrap();
+        let backend = BettiRdlBackend::new(BettiConfig {
+            process_placement: ProcessPlacement::FromFile(path.clone()),
+            ..BettiConfig::default()
+        });
+
+        let output = backend.generate_code(&create_test_program()).unwrap();
+        let ProcessPlacement::Custom(mapping) = &output.runtime_config.process_placement else {
+            panic!("placement file not resolved");
+        };
+        assert_eq!(mapping["hub"], Coord::new(1, 2, 3));
+        assert_eq!(mapping["edge"], Coord::new(4, 0, 0));
+        assert_eq!(
+            BettiRdlBackend::runtime_coords(&output),
+            vec![Coord::new(4, 0, 0), Coord::new(1, 2, 3)]
+        );
+
+        std::fs::write(&path, r#"{"hub": {"x": 1, "y": "two", "z": 3}}"#).unwrap();
+        let err = backend.generate_code(&create_test_program()).unwrap_err();
+        assert!(err.to_string().contains("Invalid placement file"), "{}", err);
+
+        let missing = BettiRdlBackend::new(BettiConfig {
+            process_placement: ProcessPlacement::FromFile(dir.path().join("missing.json")),
+            ..BettiConfig::default()
+        });
+        let err = missing.generate_code(&create_test_program()).unwrap_err();
+        assert!(err.to_string().contains("Cannot read placement file"), "{}", err);
+    }
+
+    #[test]
+    fn test_placement_report_lists_grid_processes() {
+        let mut program = create_test_program();
+        program.constants.insert("RUNTIME_PROCESSES".to_string(), grey_ir::IrValue::Integer(3));
+        let backend = BettiRdlBackend::new(BettiConfig {
+            process_placement: ProcessPlacement::GridLayout { spacing: 2, columns: None },
+            ..BettiConfig::default()
+        });
+        let output = backend.generate_code(&program).unwrap();
+
+        // Rows follow node id (x * 1024 + y * 32 + z), not spawn order
+        assert_eq!(
+            backend.placement_report(&output),
+            "process  coord            node_id\n\
+             p0       (0, 0, 0)        0\n\
+             p2       (0, 2, 0)        64\n\
+             p1       (2, 0, 0)        2048\n"
+        );
+    }
+
+    #[test]
+    fn test_ordering_seed_leaves_injections_unchanged() {
+        let coords: Vec<_> = (0..6).map(|x| Coord::new(x, 0, 0)).collect();
+        let with_ordering_seed = |ordering_seed| {
+            BettiRdlBackend::new(BettiConfig { ordering_seed, ..BettiConfig::default() })
+        };
+
+        let baseline = with_ordering_seed(None).initial_events(&coords);
+        assert_eq!(with_ordering_seed(Some(7)).initial_events(&coords), baseline);
+        assert_eq!(with_ordering_seed(Some(99)).initial_events(&coords), baseline);
+    }
+
+    #[test]
+    fn test_max_events_must_be_non_negative() {
+        let program = create_test_program();
+        let with_max_events = |max_events| {
+            BettiRdlBackend::new(BettiConfig { max_events, ..BettiConfig::default() })
+        };
+
+        let err = with_max_events(-5).plan(&program).unwrap_err();
+        assert!(err.to_string().contains("max_events must be non-negative"), "{err}");
+        assert_eq!(event_budget(0).unwrap(), i32::MAX);
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_zero_max_events_runs_to_quiescence() {
+        let backend = BettiRdlBackend::new(BettiConfig { max_events: 0, ..BettiConfig::default() });
+        let output = backend.generate_code(&create_test_program()).unwrap();
+
+        let telemetry = backend.execute(&output).unwrap();
+        assert!(telemetry.quiescent);
+    }
+
+    #[test]
+    fn test_plan_lists_generated_files() {
+        let backend = BettiRdlBackend::new_with_defaults();
+        let program = create_test_program();
+
+        let plan = backend.plan(&program).unwrap();
+        let output = backend.generate_code(&program).unwrap();
+
+        let mut planned = plan.files.clone();
+        planned.sort();
+        let mut generated: Vec<PathBuf> = output.files.keys().cloned().collect();
+        generated.sort();
+        assert_eq!(planned, generated);
+        assert_eq!(plan.metadata.runtime_process_count, output.metadata.runtime_process_count);
+        assert_eq!(plan.runtime_config, output.runtime_config);
+    }
+
+    #[test]
+    fn test_program_without_processes_rejected() {
+        let backend = BettiRdlBackend::new_with_defaults();
+        let mut program = create_test_program();
+        program.processes.clear();
+
+        let err = backend.generate_code(&program).unwrap_err();
+        assert!(err.to_string().contains("has no processes"));
+    }
+
+    #[test]
+    fn test_empty_custom_placement_rejected() {
+        let backend = BettiRdlBackend::new(BettiConfig {
+            process_placement: ProcessPlacement::Custom(HashMap::new()),
+            ..BettiConfig::default()
+        });
+
+        let err = backend.plan(&create_test_program()).unwrap_err();
+        assert_eq!(err.to_string(), "Validation failed: Custom placement has no processes");
+    }
+
+    #[test]
+    #[cfg(not(feature = "betti-runtime"))]
+    fn test_execute_without_runtime_feature_errors() {
+        let backend = BettiRdlBackend::new_with_defaults();
+        let output = backend.generate_code(&create_test_program()).unwrap();
+
+        let err = backend.execute(&output).unwrap_err();
+        assert!(err.to_string().contains("runtime feature disabled"));
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
     fn test_execution() {
         let backend = BettiRdlBackend::new_with_defaults();
         let program = create_test_program();
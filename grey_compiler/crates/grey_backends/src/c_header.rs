@@ -0,0 +1,236 @@
+//! C Header Backend for Grey Compiler
+//!
+//! Describes a program's events and process state as C declarations, so the C++
+//! reference can include the layout instead of restating it by hand. Event tags
+//! are the 1-based declaration indices the Betti backend injects with, event
+//! structs list payload fields in wire order, and process state structs are
+//! packed so each field sits at the offset given in its comment.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use grey_ir::{IrProgram, IrType, STRING_FIELD_CAPACITY};
+
+use crate::{
+    BackendError, CodeGenMetadata, CodeGenOutput, CodeGenerator, ConfigOption, ExecutionTelemetry,
+    RuntimeConfig,
+};
+use crate::utils::process_footprints;
+
+/// C header backend implementation
+#[derive(Debug, Default)]
+pub struct CHeaderBackend;
+
+impl CHeaderBackend {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Render the program's event tags, event payloads and process state layouts
+    pub fn render(&self, program: &IrProgram) -> String {
+        let prefix = c_identifier(&program.name);
+        let guard = format!("GREY_{}_H", prefix.to_uppercase());
+
+        let mut header = String::new();
+        header.push_str(&format!("/* Generated by greyc from `{}`; do not edit. */\n", program.name));
+        header.push_str(&format!("#ifndef {0}\n#define {0}\n\n", guard));
+        header.push_str("#include <stdint.h>\n\n");
+        // Shared by every generated header, so several can be included together
+        header.push_str("#ifndef GREY_HEADER_COMMON\n#define GREY_HEADER_COMMON\n");
+        header.push_str("#ifdef __cplusplus\n#define GREY_STATIC_ASSERT static_assert\n");
+        header.push_str("#else\n#define GREY_STATIC_ASSERT _Static_assert\n#endif\n");
+        header.push_str("typedef struct { int32_t x, y, z; } grey_coord_t;\n#endif\n\n");
+
+        header.push_str("/* Event tags: the 1-based declaration index of each event */\n");
+        header.push_str("typedef enum {\n");
+        for (index, event) in program.events.iter().enumerate() {
+            header.push_str(&format!(
+                "    {}_EVENT_{} = {},\n",
+                prefix.to_uppercase(),
+                c_identifier(&event.name).to_uppercase(),
+                index + 1
+            ));
+        }
+        if program.events.is_empty() {
+            // C does not allow empty enums; no event is ever tagged 0
+            header.push_str(&format!("    {}_EVENT_NONE = 0,\n", prefix.to_uppercase()));
+        }
+        header.push_str(&format!("}} {}_event_tag_t;\n", prefix));
+
+        for event in &program.events {
+            header.push_str(&format!("\n/* Event {}: payload fields in wire order */\n", event.name));
+            header.push_str("typedef struct {\n");
+            let fields = event.wire_field_order();
+            for name in &fields {
+                match &event.fields[*name] {
+                    IrType::Int | IrType::Bool => {
+                        header.push_str(&format!("    int32_t {};\n", c_identifier(name)))
+                    }
+                    IrType::Coord => {
+                        header.push_str(&format!("    grey_coord_t {};\n", c_identifier(name)))
+                    }
+                    IrType::String => header.push_str(&format!(
+                        "    /* {}: String has no wire form */\n",
+                        name
+                    )),
+                }
+            }
+            if fields.iter().all(|name| event.fields[*name] == IrType::String) {
+                // C does not allow empty structs
+                header.push_str("    uint8_t unused;\n");
+            }
+            header.push_str(&format!("}} {}_{}_event_t;\n", prefix, c_identifier(&event.name)));
+        }
+
+        for process in &program.processes {
+            let type_name = format!("{}_{}_state_t", prefix, c_identifier(&process.name));
+            header.push_str(&format!(
+                "\n/* Process {}: {} bytes of state per instance */\n",
+                process.name,
+                process.footprint_bytes()
+            ));
+            header.push_str("#pragma pack(push, 1)\n");
+            header.push_str("typedef struct {\n");
+
+            let mut fields: Vec<_> = process.fields.iter().collect();
+            fields.sort_by_key(|(name, _)| *name);
+            let mut offset = 0;
+            for (name, ty) in &fields {
+                let declaration = match ty {
+                    IrType::Int => format!("int64_t {}", c_identifier(name)),
+                    IrType::Bool => format!("uint8_t {}", c_identifier(name)),
+                    IrType::Coord => format!("grey_coord_t {}", c_identifier(name)),
+                    IrType::String => {
+                        format!("char {}[{}]", c_identifier(name), STRING_FIELD_CAPACITY)
+                    }
+                };
+                header.push_str(&format!("    {}; /* offset {} */\n", declaration, offset));
+                offset += ty.size_bytes();
+            }
+            if fields.is_empty() {
+                header.push_str("    uint8_t unused;\n");
+            }
+
+            header.push_str(&format!("}} {};\n", type_name));
+            header.push_str("#pragma pack(pop)\n");
+            if !fields.is_empty() {
+                header.push_str(&format!(
+                    "GREY_STATIC_ASSERT(sizeof({0}) == {1}, \"{0} layout\");\n",
+                    type_name, offset
+                ));
+            }
+        }
+
+        header.push_str(&format!("\n#endif /* {} */\n", guard));
+        header
+    }
+}
+
+/// `name` with every character that cannot appear in a C identifier replaced by `_`
+fn c_identifier(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if ident.chars().next().is_none_or(|c| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+impl CodeGenerator for CHeaderBackend {
+    fn generate_code(&self, program: &IrProgram) -> Result<CodeGenOutput, BackendError> {
+        let mut files = HashMap::new();
+        files.insert(
+            PathBuf::from(format!("{}.h", c_identifier(&program.name))),
+            self.render(program),
+        );
+
+        Ok(CodeGenOutput {
+            files,
+            runtime_config: RuntimeConfig::default(),
+            metadata: CodeGenMetadata {
+                source_name: program.name.clone(),
+                process_count: program.processes.len(),
+                runtime_process_count: program.processes.len(),
+                event_count: program.events.len(),
+                expected_execution_time: None,
+                process_footprints: process_footprints(program),
+            },
+        })
+    }
+
+    fn execute(&self, _output: &CodeGenOutput) -> Result<ExecutionTelemetry, BackendError> {
+        Err(BackendError::RuntimeError(
+            "C header backend output is declarations only and cannot be executed".to_string(),
+        ))
+    }
+
+    fn config_options(&self) -> HashMap<String, ConfigOption> {
+        HashMap::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grey_ir::{Coord, IrProgramBuilder};
+
+    fn create_test_program() -> IrProgram {
+        IrProgramBuilder::new("sir")
+            .event("Tick", &[])
+            .event("Infect", &[("strain", IrType::Int), ("origin", IrType::Coord)])
+            .process("Person", Coord::new(0, 0, 0))
+            .field("infected", IrType::Bool)
+            .field("contacts", IrType::Int)
+            .field("home", IrType::Coord)
+            .transition("Tick", vec![])
+            .transition("Infect", vec![])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_header_declares_event_structs_and_tags() {
+        let header = CHeaderBackend::new().render(&create_test_program());
+
+        assert!(header.contains("#ifndef GREY_SIR_H"));
+        assert!(header.contains("    SIR_EVENT_TICK = 1,\n    SIR_EVENT_INFECT = 2,\n"));
+        assert!(header.contains("} sir_Tick_event_t;"));
+        assert!(header.contains(
+            "typedef struct {\n    grey_coord_t origin;\n    int32_t strain;\n} sir_Infect_event_t;"
+        ));
+    }
+
+    #[test]
+    fn test_header_without_events_has_a_placeholder_tag() {
+        let program = IrProgramBuilder::new("idle")
+            .process("Sleeper", Coord::new(0, 0, 0))
+            .field("naps", IrType::Int)
+            .build()
+            .unwrap();
+        let header = CHeaderBackend::new().render(&program);
+
+        assert!(header.contains("typedef enum {\n    IDLE_EVENT_NONE = 0,\n} idle_event_tag_t;"));
+    }
+
+    #[test]
+    fn test_header_gives_state_field_offsets() {
+        let header = CHeaderBackend::new().render(&create_test_program());
+
+        assert!(header.contains("/* Process Person: 21 bytes of state per instance */"));
+        assert!(header.contains("    int64_t contacts; /* offset 0 */\n"));
+        assert!(header.contains("    grey_coord_t home; /* offset 8 */\n"));
+        assert!(header.contains("    uint8_t infected; /* offset 20 */\n"));
+        assert!(header.contains("GREY_STATIC_ASSERT(sizeof(sir_Person_state_t) == 21"));
+    }
+
+    #[test]
+    fn test_header_output_is_not_executable() {
+        let backend = CHeaderBackend::new();
+        let output = backend.generate_code(&create_test_program()).unwrap();
+
+        assert!(output.files.contains_key(&PathBuf::from("sir.h")));
+        assert!(backend.execute(&output).is_err());
+    }
+}
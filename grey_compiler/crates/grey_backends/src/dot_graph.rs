@@ -0,0 +1,181 @@
+//! Graphviz DOT Backend for Grey Compiler
+//!
+//! Renders the topology of an IR program as a DOT graph: processes and events are
+//! nodes, handler transitions are `event -> process` edges and `SendEvent` actions
+//! are `process -> event` edges. The output is meant for inspection, not execution.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use grey_ir::{IrAction, IrProgram};
+
+use crate::{
+    BackendError, CodeGenMetadata, CodeGenOutput, CodeGenerator, ConfigOption, ExecutionTelemetry,
+    RuntimeConfig,
+};
+use crate::utils::process_footprints;
+
+/// DOT graph backend implementation
+#[derive(Debug, Default)]
+pub struct DotGraphBackend;
+
+impl DotGraphBackend {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Render the program topology as DOT source
+    pub fn render(&self, program: &IrProgram) -> String {
+        let mut dot = String::new();
+        dot.push_str(&format!("digraph \"{}\" {{\n", program.name));
+        dot.push_str("    rankdir=LR;\n");
+
+        let mut events: BTreeSet<&str> = program.events.iter().map(|e| e.name.as_str()).collect();
+        let mut edges = BTreeSet::new();
+
+        for process in &program.processes {
+            for transition in &process.transitions {
+                events.insert(&transition.event_type);
+                edges.insert((
+                    format!("event_{}", transition.event_type),
+                    format!("process_{}", process.name),
+                    "handles",
+                ));
+
+                for action in transition.flattened_actions() {
+                    if let IrAction::SendEvent { event_type, .. } = action {
+                        events.insert(event_type);
+                        edges.insert((
+                            format!("process_{}", process.name),
+                            format!("event_{}", event_type),
+                            "sends",
+                        ));
+                    }
+                }
+            }
+        }
+
+        for process in &program.processes {
+            dot.push_str(&format!(
+                "    \"process_{0}\" [shape=box, label=\"{0}\\n({1}, {2}, {3})\"];\n",
+                process.name, process.coord.x, process.coord.y, process.coord.z
+            ));
+        }
+
+        for event in events {
+            dot.push_str(&format!(
+                "    \"event_{0}\" [shape=ellipse, label=\"{0}\"];\n",
+                event
+            ));
+        }
+
+        for (from, to, label) in edges {
+            dot.push_str(&format!("    \"{}\" -> \"{}\" [label=\"{}\"];\n", from, to, label));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl CodeGenerator for DotGraphBackend {
+    fn generate_code(&self, program: &IrProgram) -> Result<CodeGenOutput, BackendError> {
+        let mut files = HashMap::new();
+        files.insert(PathBuf::from(format!("{}.dot", program.name)), self.render(program));
+
+        Ok(CodeGenOutput {
+            files,
+            runtime_config: RuntimeConfig::default(),
+            metadata: CodeGenMetadata {
+                source_name: program.name.clone(),
+                process_count: program.processes.len(),
+                runtime_process_count: program.processes.len(),
+                event_count: program.events.len(),
+                expected_execution_time: None,
+                process_footprints: process_footprints(program),
+            },
+        })
+    }
+
+    fn execute(&self, _output: &CodeGenOutput) -> Result<ExecutionTelemetry, BackendError> {
+        Err(BackendError::RuntimeError(
+            "DOT backend output is a graph and cannot be executed".to_string(),
+        ))
+    }
+
+    fn config_options(&self) -> HashMap<String, ConfigOption> {
+        HashMap::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grey_ir::{Coord, IrEvent, IrProcess, IrResourceBounds, IrState, IrTransition};
+
+    fn create_test_program() -> IrProgram {
+        let process = |name: &str, x: i32, transitions: Vec<IrTransition>| IrProcess {
+            name: name.to_string(),
+            coord: Coord::new(x, 0, 0),
+            fields: HashMap::new(),
+            initial_state: IrState { values: HashMap::new() },
+            transitions,
+        };
+
+        IrProgram {
+            name: "topology".to_string(),
+            processes: vec![
+                process(
+                    "Producer",
+                    0,
+                    vec![IrTransition {
+                        event_type: "Tick".to_string(),
+                        condition: None,
+                        priority: 0,
+                        actions: vec![IrAction::SendEvent {
+                            event_type: "Item".to_string(),
+                            target: Coord::new(1, 0, 0).into(),
+                            fields: HashMap::new(),
+                        }],
+                    }],
+                ),
+                process(
+                    "Consumer",
+                    1,
+                    vec![IrTransition {
+                        event_type: "Item".to_string(),
+                        condition: None,
+                        priority: 0,
+                        actions: vec![],
+                    }],
+                ),
+            ],
+            events: vec![
+                IrEvent { name: "Tick".to_string(), fields: HashMap::new() },
+                IrEvent { name: "Item".to_string(), fields: HashMap::new() },
+            ],
+            constants: HashMap::new(),
+            resources: IrResourceBounds::default(),
+        }
+    }
+
+    #[test]
+    fn test_dot_contains_processes_and_edges() {
+        let dot = DotGraphBackend::new().render(&create_test_program());
+
+        assert!(dot.starts_with("digraph \"topology\""));
+        assert!(dot.contains("\"process_Producer\""));
+        assert!(dot.contains("\"process_Consumer\""));
+        assert!(dot.contains("\"process_Producer\" -> \"event_Item\" [label=\"sends\"]"));
+        assert!(dot.contains("\"event_Item\" -> \"process_Consumer\" [label=\"handles\"]"));
+    }
+
+    #[test]
+    fn test_dot_output_is_not_executable() {
+        let backend = DotGraphBackend::new();
+        let output = backend.generate_code(&create_test_program()).unwrap();
+
+        assert!(output.files.contains_key(&PathBuf::from("topology.dot")));
+        assert!(backend.execute(&output).is_err());
+    }
+}
@@ -0,0 +1,549 @@
+//! Pure-Rust IR Interpreter
+//!
+//! Executes an `IrProgram` directly, without the Betti RDL kernel. Each `IrProcess`
+//! becomes one instance at its coordinate; events are delivered to the instance at
+//! the target coordinate and run through the matching transitions. Events produced
+//! by `SendEvent` actions are enqueued one tick later and processed in the order
+//! defined by `EventOrdering`, up to `max_events`.
+
+use std::collections::HashMap;
+
+use grey_ir::{
+    Coord, EventQueue, IrAction, IrArithmeticOp, IrComparisonOp, IrCoordExpression, IrExpression,
+    IrProgram, IrState, IrValue,
+};
+
+use crate::{BackendError, EventOrdering};
+
+/// An event waiting to be (or already) delivered by the interpreter
+#[derive(Debug, Clone)]
+pub struct InterpreterEvent {
+    pub time: u64,
+    pub target: Coord,
+    pub event_type: String,
+    pub fields: HashMap<String, IrValue>,
+}
+
+/// Outcome of an interpreter run
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterpreterSummary {
+    pub events_processed: u64,
+    pub current_time: u64,
+
+    /// True if the run stopped at `max_events` with events still queued.
+    pub truncated: bool,
+}
+
+struct ProcessInstance {
+    name: String,
+    state: IrState,
+}
+
+/// Interpreter over a single IR program
+pub struct Interpreter<'a> {
+    program: &'a IrProgram,
+    max_events: u64,
+    instances: HashMap<Coord, ProcessInstance>,
+    queue: EventQueue<InterpreterEvent>,
+    current_time: u64,
+    events_processed: u64,
+    log: Vec<InterpreterEvent>,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(program: &'a IrProgram, ordering: EventOrdering, max_events: u64) -> Self {
+        let instances = program
+            .processes
+            .iter()
+            .map(|p| {
+                (
+                    p.coord.clone(),
+                    ProcessInstance {
+                        name: p.name.clone(),
+                        state: p.initial_state.clone(),
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            program,
+            max_events,
+            instances,
+            queue: EventQueue::new(ordering),
+            current_time: 0,
+            events_processed: 0,
+            log: Vec::new(),
+        }
+    }
+
+    /// Break same-time ties between targets by a seeded permutation instead of by
+    /// coordinate (`Deterministic` ordering only). Call before injecting events.
+    pub fn with_ordering_seed(mut self, seed: u64) -> Self {
+        self.queue = self.queue.with_tie_break_seed(seed);
+        self
+    }
+
+    /// Queue an event for delivery at the current time
+    pub fn inject(&mut self, event_type: &str, target: Coord, fields: HashMap<String, IrValue>) {
+        self.enqueue(InterpreterEvent {
+            time: self.current_time,
+            target,
+            event_type: event_type.to_string(),
+            fields,
+        });
+    }
+
+    /// Process queued events until the queue drains or `max_events` is reached
+    pub fn run(&mut self) -> Result<InterpreterSummary, BackendError> {
+        while self.events_processed < self.max_events {
+            let Some(event) = self.queue.pop() else {
+                break;
+            };
+            self.dispatch(event)?;
+        }
+
+        Ok(InterpreterSummary {
+            events_processed: self.events_processed,
+            current_time: self.current_time,
+            truncated: !self.queue.is_empty(),
+        })
+    }
+
+    /// Events delivered so far, in processing order
+    pub fn processed_events(&self) -> &[InterpreterEvent] {
+        &self.log
+    }
+
+    /// Current state of the process instance at `coord`
+    pub fn process_state(&self, coord: &Coord) -> Option<&IrState> {
+        self.instances.get(coord).map(|i| &i.state)
+    }
+
+    fn enqueue(&mut self, event: InterpreterEvent) {
+        self.queue.push(event.time, event.target.to_node_id(), event);
+    }
+
+    fn dispatch(&mut self, event: InterpreterEvent) -> Result<(), BackendError> {
+        self.current_time = event.time;
+        self.events_processed += 1;
+        self.log.push(event.clone());
+
+        let Some(instance) = self.instances.get(&event.target) else {
+            return Ok(());
+        };
+        let Some(process) = self.program.processes.iter().find(|p| p.name == instance.name) else {
+            return Ok(());
+        };
+
+        let mut state = instance.state.clone();
+        let mut outgoing = Vec::new();
+
+        let mut transitions: Vec<_> = process
+            .transitions
+            .iter()
+            .filter(|t| t.event_type == event.event_type)
+            .collect();
+        transitions.sort_by_key(|t| t.priority);
+
+        for transition in transitions {
+            if let Some(condition) = &transition.condition {
+                if !evaluate(condition, &state, &event)?.is_truthy() {
+                    continue;
+                }
+            }
+
+            apply_actions(&transition.actions, &mut state, &event, &mut self.instances, &mut outgoing)?;
+        }
+
+        if let Some(instance) = self.instances.get_mut(&event.target) {
+            instance.state = state;
+        }
+
+        for next in outgoing {
+            self.enqueue(next);
+        }
+
+        Ok(())
+    }
+}
+
+/// Apply transition actions in order, expanding `Repeat` bodies and taking the
+/// first `Branch` arm whose guard holds.
+fn apply_actions(
+    actions: &[IrAction],
+    state: &mut IrState,
+    event: &InterpreterEvent,
+    instances: &mut HashMap<Coord, ProcessInstance>,
+    outgoing: &mut Vec<InterpreterEvent>,
+) -> Result<(), BackendError> {
+    for action in actions {
+        match action {
+            IrAction::UpdateField { field, value } => {
+                let value = evaluate(value, state, event)?;
+                state.values.insert(field.clone(), value);
+            }
+            IrAction::SendEvent { event_type, target, fields } => {
+                let mut values = HashMap::new();
+                for (name, expr) in fields {
+                    values.insert(name.clone(), evaluate(expr, state, event)?);
+                }
+                outgoing.push(InterpreterEvent {
+                    time: event.time + 1,
+                    target: evaluate_coord(target, state, event)?,
+                    event_type: event_type.clone(),
+                    fields: values,
+                });
+            }
+            IrAction::SpawnProcess { process_type, coord, initial_state } => {
+                instances.entry(coord.clone()).or_insert_with(|| ProcessInstance {
+                    name: process_type.clone(),
+                    state: initial_state.clone(),
+                });
+            }
+            IrAction::Repeat { count, body } => {
+                for _ in 0..*count {
+                    apply_actions(body, state, event, instances, outgoing)?;
+                }
+            }
+            IrAction::Branch { arms } => {
+                for arm in arms {
+                    if evaluate(&arm.guard, state, event)?.is_truthy() {
+                        apply_actions(&arm.actions, state, event, instances, outgoing)?;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Evaluate an IR expression against a process state, falling back to the
+/// triggering event's fields for names the state does not define.
+fn evaluate(
+    expr: &IrExpression,
+    state: &IrState,
+    event: &InterpreterEvent,
+) -> Result<IrValue, BackendError> {
+    match expr {
+        IrExpression::Constant(value) => Ok(value.clone()),
+        IrExpression::FieldAccess(name) => state
+            .values
+            .get(name)
+            .or_else(|| event.fields.get(name))
+            .cloned()
+            .ok_or_else(|| BackendError::RuntimeError(format!("Unknown field: {}", name))),
+        IrExpression::Arithmetic { op, left, right } => {
+            let (l, r) = (evaluate(left, state, event)?, evaluate(right, state, event)?);
+            let (Some(l), Some(r)) = (as_integer(&l), as_integer(&r)) else {
+                return Err(BackendError::RuntimeError(format!(
+                    "Arithmetic on non-integer values: {:?} {:?} {:?}",
+                    l, op, r
+                )));
+            };
+            let result = match op {
+                IrArithmeticOp::Add => l.wrapping_add(r),
+                IrArithmeticOp::Subtract => l.wrapping_sub(r),
+                IrArithmeticOp::Multiply => l.wrapping_mul(r),
+                IrArithmeticOp::Divide | IrArithmeticOp::Modulo if r == 0 => {
+                    return Err(BackendError::RuntimeError("Division by zero".to_string()));
+                }
+                IrArithmeticOp::Divide => l.wrapping_div(r),
+                IrArithmeticOp::Modulo => l.wrapping_rem(r),
+            };
+            Ok(IrValue::Integer(result))
+        }
+        IrExpression::Comparison { op, left, right } => {
+            let (l, r) = (evaluate(left, state, event)?, evaluate(right, state, event)?);
+            let result = match (&l, &r) {
+                (IrValue::String(l), IrValue::String(r)) => compare(op, l, r),
+                _ => match (as_integer(&l), as_integer(&r)) {
+                    (Some(l), Some(r)) => compare(op, &l, &r),
+                    _ => {
+                        return Err(BackendError::RuntimeError(format!(
+                            "Cannot compare {:?} with {:?}",
+                            l, r
+                        )))
+                    }
+                },
+            };
+            Ok(IrValue::Boolean(result))
+        }
+        IrExpression::Not(inner) => Ok(IrValue::Boolean(!evaluate(inner, state, event)?.is_truthy())),
+    }
+}
+
+/// Resolve a send target; `event.target` is the sending process's coordinate.
+fn evaluate_coord(
+    target: &IrCoordExpression,
+    state: &IrState,
+    event: &InterpreterEvent,
+) -> Result<Coord, BackendError> {
+    match target {
+        IrCoordExpression::Literal(coord) => Ok(coord.clone()),
+        IrCoordExpression::SelfCoord => Ok(event.target.clone()),
+        IrCoordExpression::Value(expr) => match evaluate(expr, state, event)? {
+            IrValue::Coord(coord) => Ok(coord),
+            other => Err(BackendError::RuntimeError(format!(
+                "Send target is not a coordinate: {:?}",
+                other
+            ))),
+        },
+        IrCoordExpression::Offset { base, dx, dy, dz } => {
+            let base = evaluate_coord(base, state, event)?;
+            let mut offsets = [0i32; 3];
+            for (offset, expr) in offsets.iter_mut().zip([dx, dy, dz]) {
+                let value = evaluate(expr, state, event)?;
+                *offset = as_integer(&value).ok_or_else(|| {
+                    BackendError::RuntimeError(format!("Non-integer coordinate offset: {:?}", value))
+                })? as i32;
+            }
+            Ok(Coord::new(
+                base.x.wrapping_add(offsets[0]),
+                base.y.wrapping_add(offsets[1]),
+                base.z.wrapping_add(offsets[2]),
+            )
+            .wrapped())
+        }
+    }
+}
+
+/// Integer view of a value, with booleans as 0/1 like the kernel sees them
+fn as_integer(value: &IrValue) -> Option<i64> {
+    match value {
+        IrValue::Integer(i) => Some(*i),
+        IrValue::Boolean(b) => Some(i64::from(*b)),
+        IrValue::String(_) | IrValue::Coord(_) => None,
+    }
+}
+
+fn compare<T: PartialOrd>(op: &IrComparisonOp, l: &T, r: &T) -> bool {
+    match op {
+        IrComparisonOp::Equal => l == r,
+        IrComparisonOp::NotEqual => l != r,
+        IrComparisonOp::LessThan => l < r,
+        IrComparisonOp::LessThanOrEqual => l <= r,
+        IrComparisonOp::GreaterThan => l > r,
+        IrComparisonOp::GreaterThanOrEqual => l >= r,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grey_ir::{IrEvent, IrProcess, IrResourceBounds, IrTransition, IrType};
+
+    fn counter_state() -> IrState {
+        let mut values = HashMap::new();
+        values.insert("count".to_string(), IrValue::Integer(0));
+        IrState { values }
+    }
+
+    fn increment() -> IrAction {
+        IrAction::UpdateField {
+            field: "count".to_string(),
+            value: IrExpression::Arithmetic {
+                op: IrArithmeticOp::Add,
+                left: Box::new(IrExpression::FieldAccess("count".to_string())),
+                right: Box::new(IrExpression::Constant(IrValue::Integer(1))),
+            },
+        }
+    }
+
+    /// A pings B, B pongs A, forever.
+    fn ping_pong_program() -> IrProgram {
+        let node = |name: &str, x: i32, handles: &str, sends: &str, to: i32| IrProcess {
+            name: name.to_string(),
+            coord: Coord::new(x, 0, 0),
+            fields: [("count".to_string(), IrType::Int)].into_iter().collect(),
+            initial_state: counter_state(),
+            transitions: vec![IrTransition {
+                event_type: handles.to_string(),
+                condition: None,
+                priority: 0,
+                actions: vec![
+                    increment(),
+                    IrAction::SendEvent {
+                        event_type: sends.to_string(),
+                        target: Coord::new(to, 0, 0).into(),
+                        fields: HashMap::new(),
+                    },
+                ],
+            }],
+        };
+
+        IrProgram {
+            name: "ping_pong".to_string(),
+            processes: vec![node("A", 0, "Ping", "Pong", 1), node("B", 1, "Pong", "Ping", 0)],
+            events: vec![
+                IrEvent { name: "Ping".to_string(), fields: HashMap::new() },
+                IrEvent { name: "Pong".to_string(), fields: HashMap::new() },
+            ],
+            constants: HashMap::new(),
+            resources: IrResourceBounds::default(),
+        }
+    }
+
+    #[test]
+    fn test_handler_events_processed_in_order() {
+        let program = ping_pong_program();
+        let mut interpreter = Interpreter::new(&program, EventOrdering::Deterministic, 2);
+        interpreter.inject("Ping", Coord::new(0, 0, 0), HashMap::new());
+
+        let summary = interpreter.run().unwrap();
+
+        let order: Vec<_> = interpreter
+            .processed_events()
+            .iter()
+            .map(|e| (e.time, e.event_type.as_str()))
+            .collect();
+        assert_eq!(order, vec![(0, "Ping"), (1, "Pong")]);
+        assert_eq!(summary.current_time, 1);
+        assert!(matches!(
+            interpreter.process_state(&Coord::new(1, 0, 0)).unwrap().values["count"],
+            IrValue::Integer(1)
+        ));
+    }
+
+    #[test]
+    fn test_max_events_caps_cascade() {
+        let program = ping_pong_program();
+        let mut interpreter = Interpreter::new(&program, EventOrdering::Fifo, 50);
+        interpreter.inject("Ping", Coord::new(0, 0, 0), HashMap::new());
+
+        let summary = interpreter.run().unwrap();
+
+        assert_eq!(summary.events_processed, 50);
+        assert!(summary.truncated);
+    }
+
+    #[test]
+    fn test_deterministic_ordering_breaks_ties_by_coord() {
+        let program = ping_pong_program();
+        let mut interpreter = Interpreter::new(&program, EventOrdering::Deterministic, 2);
+        interpreter.inject("Pong", Coord::new(1, 0, 0), HashMap::new());
+        interpreter.inject("Ping", Coord::new(0, 0, 0), HashMap::new());
+        interpreter.run().unwrap();
+
+        let targets: Vec<_> = interpreter.processed_events().iter().map(|e| e.target.x).collect();
+        assert_eq!(targets, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_comparison_encodes_as_kernel_bool_and_gates_actions() {
+        let below_one = IrExpression::Comparison {
+            op: IrComparisonOp::LessThan,
+            left: Box::new(IrExpression::FieldAccess("count".to_string())),
+            right: Box::new(IrExpression::Constant(IrValue::Integer(1))),
+        };
+
+        let mut program = ping_pong_program();
+        program.processes[0].transitions = vec![IrTransition {
+            event_type: "Ping".to_string(),
+            condition: Some(below_one.clone()),
+            priority: 0,
+            actions: vec![
+                increment(),
+                IrAction::UpdateField {
+                    field: "below_one".to_string(),
+                    value: below_one.clone(),
+                },
+                IrAction::UpdateField {
+                    field: "at_least_one".to_string(),
+                    value: IrExpression::Not(Box::new(below_one)),
+                },
+            ],
+        }];
+
+        let mut interpreter = Interpreter::new(&program, EventOrdering::Deterministic, 10);
+        interpreter.inject("Ping", Coord::new(0, 0, 0), HashMap::new());
+        interpreter.inject("Ping", Coord::new(0, 0, 0), HashMap::new());
+        interpreter.run().unwrap();
+
+        let state = &interpreter.process_state(&Coord::new(0, 0, 0)).unwrap().values;
+        assert_eq!(state["count"], IrValue::Integer(1));
+        assert_eq!(state["below_one"].to_kernel_word(), Some(0));
+        assert_eq!(state["at_least_one"].to_kernel_word(), Some(1));
+    }
+
+    #[test]
+    fn test_negated_source_guard_blocks_action_when_field_is_true() {
+        let count_after_tick = |infected: bool| {
+            let source = format!(
+                "module M {{
+                    event Tick {{}}
+                    process Person {{
+                        infected: Bool,
+                        count: Int,
+                        method init() {{ this.infected = {}; }}
+                        method handle_tick(event: Tick) {{
+                            if (!this.infected) {{ this.count = this.count + 1; }}
+                        }}
+                    }}
+                }}",
+                infected
+            );
+            let typed = grey_lang::compile(&source).unwrap();
+            let program = grey_ir::IrBuilder::new().build_program("guard", &typed).unwrap().clone();
+
+            let mut interpreter = Interpreter::new(&program, EventOrdering::Deterministic, 10);
+            interpreter.inject("Tick", program.processes[0].coord.clone(), HashMap::new());
+            interpreter.run().unwrap();
+            interpreter.process_state(&program.processes[0].coord).unwrap().values["count"].clone()
+        };
+
+        assert_eq!(count_after_tick(true), IrValue::Integer(0));
+        assert_eq!(count_after_tick(false), IrValue::Integer(1));
+    }
+
+    #[test]
+    fn test_send_to_offset_from_self_reaches_neighbor() {
+        let mut program = ping_pong_program();
+        let IrAction::SendEvent { target, .. } = &mut program.processes[0].transitions[0].actions[1] else {
+            panic!("expected a send");
+        };
+        *target = IrCoordExpression::Offset {
+            base: Box::new(IrCoordExpression::SelfCoord),
+            dx: IrExpression::Constant(IrValue::Integer(1)),
+            dy: IrExpression::Constant(IrValue::Integer(0)),
+            dz: IrExpression::Constant(IrValue::Integer(0)),
+        };
+
+        let mut interpreter = Interpreter::new(&program, EventOrdering::Deterministic, 2);
+        interpreter.inject("Ping", Coord::new(0, 0, 0), HashMap::new());
+        interpreter.run().unwrap();
+
+        let delivered: Vec<_> = interpreter
+            .processed_events()
+            .iter()
+            .map(|e| (e.event_type.as_str(), e.target.clone()))
+            .collect();
+        assert_eq!(delivered, vec![("Ping", Coord::new(0, 0, 0)), ("Pong", Coord::new(1, 0, 0))]);
+        assert_eq!(
+            interpreter.process_state(&Coord::new(1, 0, 0)).unwrap().values["count"],
+            IrValue::Integer(1)
+        );
+    }
+
+    #[test]
+    fn test_ordering_seed_reorders_ties_without_changing_events() {
+        let program = ping_pong_program();
+        let delivered = |seed: Option<u64>| {
+            let interpreter = Interpreter::new(&program, EventOrdering::Deterministic, 2);
+            let mut interpreter = match seed {
+                Some(seed) => interpreter.with_ordering_seed(seed),
+                None => interpreter,
+            };
+            interpreter.inject("Pong", Coord::new(1, 0, 0), HashMap::new());
+            interpreter.inject("Ping", Coord::new(0, 0, 0), HashMap::new());
+            interpreter.run().unwrap();
+            interpreter.processed_events().iter().map(|e| e.target.x).collect::<Vec<_>>()
+        };
+
+        assert_eq!(delivered(None), vec![0, 1]);
+        let orders: Vec<_> = (0..8).map(|seed| delivered(Some(seed))).collect();
+        assert!(orders.contains(&vec![0, 1]) && orders.contains(&vec![1, 0]), "{:?}", orders);
+        assert_eq!(delivered(Some(3)), delivered(Some(3)));
+    }
+}
@@ -4,9 +4,11 @@
 //! Each backend implements the CodeGenerator trait to convert IR to executable code.
 
 use anyhow::Result;
-use grey_ir::{IrProgram, IrError};
+use grey_ir::{Coord, IrProgram, IrError};
+pub use grey_ir::EventOrdering;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Output artifacts from code generation
 #[derive(Debug)]
@@ -21,8 +23,21 @@ pub struct CodeGenOutput {
     pub metadata: CodeGenMetadata,
 }
 
+/// What code generation would produce, without generating any file contents
+#[derive(Debug)]
+pub struct CodeGenPlan {
+    /// Paths of the files code generation would produce
+    pub files: Vec<PathBuf>,
+
+    /// Runtime execution configuration
+    pub runtime_config: RuntimeConfig,
+
+    /// Metadata for validation
+    pub metadata: CodeGenMetadata,
+}
+
 /// Runtime execution configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RuntimeConfig {
     /// Maximum events to process
     pub max_events: i32,
@@ -35,26 +50,51 @@ pub struct RuntimeConfig {
 }
 
 /// How to place processes in coordinate space
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ProcessPlacement {
     /// Single node at origin
     SingleNode,
     
-    /// Grid layout based on process count
-    GridLayout { spacing: i32 },
+    /// Grid layout based on process count, filled row-major
+    GridLayout {
+        spacing: i32,
+        /// Row width; `None` picks the smallest square grid that fits every process
+        #[serde(default)]
+        columns: Option<i32>,
+    },
     
     /// Custom coordinate mapping
     Custom(HashMap<String, grey_ir::Coord>),
+
+    /// A `Custom` mapping read from a JSON file of `{"name": {"x": 0, "y": 0, "z": 0}}`
+    /// entries when code is generated
+    FromFile(PathBuf),
 }
 
-/// Event ordering guarantees
-#[derive(Debug, Clone)]
-pub enum EventOrdering {
-    /// FIFO ordering within same timestamp
-    Fifo,
-    
-    /// Deterministic tie-breaking by coordinates
-    Deterministic,
+impl ProcessPlacement {
+    /// This placement with a `FromFile` mapping read from disk; other variants are
+    /// returned unchanged.
+    pub fn resolve(&self) -> Result<ProcessPlacement, BackendError> {
+        let ProcessPlacement::FromFile(path) = self else {
+            return Ok(self.clone());
+        };
+
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            BackendError::ValidationError(format!(
+                "Cannot read placement file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let mapping = serde_json::from_str(&text).map_err(|e| {
+            BackendError::ValidationError(format!(
+                "Invalid placement file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(ProcessPlacement::Custom(mapping))
+    }
 }
 
 /// Metadata for validation and debugging
@@ -70,6 +110,9 @@ pub struct CodeGenMetadata {
 
     pub event_count: usize,
     pub expected_execution_time: Option<u64>,
+
+    /// Static field storage per instance of each process type, in bytes.
+    pub process_footprints: HashMap<String, usize>,
 }
 
 /// Backend-specific error types
@@ -86,6 +129,18 @@ pub enum BackendError {
     
     #[error("Validation failed: {0}")]
     ValidationError(String),
+
+    #[error(
+        "Processes '{first}' and '{second}' are both placed at ({}, {}, {})",
+        .coord.x, .coord.y, .coord.z
+    )]
+    CoordinateCollision { first: String, second: String, coord: Coord },
+
+    #[error(
+        "Process '{process}' has invalid coordinate ({}, {}, {}) (max component {max})",
+        .coord.x, .coord.y, .coord.z
+    )]
+    CoordinateOutOfBounds { process: String, coord: Coord, max: i32 },
 }
 
 /// Trait for all backend code generators
@@ -101,13 +156,42 @@ pub trait CodeGenerator {
 }
 
 /// Telemetry from runtime execution
+///
+/// Equality and hashing cover the deterministic fields only: `execution_time_ns`
+/// is wall-clock time and is ignored, so two runs of the same workload compare equal.
 #[derive(Debug)]
 pub struct ExecutionTelemetry {
     pub events_processed: u64,
     pub current_time: u64,
     pub execution_time_ns: u64,
     pub memory_usage_kb: Option<u64>,
-    pub process_states: HashMap<usize, i32>,
+    /// Final state of each process, in ascending pid order
+    pub process_states: BTreeMap<usize, i32>,
+    /// True when the run ended with no events pending, i.e. the workload drained
+    /// rather than being cut off by `max_events`.
+    pub quiescent: bool,
+}
+
+impl PartialEq for ExecutionTelemetry {
+    fn eq(&self, other: &Self) -> bool {
+        self.events_processed == other.events_processed
+            && self.current_time == other.current_time
+            && self.memory_usage_kb == other.memory_usage_kb
+            && self.process_states == other.process_states
+            && self.quiescent == other.quiescent
+    }
+}
+
+impl Eq for ExecutionTelemetry {}
+
+impl std::hash::Hash for ExecutionTelemetry {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.events_processed.hash(state);
+        self.current_time.hash(state);
+        self.memory_usage_kb.hash(state);
+        self.process_states.hash(state);
+        self.quiescent.hash(state);
+    }
 }
 
 /// Configuration option for backends
@@ -129,24 +213,59 @@ impl Default for RuntimeConfig {
     }
 }
 
+/// Names of the backends that can be constructed with `create_backend`
+pub fn available_backends() -> &'static [&'static str] {
+    &["betti_rdl", "dot", "c_header"]
+}
+
+/// Construct a registered backend with its default configuration
+pub fn create_backend(name: &str) -> Option<Box<dyn CodeGenerator>> {
+    match name {
+        "betti_rdl" => Some(Box::new(betti_rdl::BettiRdlBackend::new_with_defaults())),
+        "dot" => Some(Box::new(dot_graph::DotGraphBackend::new())),
+        "c_header" => Some(Box::new(c_header::CHeaderBackend::new())),
+        _ => None,
+    }
+}
+
 /// Utility functions for backend implementations
 pub mod utils {
     use grey_ir::{IrProgram, IrProcess, IrEvent};
+    use sha2::{Digest, Sha256};
     use std::collections::HashMap;
-    use crate::BackendError;
+    use crate::{BackendError, CodeGenOutput};
     
     /// Validate IR program for backend compatibility
     pub fn validate_program(program: &IrProgram) -> Result<(), BackendError> {
+        if program.processes.is_empty() {
+            return Err(BackendError::ValidationError(format!(
+                "Program {} has no processes",
+                program.name
+            )));
+        }
+
         // Check coordinate bounds
+        let max_coordinate = program.resources.max_coordinate_value;
         for process in &program.processes {
-            if !process.coord.is_valid() {
-                return Err(BackendError::ValidationError(format!(
-                    "Process {} has invalid coordinate: {:?}",
-                    process.name, process.coord
-                )));
+            if !process.coord.is_valid_within(max_coordinate) {
+                return Err(BackendError::CoordinateOutOfBounds {
+                    process: process.name.clone(),
+                    coord: process.coord.clone(),
+                    max: max_coordinate,
+                });
             }
         }
         
+        // Evaluation and codegen recurse over expressions
+        let depth = program.complexity().max_expression_depth;
+        if depth > grey_ir::MAX_EXPRESSION_DEPTH {
+            return Err(BackendError::ValidationError(format!(
+                "Expression depth {} exceeds {}",
+                depth,
+                grey_ir::MAX_EXPRESSION_DEPTH
+            )));
+        }
+
         // Check resource bounds
         if program.processes.len() > program.resources.max_processes {
             return Err(BackendError::ValidationError(format!(
@@ -159,20 +278,52 @@ pub mod utils {
         Ok(())
     }
     
+    /// Kernel `run` argument for a configured `max_events`: 0 is the unbounded
+    /// sentinel and maps to `i32::MAX`; negative values are rejected.
+    pub fn event_budget(max_events: i32) -> Result<i32, BackendError> {
+        match max_events {
+            0 => Ok(i32::MAX),
+            n if n < 0 => Err(BackendError::ValidationError(format!(
+                "max_events must be non-negative (0 means unbounded), got {}",
+                n
+            ))),
+            n => Ok(n),
+        }
+    }
+
+    /// Process instance count the program asks for via `RUNTIME_PROCESSES` (or `MAX_PROCESSES`)
+    pub fn declared_runtime_processes(program: &IrProgram) -> Option<usize> {
+        program
+            .constants
+            .get("RUNTIME_PROCESSES")
+            .or_else(|| program.constants.get("MAX_PROCESSES"))
+            .and_then(|v| match v {
+                grey_ir::IrValue::Integer(i) if *i > 0 => Some(*i as usize),
+                _ => None,
+            })
+    }
+    
     /// Generate deterministic coordinate assignment
     pub fn generate_process_coords(processes: &[&IrProcess]) -> HashMap<String, grey_ir::Coord> {
-        let mut coords = HashMap::new();
-        let grid_size = ((processes.len() as f32).sqrt().ceil() as i32).max(1);
-        
-        for (i, process) in processes.iter().enumerate() {
-            let x = (i as i32) % grid_size;
-            let y = (i as i32) / grid_size;
-            let z = 0; // 2D layout for now
-            
-            coords.insert(process.name.clone(), grey_ir::Coord::new(x, y, z));
-        }
-        
-        coords
+        processes
+            .iter()
+            .zip(grid_coords(processes.len(), 1, None))
+            .map(|(process, coord)| (process.name.clone(), coord))
+            .collect()
+    }
+
+    /// Row-major `GridLayout` coordinates for `count` processes on the z = 0 plane.
+    ///
+    /// Rows are `columns` wide, or as wide as the smallest square grid that fits
+    /// `count` when `columns` is `None`. Coordinates are multiplied by `spacing`.
+    pub fn grid_coords(count: usize, spacing: i32, columns: Option<i32>) -> Vec<grey_ir::Coord> {
+        let width = columns
+            .unwrap_or_else(|| (count as f32).sqrt().ceil() as i32)
+            .max(1);
+
+        (0..count as i32)
+            .map(|i| grey_ir::Coord::new((i % width) * spacing, (i / width) * spacing, 0))
+            .collect()
     }
     
     /// Extract event definitions for validation
@@ -180,11 +331,155 @@ pub mod utils {
         program.events.iter().map(|e| (e.name.clone(), e)).collect()
     }
     
+    /// Per-instance field storage of each process type, keyed by process name
+    pub fn process_footprints(program: &IrProgram) -> HashMap<String, usize> {
+        program
+            .processes
+            .iter()
+            .map(|p| (p.name.clone(), p.footprint_bytes()))
+            .collect()
+    }
+
     /// Extract process definitions for validation
     pub fn get_process_map(program: &IrProgram) -> HashMap<String, &IrProcess> {
         program.processes.iter().map(|p| (p.name.clone(), p)).collect()
     }
+
+    /// Hex SHA-256 digest of generated output, for reproducible-build checks.
+    ///
+    /// Covers every file path and its contents in path order, then the metadata
+    /// counts, the time estimate and `max_events`. Each string is length-prefixed
+    /// so different splits of the same bytes hash differently.
+    pub fn hash_output(output: &CodeGenOutput) -> String {
+        fn write_str(hasher: &mut Sha256, value: &str) {
+            hasher.update((value.len() as u64).to_le_bytes());
+            hasher.update(value.as_bytes());
+        }
+
+        let mut hasher = Sha256::new();
+
+        let mut files: Vec<_> = output.files.iter().collect();
+        files.sort_by_key(|(path, _)| *path);
+        hasher.update((files.len() as u64).to_le_bytes());
+        for (path, contents) in files {
+            write_str(&mut hasher, &path.to_string_lossy());
+            write_str(&mut hasher, contents);
+        }
+
+        let metadata = &output.metadata;
+        write_str(&mut hasher, &metadata.source_name);
+        for count in [metadata.process_count, metadata.runtime_process_count, metadata.event_count] {
+            hasher.update((count as u64).to_le_bytes());
+        }
+        match metadata.expected_execution_time {
+            Some(ns) => {
+                hasher.update([1]);
+                hasher.update(ns.to_le_bytes());
+            }
+            None => hasher.update([0]),
+        }
+        hasher.update(output.runtime_config.max_events.to_le_bytes());
+
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
 }
 
 /// Betti RDL backend implementation
-pub mod betti_rdl;
\ No newline at end of file
+pub mod betti_rdl;
+
+/// Pure-Rust IR interpreter
+pub mod interpreter;
+
+/// Graphviz DOT topology backend
+pub mod dot_graph;
+
+/// C header describing event and process state layouts
+pub mod c_header;
+
+/// Toroidal spatial queries over process placements
+pub mod spatial;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(config: &RuntimeConfig) -> RuntimeConfig {
+        let json = serde_json::to_string(config).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_process_placement_round_trips() {
+        let mut custom = HashMap::new();
+        custom.insert("hub".to_string(), grey_ir::Coord::new(1, 2, 3));
+        custom.insert("edge".to_string(), grey_ir::Coord::new(4, 5, 6));
+
+        let placements = [
+            ProcessPlacement::SingleNode,
+            ProcessPlacement::GridLayout { spacing: 4, columns: None },
+            ProcessPlacement::GridLayout { spacing: 1, columns: Some(8) },
+            ProcessPlacement::Custom(custom),
+            ProcessPlacement::FromFile(PathBuf::from("placement.json")),
+        ];
+
+        for placement in placements {
+            for event_ordering in [EventOrdering::Fifo, EventOrdering::Deterministic] {
+                let config = RuntimeConfig {
+                    max_events: 500,
+                    process_placement: placement.clone(),
+                    event_ordering,
+                };
+                assert_eq!(round_trip(&config), config);
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_columns_force_row_width() {
+        let coords = utils::grid_coords(10, 2, Some(4));
+        let rows: Vec<(i32, i32)> = coords.iter().map(|c| (c.x, c.y)).collect();
+
+        assert_eq!(
+            rows,
+            vec![(0, 0), (2, 0), (4, 0), (6, 0), (0, 2), (2, 2), (4, 2), (6, 2), (0, 4), (2, 4)]
+        );
+        assert_eq!(utils::grid_coords(10, 1, None).iter().map(|c| c.x).max(), Some(3));
+    }
+
+    #[test]
+    fn test_validate_program_uses_resource_coordinate_bound() {
+        let typed = grey_lang::compile("module M { process Node { count: Int } }").unwrap();
+        let mut program = grey_ir::IrBuilder::new().build_program("bounded", &typed).unwrap().clone();
+        program.processes[0].coord = grey_ir::Coord::new(12, 3, 0);
+        assert!(program.processes[0].coord.is_valid());
+        assert!(utils::validate_program(&program).is_ok());
+
+        program.resources.max_coordinate_value = 7;
+        let err = utils::validate_program(&program).unwrap_err();
+        assert!(err.to_string().contains("invalid coordinate"));
+    }
+
+    #[test]
+    fn test_output_hash_tracks_generated_content() {
+        let source = "module M { event Ping {} event Pong {} process Node { count: Int, method handle_ping(event: Ping) { this.count = 1; } } }";
+        let build = || {
+            let typed = grey_lang::compile(source).unwrap();
+            grey_ir::IrBuilder::new().build_program("hashed", &typed).unwrap().clone()
+        };
+        let backend = dot_graph::DotGraphBackend::new();
+        let hash = |program: &IrProgram| utils::hash_output(&backend.generate_code(program).unwrap());
+
+        let program = build();
+        let digest = hash(&program);
+        assert_eq!(digest.len(), 64);
+        assert_eq!(digest, hash(&build()));
+
+        let mut changed = program.clone();
+        changed.processes[0].transitions[0].event_type = "Pong".to_string();
+        assert_ne!(hash(&changed), digest);
+    }
+}
@@ -0,0 +1,124 @@
+//! Spatial queries over process placements
+//!
+//! Betti RDL lays processes out on a 32x32x32 torus, so distances wrap around
+//! each axis: x = 0 and x = 31 are neighbours. `SpatialIndex` answers nearest
+//! and radius queries over a placement map using that toroidal metric.
+
+use std::collections::HashMap;
+
+use grey_ir::Coord;
+
+/// Extent of each axis of the Betti RDL torus
+pub const TORUS_SIZE: i32 = 32;
+
+/// Index of placed processes for toroidal nearest/radius queries
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    /// Placements sorted by process name so ties resolve deterministically
+    entries: Vec<(String, Coord)>,
+}
+
+impl SpatialIndex {
+    /// Build an index from a process placement map
+    pub fn new(placements: &HashMap<String, Coord>) -> Self {
+        let mut entries: Vec<(String, Coord)> = placements
+            .iter()
+            .map(|(name, coord)| (name.clone(), coord.clone()))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Self { entries }
+    }
+
+    /// Number of indexed processes
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the index has no processes
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Process closest to `coord`; ties go to the alphabetically first name
+    pub fn nearest(&self, coord: &Coord) -> Option<&str> {
+        self.entries
+            .iter()
+            .min_by_key(|(_, placed)| toroidal_distance_squared(coord, placed))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Processes within `radius` of `coord` (inclusive), nearest first
+    pub fn within_radius(&self, coord: &Coord, radius: u32) -> Vec<&str> {
+        let limit = i64::from(radius) * i64::from(radius);
+        let mut hits: Vec<(i64, &str)> = self
+            .entries
+            .iter()
+            .map(|(name, placed)| (toroidal_distance_squared(coord, placed), name.as_str()))
+            .filter(|(distance, _)| *distance <= limit)
+            .collect();
+        hits.sort();
+        hits.into_iter().map(|(_, name)| name).collect()
+    }
+}
+
+/// Squared Euclidean distance between two coordinates on the torus
+pub fn toroidal_distance_squared(a: &Coord, b: &Coord) -> i64 {
+    fn axis(a: i32, b: i32) -> i64 {
+        let d = i64::from(a - b).rem_euclid(i64::from(TORUS_SIZE));
+        let d = d.min(i64::from(TORUS_SIZE) - d);
+        d * d
+    }
+
+    axis(a.x, b.x) + axis(a.y, b.y) + axis(a.z, b.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(placements: &[(&str, Coord)]) -> SpatialIndex {
+        SpatialIndex::new(
+            &placements
+                .iter()
+                .map(|(name, coord)| (name.to_string(), coord.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_nearest() {
+        let spatial = index(&[
+            ("depot", Coord::new(0, 0, 0)),
+            ("drone_a", Coord::new(5, 5, 0)),
+            ("drone_b", Coord::new(10, 2, 0)),
+        ]);
+
+        assert_eq!(spatial.nearest(&Coord::new(9, 3, 0)), Some("drone_b"));
+        assert_eq!(spatial.nearest(&Coord::new(1, 1, 0)), Some("depot"));
+        assert_eq!(SpatialIndex::default().nearest(&Coord::new(0, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_nearest_wraps_around() {
+        let spatial = index(&[("east", Coord::new(31, 0, 0)), ("middle", Coord::new(4, 0, 0))]);
+
+        // x = 31 is one step from x = 0 across the seam
+        assert_eq!(spatial.nearest(&Coord::new(0, 0, 0)), Some("east"));
+        assert_eq!(toroidal_distance_squared(&Coord::new(0, 0, 0), &Coord::new(31, 31, 31)), 3);
+    }
+
+    #[test]
+    fn test_within_radius() {
+        let spatial = index(&[
+            ("a", Coord::new(0, 0, 0)),
+            ("b", Coord::new(3, 4, 0)),
+            ("c", Coord::new(10, 0, 0)),
+            ("d", Coord::new(30, 0, 1)),
+        ]);
+
+        // b is exactly 5 away; d is sqrt(5) away across the x seam
+        assert_eq!(spatial.within_radius(&Coord::new(0, 0, 0), 5), vec!["a", "d", "b"]);
+        assert_eq!(spatial.within_radius(&Coord::new(0, 0, 0), 0), vec!["a"]);
+        assert_eq!(spatial.within_radius(&Coord::new(20, 16, 16), 2), Vec::<&str>::new());
+    }
+}
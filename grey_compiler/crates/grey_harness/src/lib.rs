@@ -1,5 +1,5 @@
 use std::collections::{BTreeMap, HashMap};
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::time::Instant;
 
@@ -7,10 +7,23 @@ use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 
 use grey_backends::betti_rdl::{BettiConfig, BettiRdlBackend};
-use grey_backends::{CodeGenerator, ProcessPlacement};
-use grey_ir::IrBuilder;
+use grey_backends::utils::{declared_runtime_processes, event_budget};
+use grey_backends::{CodeGenOutput, CodeGenerator, ProcessPlacement};
+use grey_ir::{IrBuilder, IrProgram};
 use grey_lang::compile;
 
+/// Per-field state of one process, keyed by field name.
+pub type ProcessFields = BTreeMap<String, serde_json::Value>;
+
+/// Field name used for the single integer state the Betti kernel reports per process.
+pub const KERNEL_STATE_FIELD: &str = "state";
+
+fn kernel_state_fields(state: i32) -> ProcessFields {
+    let mut fields = ProcessFields::new();
+    fields.insert(KERNEL_STATE_FIELD.to_string(), state.into());
+    fields
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionResult {
     pub seed_used: u64,
@@ -22,7 +35,15 @@ pub struct ExecutionResult {
     pub current_time: u64,
     pub execution_time_ns: u64,
 
-    pub process_states: BTreeMap<usize, i32>,
+    /// Whether the event queue drained before `max_events` was reached.
+    #[serde(default = "default_quiescent")]
+    pub quiescent: bool,
+
+    pub process_states: BTreeMap<usize, ProcessFields>,
+}
+
+fn default_quiescent() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +56,32 @@ pub struct ComparisonResult {
     pub state_differences: Vec<String>,
 
     pub parity_achieved: bool,
+
+    /// Problems that make the comparison less trustworthy, such as a run that
+    /// stopped at `max_events` with events still queued.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+
+    /// Differences between the built IR and the golden IR, when one was given.
+    #[serde(default)]
+    pub ir_diff: Vec<String>,
+}
+
+/// Implementation the Grey run is compared against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReferenceBackend {
+    /// The `grey_sir_reference` C++ executable, built via CMake unless overridden.
+    #[default]
+    Cpp,
+
+    /// The pure-Rust IR interpreter, run in-process on the same placement and
+    /// seeded injections as the Grey side. Needs neither CMake nor a C++
+    /// toolchain for the reference.
+    ///
+    /// The interpreter keeps each process's IR fields rather than the kernel's
+    /// per-node value, so only the set of spawned pids is compared, alongside
+    /// `events_processed` and `current_time`.
+    RustInterpreter,
 }
 
 #[derive(Debug, Clone)]
@@ -45,8 +92,22 @@ pub struct HarnessConfig {
 
     pub demo_path: PathBuf,
 
+    /// Grey source to run instead of reading `demo_path`.
+    pub demo_source: Option<String>,
+
+    /// Which reference implementation to compare against.
+    pub reference: ReferenceBackend,
+
     /// If set, uses this executable directly instead of building it via CMake.
     pub cpp_exe_override: Option<PathBuf>,
+
+    /// Golden IR JSON the built IR must match, as written by `ir_json`.
+    pub ir_golden: Option<PathBuf>,
+
+    /// Placement for the Grey side instead of the square grid of the declared
+    /// process count. The C++ reference always lays out its own grid, so only the
+    /// interpreter reference sees the same coordinates.
+    pub placement: Option<ProcessPlacement>,
 }
 
 impl Default for HarnessConfig {
@@ -61,34 +122,58 @@ impl Default for HarnessConfig {
             max_events: 1000,
             spacing: 1,
             demo_path: workspace_root.join("examples/sir_demo.grey"),
+            demo_source: None,
+            reference: ReferenceBackend::default(),
             cpp_exe_override: None,
+            ir_golden: None,
+            placement: None,
         }
     }
 }
 
 pub fn run_harness(config: &HarnessConfig) -> Result<ComparisonResult> {
-    let grey = execute_grey(&config.demo_path, config)?;
-    let cpp = execute_cpp(&grey, config)?;
-
-    let mut state_differences = Vec::new();
-    let pids: std::collections::BTreeSet<usize> = grey
-        .process_states
-        .keys()
-        .chain(cpp.process_states.keys())
-        .copied()
-        .collect();
-
-    for pid in pids {
-        let g = grey.process_states.get(&pid);
-        let c = cpp.process_states.get(&pid);
-        if g != c {
-            state_differences.push(format!("pid {}: grey={:?} cpp={:?}", pid, g, c));
+    event_budget(config.max_events)?;
+    let ir_program = build_ir(config)?;
+    let backend = harness_backend(config);
+    if let Some(placement) = &config.placement {
+        if let Some(spawned) = placement_process_count(placement)? {
+            check_process_count(&ir_program, spawned)?;
         }
     }
+    let output = backend
+        .generate_code(&ir_program)
+        .context("Betti codegen failed")?;
+    let grey = execute_grey(&backend, &output, config)?;
+    let ir_diff = match &config.ir_golden {
+        Some(path) => {
+            let golden = std::fs::read_to_string(path)
+                .with_context(|| format!("reading golden IR at {}", path.display()))?;
+            let golden: serde_json::Value = serde_json::from_str(&golden)
+                .with_context(|| format!("parsing golden IR at {}", path.display()))?;
+            diff_ir(&golden, &serde_json::to_value(&ir_program)?)
+        }
+        None => Vec::new(),
+    };
+    let cpp = match config.reference {
+        ReferenceBackend::Cpp => execute_cpp(&grey, config)?,
+        ReferenceBackend::RustInterpreter => execute_interpreter(&backend, &ir_program, &output, config)?,
+    };
+
+    let state_differences = match config.reference {
+        ReferenceBackend::Cpp => diff_process_states(&grey.process_states, &cpp.process_states),
+        ReferenceBackend::RustInterpreter => {
+            let pids_only = |states: &BTreeMap<usize, ProcessFields>| -> BTreeMap<usize, ProcessFields> {
+                states.keys().map(|&pid| (pid, ProcessFields::new())).collect()
+            };
+            diff_process_states(&pids_only(&grey.process_states), &pids_only(&cpp.process_states))
+        }
+    };
 
     let events_match = grey.events_processed == cpp.events_processed;
     let current_time_match = grey.current_time == cpp.current_time;
-    let parity_achieved = events_match && current_time_match && state_differences.is_empty();
+    let parity_achieved =
+        events_match && current_time_match && state_differences.is_empty() && ir_diff.is_empty();
+    let warnings = event_limit_warnings(&grey, &cpp);
 
     Ok(ComparisonResult {
         grey,
@@ -97,14 +182,151 @@ pub fn run_harness(config: &HarnessConfig) -> Result<ComparisonResult> {
         current_time_match,
         state_differences,
         parity_achieved,
+        warnings,
+        ir_diff,
     })
 }
 
-fn execute_grey(demo_path: &Path, config: &HarnessConfig) -> Result<ExecutionResult> {
-    let source = std::fs::read_to_string(demo_path)
-        .with_context(|| format!("reading Grey demo at {}", demo_path.display()))?;
+/// The IR as pretty JSON with object keys sorted, for storing as a golden file
+pub fn ir_json(program: &IrProgram) -> Result<String> {
+    let value = serde_json::to_value(program).context("serializing IR")?;
+    serde_json::to_string_pretty(&value).context("serializing IR")
+}
 
-    let start = Instant::now();
+/// Describe every difference between a golden IR and the built one, as JSON paths.
+///
+/// Objects are compared key by key, so field order in the golden file does not
+/// matter; arrays are compared element by element.
+pub fn diff_ir(golden: &serde_json::Value, actual: &serde_json::Value) -> Vec<String> {
+    fn walk(path: &str, golden: &serde_json::Value, actual: &serde_json::Value, out: &mut Vec<String>) {
+        use serde_json::Value;
+        match (golden, actual) {
+            (Value::Object(g), Value::Object(a)) => {
+                let keys: std::collections::BTreeSet<&String> = g.keys().chain(a.keys()).collect();
+                for key in keys {
+                    let child = format!("{path}.{key}");
+                    match (g.get(key), a.get(key)) {
+                        (Some(gv), Some(av)) => walk(&child, gv, av, out),
+                        (gv, av) => out.push(format!("{child}: golden={} actual={}", show(gv), show(av))),
+                    }
+                }
+            }
+            (Value::Array(g), Value::Array(a)) => {
+                for index in 0..g.len().max(a.len()) {
+                    let child = format!("{path}[{index}]");
+                    match (g.get(index), a.get(index)) {
+                        (Some(gv), Some(av)) => walk(&child, gv, av, out),
+                        (gv, av) => out.push(format!("{child}: golden={} actual={}", show(gv), show(av))),
+                    }
+                }
+            }
+            _ if golden != actual => out.push(format!("{path}: golden={golden} actual={actual}")),
+            _ => {}
+        }
+    }
+
+    fn show(value: Option<&serde_json::Value>) -> String {
+        value.map_or_else(|| "<missing>".to_string(), |v| v.to_string())
+    }
+
+    let mut differences = Vec::new();
+    walk("ir", golden, actual, &mut differences);
+    differences
+}
+
+/// Warn about runs cut off by `max_events` before their event queue drained.
+///
+/// Such runs are compared on a truncated prefix, so a mismatch may mean one side
+/// is running away rather than that the two produce genuinely different results.
+pub fn event_limit_warnings(grey: &ExecutionResult, cpp: &ExecutionResult) -> Vec<String> {
+    [("grey", grey), ("reference", cpp)]
+        .into_iter()
+        .filter(|(_, run)| !run.quiescent)
+        .map(|(side, run)| {
+            format!(
+                "{side} run hit max_events={} with events still queued; results are truncated, not necessarily diverged",
+                run.max_events
+            )
+        })
+        .collect()
+}
+
+/// Describe every per-field difference between two process-state snapshots.
+///
+/// A pid present on only one side is reported once; otherwise each differing or
+/// missing field is reported as ``pid N field `name`: grey=.. cpp=..``.
+pub fn diff_process_states(
+    grey: &BTreeMap<usize, ProcessFields>,
+    cpp: &BTreeMap<usize, ProcessFields>,
+) -> Vec<String> {
+    let mut differences = Vec::new();
+    let pids: std::collections::BTreeSet<usize> = grey.keys().chain(cpp.keys()).copied().collect();
+
+    for pid in pids {
+        let (g, c) = match (grey.get(&pid), cpp.get(&pid)) {
+            (Some(g), Some(c)) => (g, c),
+            (g, c) => {
+                differences.push(format!("pid {}: grey={:?} cpp={:?}", pid, g, c));
+                continue;
+            }
+        };
+
+        let fields: std::collections::BTreeSet<&String> = g.keys().chain(c.keys()).collect();
+        for field in fields {
+            let (gv, cv) = (g.get(field), c.get(field));
+            if gv != cv {
+                let show = |v: Option<&serde_json::Value>| {
+                    v.map_or_else(|| "<missing>".to_string(), |v| v.to_string())
+                };
+                differences.push(format!(
+                    "pid {} field `{}`: grey={} cpp={}",
+                    pid,
+                    field,
+                    show(gv),
+                    show(cv)
+                ));
+            }
+        }
+    }
+
+    differences
+}
+
+/// Check that the placement spawns the process count the program declares.
+///
+/// Returns the count both sides should run with. Programs that declare neither
+/// `RUNTIME_PROCESSES` nor `MAX_PROCESSES` accept whatever the placement spawns.
+pub fn check_process_count(program: &IrProgram, runtime_processes: usize) -> Result<usize> {
+    match declared_runtime_processes(program) {
+        Some(declared) if declared != runtime_processes => Err(anyhow!(
+            "process count mismatch: program declares {} processes but the placement spawns {}",
+            declared,
+            runtime_processes
+        )),
+        _ => Ok(runtime_processes),
+    }
+}
+
+/// Processes `placement` spawns on its own, before codegen sizes it.
+///
+/// `GridLayout` has no count of its own: the backend sizes it from the declared
+/// count, so it yields `None` and cannot disagree with the program.
+fn placement_process_count(placement: &ProcessPlacement) -> Result<Option<usize>> {
+    match placement.resolve().context("resolving harness placement")? {
+        ProcessPlacement::SingleNode => Ok(Some(1)),
+        ProcessPlacement::GridLayout { .. } => Ok(None),
+        ProcessPlacement::Custom(mapping) => Ok(Some(mapping.len())),
+        ProcessPlacement::FromFile(_) => unreachable!("resolve reads FromFile placements"),
+    }
+}
+
+/// Compile the configured Grey demo and build its IR, as the harness runs it
+pub fn build_ir(config: &HarnessConfig) -> Result<IrProgram> {
+    let source = match &config.demo_source {
+        Some(source) => source.clone(),
+        None => std::fs::read_to_string(&config.demo_path)
+            .with_context(|| format!("reading Grey demo at {}", config.demo_path.display()))?,
+    };
 
     let typed_program = compile(&source).map_err(|e| anyhow!("Grey compilation failed: {e}"))?;
 
@@ -112,38 +334,53 @@ fn execute_grey(demo_path: &Path, config: &HarnessConfig) -> Result<ExecutionRes
     let ir_program = builder
         .build_program("sir_demo", &typed_program)
         .context("IR build failed")?;
+    Ok(ir_program.clone())
+}
 
-    let backend = BettiRdlBackend::new(BettiConfig {
+/// Backend both sides run with. The grid is square (`columns: None`), like the
+/// C++ reference's.
+fn harness_backend(config: &HarnessConfig) -> BettiRdlBackend {
+    BettiRdlBackend::new(BettiConfig {
         max_events: config.max_events,
         seed: config.seed,
-        process_placement: ProcessPlacement::GridLayout {
+        process_placement: config.placement.clone().unwrap_or(ProcessPlacement::GridLayout {
             spacing: config.spacing,
-        },
+            columns: None,
+        }),
         telemetry_enabled: true,
         validate_coordinates: true,
-    });
+        ..BettiConfig::default()
+    })
+}
 
-    let output = backend
-        .generate_code(ir_program)
-        .context("Betti codegen failed")?;
+fn execute_grey(
+    backend: &BettiRdlBackend,
+    output: &CodeGenOutput,
+    config: &HarnessConfig,
+) -> Result<ExecutionResult> {
+    let start = Instant::now();
+    let runtime_processes = output.metadata.runtime_process_count;
 
-    let telemetry = backend.execute(&output).context("Betti execution failed")?;
+    let telemetry = backend.execute(output).context("Betti execution failed")?;
 
-    let mut process_states = BTreeMap::new();
-    for (pid, state) in telemetry.process_states {
-        process_states.insert(pid, state);
-    }
+    let process_states = telemetry
+        .process_states
+        .into_iter()
+        .map(|(pid, state)| (pid, kernel_state_fields(state)))
+        .collect();
 
-    Ok(ExecutionResult {
+    let result = ExecutionResult {
         seed_used: config.seed,
         max_events: config.max_events,
-        runtime_processes: output.metadata.runtime_process_count,
+        runtime_processes,
         spacing: config.spacing,
         events_processed: telemetry.events_processed,
         current_time: telemetry.current_time,
         execution_time_ns: start.elapsed().as_nanos() as u64,
+        quiescent: telemetry.quiescent,
         process_states,
-    })
+    };
+    Ok(result)
 }
 
 #[derive(Debug, Deserialize)]
@@ -156,7 +393,12 @@ struct CppJsonOutput {
     events_processed: u64,
     current_time: u64,
 
-    process_states: HashMap<String, i32>,
+    /// Older reference builds omit this; it is then inferred from the event count.
+    #[serde(default)]
+    quiescent: Option<bool>,
+
+    /// Either a bare kernel state integer or an object of per-field values.
+    process_states: HashMap<String, serde_json::Value>,
 }
 
 fn execute_cpp(grey: &ExecutionResult, config: &HarnessConfig) -> Result<ExecutionResult> {
@@ -169,7 +411,7 @@ fn execute_cpp(grey: &ExecutionResult, config: &HarnessConfig) -> Result<Executi
         .arg("--seed")
         .arg(config.seed.to_string())
         .arg("--max-events")
-        .arg(config.max_events.to_string())
+        .arg(event_budget(config.max_events)?.to_string())
         .arg("--processes")
         .arg(grey.runtime_processes.to_string())
         .arg("--spacing")
@@ -201,7 +443,15 @@ fn execute_cpp(grey: &ExecutionResult, config: &HarnessConfig) -> Result<Executi
         let pid: usize = k
             .parse()
             .with_context(|| format!("invalid pid key in C++ output: {k}"))?;
-        process_states.insert(pid, v);
+        let fields = match v {
+            serde_json::Value::Object(map) => map.into_iter().collect(),
+            other => {
+                let mut fields = ProcessFields::new();
+                fields.insert(KERNEL_STATE_FIELD.to_string(), other);
+                fields
+            }
+        };
+        process_states.insert(pid, fields);
     }
 
     Ok(ExecutionResult {
@@ -212,6 +462,43 @@ fn execute_cpp(grey: &ExecutionResult, config: &HarnessConfig) -> Result<Executi
         events_processed: parsed.events_processed,
         current_time: parsed.current_time,
         execution_time_ns: 0,
+        quiescent: parsed
+            .quiescent
+            .unwrap_or(parsed.events_processed < parsed.max_events.max(0) as u64),
+        process_states,
+    })
+}
+
+/// Run the program on the IR interpreter with the Grey side's placement and
+/// seeded injections, through `BettiRdlBackend::execute_interpreted`.
+///
+/// Each spawned pid is reported with no fields; see `ReferenceBackend::RustInterpreter`.
+fn execute_interpreter(
+    backend: &BettiRdlBackend,
+    ir_program: &IrProgram,
+    output: &CodeGenOutput,
+    config: &HarnessConfig,
+) -> Result<ExecutionResult> {
+    let start = Instant::now();
+    let telemetry = backend
+        .execute_interpreted(ir_program, output)
+        .context("interpreter execution failed")?;
+
+    let coords = BettiRdlBackend::runtime_coords(output);
+    let process_states = coords
+        .iter()
+        .map(|coord| (coord.to_node_id(), ProcessFields::new()))
+        .collect();
+
+    Ok(ExecutionResult {
+        seed_used: config.seed,
+        max_events: config.max_events,
+        runtime_processes: coords.len(),
+        spacing: config.spacing,
+        events_processed: telemetry.events_processed,
+        current_time: telemetry.current_time,
+        execution_time_ns: start.elapsed().as_nanos() as u64,
+        quiescent: telemetry.quiescent,
         process_states,
     })
 }
@@ -239,7 +526,8 @@ fn build_cpp_reference() -> Result<PathBuf> {
         .arg("-B")
         .arg(&build_dir)
         .arg("-DCMAKE_BUILD_TYPE=Release")
-        .stdout(Stdio::inherit())
+        // Build logs go to stderr so stdout stays machine-readable (--format json).
+        .stdout(std::io::stderr())
         .stderr(Stdio::inherit())
         .status()
         .context("cmake configure")?;
@@ -253,7 +541,7 @@ fn build_cpp_reference() -> Result<PathBuf> {
         .arg(&build_dir)
         .arg("--target")
         .arg("grey_sir_reference")
-        .stdout(Stdio::inherit())
+        .stdout(std::io::stderr())
         .stderr(Stdio::inherit())
         .status()
         .context("cmake build")?;
@@ -279,9 +567,18 @@ fn build_cpp_reference() -> Result<PathBuf> {
         .ok_or_else(|| anyhow!("built executable not found in {}", build_dir.display()))
 }
 
+/// Render a comparison as pretty-printed JSON for scripts.
+pub fn summary_json(result: &ComparisonResult) -> Result<String> {
+    serde_json::to_string_pretty(result).context("serializing comparison result")
+}
+
 pub fn print_summary(result: &ComparisonResult) {
     println!("Grey events_processed={} current_time={} runtime_processes={}", result.grey.events_processed, result.grey.current_time, result.grey.runtime_processes);
-    println!("C++  events_processed={} current_time={} runtime_processes={}", result.cpp.events_processed, result.cpp.current_time, result.cpp.runtime_processes);
+    println!("Ref  events_processed={} current_time={} runtime_processes={}", result.cpp.events_processed, result.cpp.current_time, result.cpp.runtime_processes);
+
+    for warning in &result.warnings {
+        println!("WARNING: {warning}");
+    }
 
     if result.parity_achieved {
         println!("PARITY: OK");
@@ -290,6 +587,12 @@ pub fn print_summary(result: &ComparisonResult) {
         for diff in &result.state_differences {
             println!("  {diff}");
         }
+        if !result.ir_diff.is_empty() {
+            println!("IR changed from golden:");
+            for diff in &result.ir_diff {
+                println!("  {diff}");
+            }
+        }
     }
 }
 
@@ -304,4 +607,189 @@ mod tests {
         let result = run_harness(&config).expect("harness run");
         assert!(result.parity_achieved, "parity must be achieved: {result:?}");
     }
+
+    #[test]
+    fn state_diff_names_the_differing_field() {
+        let fields = |infected: bool, count: i64| -> ProcessFields {
+            [
+                ("infected".to_string(), infected.into()),
+                ("infection_count".to_string(), count.into()),
+            ]
+            .into_iter()
+            .collect()
+        };
+
+        let grey: BTreeMap<usize, ProcessFields> =
+            [(0, fields(false, 1)), (3, fields(true, 2))].into_iter().collect();
+        let cpp: BTreeMap<usize, ProcessFields> =
+            [(0, fields(false, 1)), (3, fields(false, 2))].into_iter().collect();
+
+        assert_eq!(
+            diff_process_states(&grey, &cpp),
+            vec!["pid 3 field `infected`: grey=true cpp=false".to_string()]
+        );
+    }
+
+    #[test]
+    fn process_count_mismatch_is_reported() {
+        let source = std::fs::read_to_string(HarnessConfig::default().demo_path).unwrap();
+        let typed = compile(&source).unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("sir_demo", &typed).unwrap();
+
+        assert_eq!(check_process_count(program, 64).unwrap(), 64);
+
+        let err = check_process_count(program, 10).unwrap_err();
+        assert!(err.to_string().contains("declares 64 processes but the placement spawns 10"));
+    }
+
+    fn custom_placement(count: i32) -> ProcessPlacement {
+        ProcessPlacement::Custom(
+            (0..count)
+                .map(|i| (format!("p{}", i), grey_ir::Coord::new(i % 8, i / 8, 0)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn harness_rejects_placement_disagreeing_with_declared_count() {
+        let config = HarnessConfig {
+            reference: ReferenceBackend::RustInterpreter,
+            placement: Some(custom_placement(10)),
+            ..HarnessConfig::default()
+        };
+
+        let err = run_harness(&config).unwrap_err();
+        assert!(err.to_string().contains("declares 64 processes but the placement spawns 10"));
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn harness_runs_placement_matching_declared_count() {
+        let config = HarnessConfig {
+            placement: Some(custom_placement(64)),
+            ..interpreter_config()
+        };
+
+        let result = run_harness(&config).expect("harness run");
+        assert_eq!(result.grey.runtime_processes, 64);
+        assert!(result.parity_achieved, "{:?}", result.state_differences);
+    }
+
+    /// The SIR demo's handlers send no events, so the interpreter processes exactly
+    /// the four seeded injections. Capping the kernel there keeps the event counts
+    /// and times of the two engines comparable.
+    #[cfg(feature = "betti-runtime")]
+    fn interpreter_config() -> HarnessConfig {
+        HarnessConfig {
+            reference: ReferenceBackend::RustInterpreter,
+            max_events: 4,
+            ..HarnessConfig::default()
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn summary_json_parses_back() {
+        let result = run_harness(&interpreter_config()).expect("harness run");
+
+        let json = summary_json(&result).unwrap();
+        let parsed: ComparisonResult = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.parity_achieved, result.parity_achieved);
+        assert_eq!(parsed.grey.events_processed, result.grey.events_processed);
+        assert_eq!(parsed.cpp.process_states, result.cpp.process_states);
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn event_limit_without_draining_warns() {
+        let config = HarnessConfig {
+            reference: ReferenceBackend::RustInterpreter,
+            max_events: 1,
+            ..HarnessConfig::default()
+        };
+        let result = run_harness(&config).expect("harness run");
+
+        assert!(!result.grey.quiescent);
+        assert_eq!(result.warnings.len(), 2);
+        assert!(result.warnings[0].starts_with("grey run hit max_events=1 with events still queued"));
+
+        let drained = HarnessConfig {
+            reference: ReferenceBackend::RustInterpreter,
+            max_events: 100_000,
+            ..HarnessConfig::default()
+        };
+        assert!(run_harness(&drained).expect("harness run").warnings.is_empty());
+    }
+
+    #[test]
+    fn negative_max_events_rejected() {
+        let config = HarnessConfig {
+            reference: ReferenceBackend::RustInterpreter,
+            max_events: -5,
+            ..HarnessConfig::default()
+        };
+        let err = run_harness(&config).unwrap_err();
+        assert!(err.to_string().contains("max_events must be non-negative"), "{err}");
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn inline_demo_source_replaces_demo_path() {
+        let source = std::fs::read_to_string(HarnessConfig::default().demo_path).unwrap();
+        let config = HarnessConfig {
+            demo_path: PathBuf::from("does/not/exist.grey"),
+            demo_source: Some(source),
+            ..interpreter_config()
+        };
+        let result = run_harness(&config).expect("harness run");
+
+        assert_eq!(result.grey.runtime_processes, 64);
+        assert!(result.parity_achieved, "parity must be achieved: {result:?}");
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn ir_golden_mismatch_fails_with_diff() {
+        let dir = std::env::temp_dir().join(format!("grey_harness_ir_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let golden = dir.join("sir_demo.ir.json");
+
+        let mut config = HarnessConfig {
+            ir_golden: Some(golden.clone()),
+            ..interpreter_config()
+        };
+        let program = build_ir(&config).unwrap();
+        std::fs::write(&golden, ir_json(&program).unwrap()).unwrap();
+        let matching = run_harness(&config).expect("harness run");
+
+        let mut altered = program.clone();
+        altered.processes[0].name = "Patient".to_string();
+        std::fs::write(&golden, ir_json(&altered).unwrap()).unwrap();
+        let changed = run_harness(&config).expect("harness run");
+
+        config.ir_golden = Some(dir.join("missing.json"));
+        let missing = run_harness(&config);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(matching.ir_diff.is_empty());
+        assert!(matching.parity_achieved, "parity must be achieved: {matching:?}");
+        assert_eq!(
+            changed.ir_diff,
+            vec![r#"ir.processes[0].name: golden="Patient" actual="Person""#.to_string()]
+        );
+        assert!(!changed.parity_achieved);
+        assert!(missing.unwrap_err().to_string().contains("reading golden IR"));
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn sir_harness_betti_backend_matches_interpreter() {
+        let result = run_harness(&interpreter_config()).expect("harness run");
+
+        assert_eq!(result.cpp.runtime_processes, 64);
+        assert_eq!(result.cpp.events_processed, 4);
+        assert!(result.parity_achieved, "parity must be achieved: {result:?}");
+    }
 }
@@ -1,50 +1,133 @@
+use std::io::{self, Read};
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
-use grey_harness::{print_summary, run_harness, HarnessConfig};
+use grey_backends::ProcessPlacement;
+
+use grey_harness::{
+    build_ir, ir_json, print_summary, run_harness, summary_json, HarnessConfig, ReferenceBackend,
+};
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum OutputFormat {
+    /// Human-readable summary
+    Text,
+    /// The full comparison result as JSON
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Reference {
+    /// The C++ `grey_sir_reference` executable
+    Cpp,
+    /// The pure-Rust IR interpreter, run in-process (no CMake or C++ toolchain for the reference)
+    Rust,
+}
 
 #[derive(Parser, Debug)]
 #[command(name = "grey_compare_sir")]
 #[command(about = "Compile and run the Grey SIR demo and compare against the C++ reference")]
 struct Cli {
-    /// Path to the Grey demo program
+    /// Path to the Grey demo program, or `-` to read its source from stdin
     #[arg(long)]
     demo: Option<PathBuf>,
 
+    /// Grey source to run inline instead of reading a demo file
+    #[arg(long, conflicts_with = "demo")]
+    demo_source: Option<String>,
+
     /// Seed used for deterministic injection patterns
     #[arg(long, default_value = "42")]
     seed: u64,
 
-    /// Maximum events to process
-    #[arg(long, default_value = "1000")]
+    /// Maximum events to process (0 means unbounded)
+    #[arg(long, default_value = "1000", allow_negative_numbers = true)]
     max_events: i32,
 
     /// Grid spacing used when spawning processes
     #[arg(long, default_value = "1")]
     spacing: i32,
 
+    /// Reference implementation to compare against
+    #[arg(long, value_enum, default_value = "cpp")]
+    reference: Reference,
+
+    /// Output format for the comparison summary
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+
+    /// Print nothing; report parity only through the exit code
+    #[arg(long)]
+    quiet: bool,
+
+    /// JSON placement mapping to spawn instead of the square grid; the program's
+    /// declared process count must match it
+    #[arg(long, value_name = "FILE")]
+    placement: Option<PathBuf>,
+
     /// Use an already-built C++ reference executable
     #[arg(long)]
     cpp_exe: Option<PathBuf>,
+
+    /// Also fail if the built IR differs from this golden IR JSON file
+    #[arg(long, value_name = "GOLDEN")]
+    compare_ir: Option<PathBuf>,
+
+    /// Write the built IR as a golden JSON file and exit without running
+    #[arg(long, value_name = "PATH", conflicts_with = "compare_ir")]
+    write_ir: Option<PathBuf>,
 }
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    let mut config = HarnessConfig::default();
-    config.seed = cli.seed;
-    config.max_events = cli.max_events;
-    config.spacing = cli.spacing;
+    let mut config = HarnessConfig {
+        seed: cli.seed,
+        max_events: cli.max_events,
+        spacing: cli.spacing,
+        reference: match cli.reference {
+            Reference::Cpp => ReferenceBackend::Cpp,
+            Reference::Rust => ReferenceBackend::RustInterpreter,
+        },
+        ..HarnessConfig::default()
+    };
 
     if let Some(demo) = cli.demo {
-        config.demo_path = demo;
+        if demo.as_os_str() == "-" {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source)?;
+            config.demo_source = Some(source);
+        } else {
+            config.demo_path = demo;
+        }
     }
+    config.demo_source = cli.demo_source.or(config.demo_source);
 
     config.cpp_exe_override = cli.cpp_exe;
+    config.ir_golden = cli.compare_ir;
+    config.placement = cli.placement.map(ProcessPlacement::FromFile);
+
+    if let Some(path) = cli.write_ir {
+        std::fs::write(&path, ir_json(&build_ir(&config)?)?)?;
+        if !cli.quiet {
+            println!("Wrote IR to {}", path.display());
+        }
+        return Ok(());
+    }
 
     let result = run_harness(&config)?;
-    print_summary(&result);
+    if !cli.quiet {
+        match cli.format {
+            OutputFormat::Text => print_summary(&result),
+            OutputFormat::Json => {
+                for warning in &result.warnings {
+                    eprintln!("warning: {warning}");
+                }
+                println!("{}", summary_json(&result)?);
+            }
+        }
+    }
 
     if !result.parity_achieved {
         std::process::exit(1);
@@ -5,12 +5,19 @@
 //! computational model of processes, events, and state transitions.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use thiserror::Error;
 
 /// Result type for IR operations
 pub type Result<T> = std::result::Result<T, IrError>;
 
+/// Deepest expression tree lowering produces; deeper source is rejected so that
+/// recursive evaluation and codegen stay within the stack
+pub const MAX_EXPRESSION_DEPTH: usize = 256;
+
+pub use grey_lang::types::STRING_FIELD_CAPACITY;
+
 /// IR-specific error types
 #[derive(Error, Debug)]
 pub enum IrError {
@@ -19,6 +26,9 @@ pub enum IrError {
     
     #[error("Process not found: {0}")]
     ProcessNotFound(String),
+
+    #[error("Process {0} is declared more than once")]
+    DuplicateProcess(String),
     
     #[error("Event not found: {0}")]
     EventNotFound(String),
@@ -28,6 +38,57 @@ pub enum IrError {
     
     #[error("Resource constraint violation: {0}")]
     ResourceConstraint(String),
+
+    #[error("Event field mismatch: {0}")]
+    EventFieldMismatch(String),
+
+    #[error("Dependency cycle between processes: {0}")]
+    DependencyCycle(String),
+
+    #[error("Process {process} has more than one transition for event {event}")]
+    DuplicateTransition { process: String, event: String },
+
+    #[error("Link error: {0}")]
+    Link(String),
+
+    #[error("Process {process} handler for {event} updates undeclared field {field}")]
+    UnknownField { process: String, event: String, field: String },
+
+    #[error("O(1) violation: loop bound {0} is not statically known")]
+    UnboundedLoop(String),
+
+    #[error("Expression nested deeper than {0} levels")]
+    ExpressionTooDeep(usize),
+
+    #[error("Expression cannot be lowered to IR: {0}")]
+    UnsupportedExpression(String),
+}
+
+/// Non-fatal issues found while building IR
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum IrWarning {
+    #[error("Process {0} has no transitions and can never react to events")]
+    ProcessWithoutTransitions(String),
+
+    #[error("Module {0} declares no constants, events, or processes")]
+    EmptyModule(String),
+
+    #[error("Process {process} field {field} is not set by init() and defaults to its zero value")]
+    DefaultedField { process: String, field: String },
+
+    #[error("Process {process} handler for {event} assigns {field} more than once; only the last assignment takes effect")]
+    OverwrittenField { process: String, event: String, field: String },
+}
+
+/// How to normalize processes that declare several transitions for one event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateTransitionPolicy {
+    /// Concatenate the actions of same-event transitions that share a condition
+    #[default]
+    Merge,
+
+    /// Reject any process with more than one transition for an event
+    Error,
 }
 
 /// 3D coordinate for process placement
@@ -46,12 +107,98 @@ impl Coord {
     
     /// Check if coordinate is within valid bounds (0-31 for each dimension)
     pub fn is_valid(&self) -> bool {
-        (0..=31).contains(&self.x) && (0..=31).contains(&self.y) && (0..=31).contains(&self.z)
+        self.is_valid_within(31)
+    }
+
+    /// Check that every component is in `0..=max`
+    pub fn is_valid_within(&self, max: i32) -> bool {
+        (0..=max).contains(&self.x) && (0..=max).contains(&self.y) && (0..=max).contains(&self.z)
+    }
+
+    /// The coordinate with each component wrapped into `0..32`, the way the Betti
+    /// kernel addresses its toroidal lattice.
+    pub fn wrapped(&self) -> Coord {
+        Coord::new(self.x.rem_euclid(32), self.y.rem_euclid(32), self.z.rem_euclid(32))
+    }
+
+    /// Betti RDL node id (process id) of this coordinate: `x * 1024 + y * 32 + z`
+    /// after wrapping each component modulo 32.
+    ///
+    /// This is a bijection between the 32³ lattice and `0..32768`, so
+    /// `Coord::from_node_id(c.to_node_id()) == c.wrapped()` for every coordinate.
+    pub fn to_node_id(&self) -> usize {
+        let c = self.wrapped();
+        (c.x * 1024 + c.y * 32 + c.z) as usize
+    }
+
+    /// Inverse of `to_node_id`; ids are taken modulo 32768.
+    pub fn from_node_id(id: usize) -> Coord {
+        let id = (id % 32768) as i32;
+        Coord::new(id / 1024, (id / 32) % 32, id % 32)
+    }
+
+    /// The six axis-adjacent coordinates on a torus of side `bound`.
+    ///
+    /// Components wrap modulo `bound`, matching the kernel's modulo-32 addressing when
+    /// `bound` is 32. Duplicates (on tori smaller than 3) and `self` are omitted.
+    pub fn neighbors(&self, bound: i32) -> Vec<Coord> {
+        const OFFSETS: [(i32, i32, i32); 6] = [
+            (-1, 0, 0),
+            (1, 0, 0),
+            (0, -1, 0),
+            (0, 1, 0),
+            (0, 0, -1),
+            (0, 0, 1),
+        ];
+
+        self.offset_neighbors(OFFSETS.iter().copied(), bound)
+    }
+
+    /// The 26 surrounding coordinates (Moore neighborhood) on a torus of side `bound`.
+    ///
+    /// Wrapping and de-duplication follow `neighbors`.
+    pub fn moore_neighbors(&self, bound: i32) -> Vec<Coord> {
+        let offsets = (-1..=1)
+            .flat_map(|dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| (dx, dy, dz))))
+            .filter(|&offset| offset != (0, 0, 0));
+
+        self.offset_neighbors(offsets, bound)
+    }
+
+    fn offset_neighbors(&self, offsets: impl Iterator<Item = (i32, i32, i32)>, bound: i32) -> Vec<Coord> {
+        let bound = bound.max(1);
+        let mut neighbors = Vec::new();
+
+        for (dx, dy, dz) in offsets {
+            let neighbor = Coord::new(
+                (self.x + dx).rem_euclid(bound),
+                (self.y + dy).rem_euclid(bound),
+                (self.z + dz).rem_euclid(bound),
+            );
+            if neighbor != *self && !neighbors.contains(&neighbor) {
+                neighbors.push(neighbor);
+            }
+        }
+
+        neighbors
     }
 }
 
+/// Structural size metrics of an `IrProgram`, see `IrProgram::complexity`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IrComplexity {
+    /// Transitions across all processes
+    pub total_transitions: usize,
+    /// Largest action count of any transition, counting `Repeat` bodies once
+    pub max_actions_per_transition: usize,
+    /// Deepest expression tree in any condition or action; a leaf has depth 1
+    pub max_expression_depth: usize,
+    /// Process fields plus event fields
+    pub field_count: usize,
+}
+
 /// Top-level IR program
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IrProgram {
     pub name: String,
     pub processes: Vec<IrProcess>,
@@ -60,8 +207,229 @@ pub struct IrProgram {
     pub resources: IrResourceBounds,
 }
 
+impl IrProgram {
+    /// Processes with at least one transition for `event`, in declaration order
+    pub fn processes_handling(&self, event: &str) -> Vec<&IrProcess> {
+        self.processes
+            .iter()
+            .filter(|p| p.transitions.iter().any(|t| t.event_type == event))
+            .collect()
+    }
+
+    /// Check that every `SendEvent` action supplies exactly the fields its event declares.
+    ///
+    /// Field values whose type is known statically (constants) must also match the
+    /// declared field type.
+    pub fn validate_event_sends(&self) -> Result<()> {
+        for process in &self.processes {
+            for transition in &process.transitions {
+                for action in transition.flattened_actions() {
+                    if let IrAction::SendEvent { event_type, fields, .. } = action {
+                        let event = self
+                            .events
+                            .iter()
+                            .find(|e| &e.name == event_type)
+                            .ok_or_else(|| IrError::EventNotFound(event_type.clone()))?;
+                        event.check_send_fields(&process.name, fields)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Order processes so that every process comes after the processes that send it events.
+    ///
+    /// A process depends on another if the other sends an event type it handles. Ties are
+    /// broken by declaration order and self-sends are ignored. Returns
+    /// `IrError::DependencyCycle` naming the processes involved if no such order exists.
+    pub fn topologically_ordered_processes(&self) -> Result<Vec<&IrProcess>> {
+        let count = self.processes.len();
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); count];
+        let mut in_degree = vec![0usize; count];
+
+        for (sender_idx, sender) in self.processes.iter().enumerate() {
+            let sent: Vec<&str> = sender
+                .transitions
+                .iter()
+                .flat_map(|t| t.flattened_actions())
+                .filter_map(|a| match a {
+                    IrAction::SendEvent { event_type, .. } => Some(event_type.as_str()),
+                    _ => None,
+                })
+                .collect();
+
+            for (receiver_idx, receiver) in self.processes.iter().enumerate() {
+                if receiver_idx == sender_idx {
+                    continue;
+                }
+                let receives = receiver
+                    .transitions
+                    .iter()
+                    .any(|t| sent.contains(&t.event_type.as_str()));
+                if receives && !dependents[sender_idx].contains(&receiver_idx) {
+                    dependents[sender_idx].push(receiver_idx);
+                    in_degree[receiver_idx] += 1;
+                }
+            }
+        }
+
+        let mut ordered = Vec::with_capacity(count);
+        let mut placed = vec![false; count];
+
+        while ordered.len() < count {
+            let Some(next) = (0..count).find(|&i| !placed[i] && in_degree[i] == 0) else {
+                let cycle: Vec<&str> = (0..count)
+                    .filter(|&i| !placed[i])
+                    .map(|i| self.processes[i].name.as_str())
+                    .collect();
+                return Err(IrError::DependencyCycle(cycle.join(", ")));
+            };
+
+            placed[next] = true;
+            ordered.push(&self.processes[next]);
+            for &dependent in &dependents[next] {
+                in_degree[dependent] -= 1;
+            }
+        }
+
+        Ok(ordered)
+    }
+
+    /// Map each event name to `(emitting processes, consuming processes)`.
+    ///
+    /// Emitters are processes with a `SendEvent` action for the event and consumers
+    /// are processes with a transition on it. Both lists are in declaration order
+    /// without duplicates. Every declared event gets an entry, as does any event that
+    /// is sent or handled without being declared.
+    pub fn event_dependency_graph(&self) -> HashMap<String, (Vec<String>, Vec<String>)> {
+        fn add(list: &mut Vec<String>, process: &str) {
+            if !list.iter().any(|p| p == process) {
+                list.push(process.to_string());
+            }
+        }
+
+        let mut graph: HashMap<String, (Vec<String>, Vec<String>)> = self
+            .events
+            .iter()
+            .map(|event| (event.name.clone(), (Vec::new(), Vec::new())))
+            .collect();
+
+        for process in &self.processes {
+            for transition in &process.transitions {
+                add(&mut graph.entry(transition.event_type.clone()).or_default().1, &process.name);
+                for action in transition.flattened_actions() {
+                    if let IrAction::SendEvent { event_type, .. } = action {
+                        add(&mut graph.entry(event_type.clone()).or_default().0, &process.name);
+                    }
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Size metrics used to spot programs that are getting expensive to run or lower.
+    pub fn complexity(&self) -> IrComplexity {
+        let mut complexity = IrComplexity {
+            field_count: self.processes.iter().map(|p| p.fields.len()).sum::<usize>()
+                + self.events.iter().map(|e| e.fields.len()).sum::<usize>(),
+            ..IrComplexity::default()
+        };
+
+        for transition in self.processes.iter().flat_map(|p| &p.transitions) {
+            let actions = transition.flattened_actions();
+            complexity.total_transitions += 1;
+            complexity.max_actions_per_transition =
+                complexity.max_actions_per_transition.max(actions.len());
+
+            let expressions = transition.condition.iter().chain(actions.iter().flat_map(|action| {
+                match action {
+                    IrAction::UpdateField { value, .. } => vec![value],
+                    IrAction::SendEvent { fields, .. } => fields.values().collect(),
+                    IrAction::Branch { arms } => arms.iter().map(|arm| &arm.guard).collect(),
+                    IrAction::SpawnProcess { .. } | IrAction::Repeat { .. } => Vec::new(),
+                }
+            }));
+            for expression in expressions {
+                complexity.max_expression_depth =
+                    complexity.max_expression_depth.max(expression.depth());
+            }
+        }
+
+        complexity
+    }
+
+    /// Move every process to `f(name, coord)`.
+    ///
+    /// All new coordinates must lie within `resources.max_coordinate_value`; if
+    /// any does not, the program is left unchanged. `SendEvent` targets are kept.
+    pub fn remap_coords(&mut self, f: impl Fn(&str, Coord) -> Coord) -> Result<()> {
+        self.remap(f, false)
+    }
+
+    /// `remap_coords`, also moving each literal `SendEvent` target that addresses a
+    /// process to where that process moved. Where several processes share the old
+    /// coordinate, the first declared one decides.
+    pub fn remap_coords_and_targets(&mut self, f: impl Fn(&str, Coord) -> Coord) -> Result<()> {
+        self.remap(f, true)
+    }
+
+    fn remap(&mut self, f: impl Fn(&str, Coord) -> Coord, remap_targets: bool) -> Result<()> {
+        fn retarget(actions: &mut [IrAction], moved: &HashMap<Coord, Coord>) {
+            for action in actions {
+                match action {
+                    IrAction::SendEvent { target: IrCoordExpression::Literal(coord), .. } => {
+                        if let Some(new) = moved.get(coord) {
+                            *coord = new.clone();
+                        }
+                    }
+                    IrAction::Repeat { body, .. } => retarget(body, moved),
+                    IrAction::Branch { arms } => {
+                        for arm in arms {
+                            retarget(&mut arm.actions, moved);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let max = self.resources.max_coordinate_value;
+        let mut new_coords = Vec::with_capacity(self.processes.len());
+        for process in &self.processes {
+            let coord = f(&process.name, process.coord.clone());
+            if !coord.is_valid_within(max) {
+                return Err(IrError::InvalidCoordinate(format!(
+                    "process {} remapped to {:?} (max component {})",
+                    process.name, coord, max
+                )));
+            }
+            new_coords.push(coord);
+        }
+
+        let mut moved = HashMap::new();
+        for (process, coord) in self.processes.iter().zip(&new_coords) {
+            moved.entry(process.coord.clone()).or_insert_with(|| coord.clone());
+        }
+        for (process, coord) in self.processes.iter_mut().zip(new_coords) {
+            process.coord = coord;
+        }
+
+        if remap_targets {
+            for process in &mut self.processes {
+                for transition in &mut process.transitions {
+                    retarget(&mut transition.actions, &moved);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Process definition in IR
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IrProcess {
     pub name: String,
     pub coord: Coord,
@@ -70,29 +438,245 @@ pub struct IrProcess {
     pub transitions: Vec<IrTransition>,
 }
 
+impl IrProcess {
+    /// Normalize transitions so each (event, condition) pair appears at most once.
+    ///
+    /// With `Merge`, transitions for the same event and an identical condition are
+    /// combined into the first one, actions kept in declaration order. Transitions
+    /// guarded by different conditions stay separate. With `Error`, any repeated
+    /// event type is rejected.
+    pub fn merge_duplicate_transitions(&mut self, policy: DuplicateTransitionPolicy) -> Result<()> {
+        let mut merged: Vec<IrTransition> = Vec::with_capacity(self.transitions.len());
+
+        for transition in self.transitions.drain(..) {
+            let seen_event = merged.iter().any(|t| t.event_type == transition.event_type);
+
+            match (policy, seen_event) {
+                (DuplicateTransitionPolicy::Error, true) => {
+                    return Err(IrError::DuplicateTransition {
+                        process: self.name.clone(),
+                        event: transition.event_type,
+                    });
+                }
+                (DuplicateTransitionPolicy::Merge, true) => {
+                    if let Some(existing) = merged.iter_mut().find(|t| {
+                        t.event_type == transition.event_type && t.condition == transition.condition
+                    }) {
+                        existing.actions.extend(transition.actions);
+                    } else {
+                        merged.push(transition);
+                    }
+                }
+                (_, false) => merged.push(transition),
+            }
+        }
+
+        self.transitions = merged;
+        Ok(())
+    }
+
+    /// Static size of one instance's fields, summed without padding
+    pub fn footprint_bytes(&self) -> usize {
+        self.fields.values().map(IrType::size_bytes).sum()
+    }
+
+    /// Distinct event types this process has transitions for, in declaration order
+    pub fn handled_events(&self) -> Vec<&str> {
+        let mut events: Vec<&str> = Vec::new();
+        for transition in &self.transitions {
+            if !events.contains(&transition.event_type.as_str()) {
+                events.push(&transition.event_type);
+            }
+        }
+        events
+    }
+}
+
 /// Event definition in IR
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IrEvent {
     pub name: String,
     pub fields: HashMap<String, IrType>,
 }
 
+impl IrEvent {
+    fn check_send_fields(&self, sender: &str, fields: &HashMap<String, IrExpression>) -> Result<()> {
+        let mut declared: Vec<_> = self.fields.keys().collect();
+        declared.sort();
+
+        // Undeclared names first: a misspelled field is also a missing one, and
+        // the misspelling is the more useful thing to report
+        let mut provided: Vec<_> = fields.keys().collect();
+        provided.sort();
+
+        if let Some(extra) = provided.into_iter().find(|name| !self.fields.contains_key(*name)) {
+            let suggestion = declared
+                .iter()
+                .filter(|name| !fields.contains_key(**name))
+                .map(|name| (edit_distance(extra, name), name))
+                .filter(|(distance, name)| *distance <= name.len() / 3 + 1)
+                .min()
+                .map(|(_, name)| format!("; did you mean '{}'?", name))
+                .unwrap_or_default();
+            return Err(IrError::EventFieldMismatch(format!(
+                "{} sends {} with undeclared field '{}'{}",
+                sender, self.name, extra, suggestion
+            )));
+        }
+
+        for name in declared {
+            let Some(value) = fields.get(name) else {
+                return Err(IrError::EventFieldMismatch(format!(
+                    "{} sends {} without required field '{}'",
+                    sender, self.name, name
+                )));
+            };
+
+            if let IrExpression::Constant(constant) = value {
+                let expected = &self.fields[name];
+                let actual = constant.type_of();
+                if &actual != expected {
+                    return Err(IrError::EventFieldMismatch(format!(
+                        "{} sends {}.{} as {:?}, expected {:?}",
+                        sender, self.name, name, actual, expected
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Field names in canonical wire order: sorted by name.
+    ///
+    /// Field declaration order is not preserved in `fields`, so both the Grey
+    /// backend and the C++ reference lay out payloads alphabetically.
+    pub fn wire_field_order(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.fields.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Encode an event instance into the `i32` payload the kernel receives.
+    ///
+    /// Fields are written in [`wire_field_order`](Self::wire_field_order):
+    /// `Int` takes one slot (must fit in `i32`), `Bool` one slot (see
+    /// [`IrValue::to_kernel_word`]) and
+    /// `Coord` three slots (`x`, `y`, `z`). `String` fields have no wire form.
+    /// Every declared field must be present with its declared type.
+    pub fn encode_payload(&self, values: &HashMap<String, IrValue>) -> Result<Vec<i32>> {
+        if let Some(extra) = values.keys().filter(|name| !self.fields.contains_key(*name)).min() {
+            return Err(IrError::EventFieldMismatch(format!(
+                "{} has no field '{}'",
+                self.name, extra
+            )));
+        }
+
+        let mut payload = Vec::with_capacity(self.fields.len());
+        for name in self.wire_field_order() {
+            let value = values.get(name).ok_or_else(|| {
+                IrError::EventFieldMismatch(format!("{} is missing field '{}'", self.name, name))
+            })?;
+
+            let expected = &self.fields[name];
+            if &value.type_of() != expected {
+                return Err(IrError::EventFieldMismatch(format!(
+                    "{}.{} is {:?}, expected {:?}",
+                    self.name,
+                    name,
+                    value.type_of(),
+                    expected
+                )));
+            }
+
+            match value {
+                IrValue::Integer(_) | IrValue::Boolean(_) => {
+                    payload.push(value.to_kernel_word().ok_or_else(|| {
+                        IrError::EventFieldMismatch(format!(
+                            "{}.{} = {:?} does not fit in an i32 payload slot",
+                            self.name, name, value
+                        ))
+                    })?)
+                }
+                IrValue::Coord(c) => payload.extend([c.x, c.y, c.z]),
+                IrValue::String(_) => {
+                    return Err(IrError::EventFieldMismatch(format!(
+                        "{}.{} is a String, which has no wire encoding",
+                        self.name, name
+                    )))
+                }
+            }
+        }
+
+        Ok(payload)
+    }
+}
+
 /// Process state representation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IrState {
     pub values: HashMap<String, IrValue>,
 }
 
 /// State transition from event handling
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IrTransition {
     pub event_type: String,
     pub condition: Option<IrExpression>,
     pub actions: Vec<IrAction>,
+    /// Dispatch order when several transitions match one event: lower values
+    /// fire first, and equal priorities keep declaration order
+    #[serde(default)]
+    pub priority: i32,
+}
+
+impl IrTransition {
+    /// Every action in the transition, including those nested in `Repeat` bodies
+    /// and `Branch` arms, in source order. Repeated bodies are listed once.
+    pub fn flattened_actions(&self) -> Vec<&IrAction> {
+        fn collect<'a>(actions: &'a [IrAction], out: &mut Vec<&'a IrAction>) {
+            for action in actions {
+                out.push(action);
+                match action {
+                    IrAction::Repeat { body, .. } => collect(body, out),
+                    IrAction::Branch { arms } => {
+                        for arm in arms {
+                            collect(&arm.actions, out);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut actions = Vec::new();
+        collect(&self.actions, &mut actions);
+        actions
+    }
+
+    /// Fields assigned again at the top level of the transition before anything
+    /// read the earlier value, in order of the overwriting assignment. Only the
+    /// last of such assignments has any effect.
+    pub fn overwritten_fields(&self) -> Vec<&str> {
+        let mut unread: Vec<&str> = Vec::new();
+        let mut overwritten: Vec<&str> = Vec::new();
+
+        for action in &self.actions {
+            unread.retain(|field| !action.reads_field(field));
+            if let IrAction::UpdateField { field, .. } = action {
+                if !unread.contains(&field.as_str()) {
+                    unread.push(field);
+                } else if !overwritten.contains(&field.as_str()) {
+                    overwritten.push(field);
+                }
+            }
+        }
+        overwritten
+    }
 }
 
 /// Action performed during state transition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum IrAction {
     UpdateField {
         field: String,
@@ -100,7 +684,7 @@ pub enum IrAction {
     },
     SendEvent {
         event_type: String,
-        target: Coord,
+        target: IrCoordExpression,
         fields: HashMap<String, IrExpression>,
     },
     SpawnProcess {
@@ -108,10 +692,85 @@ pub enum IrAction {
         coord: Coord,
         initial_state: IrState,
     },
+    /// Run `body` `count` times, lowered from a `for` loop with static bounds.
+    /// The loop variable is not visible to the body.
+    Repeat {
+        count: u32,
+        body: Vec<IrAction>,
+    },
+    /// Run the actions of the first arm whose guard holds, lowered from an
+    /// `if`/`else if`/`else` chain. The arms are mutually exclusive; a final
+    /// `else` becomes an arm guarded by `true`.
+    Branch {
+        arms: Vec<IrBranchArm>,
+    },
+}
+
+impl IrAction {
+    /// Whether evaluating the action, including nested bodies and arms, reads `field`
+    fn reads_field(&self, field: &str) -> bool {
+        match self {
+            IrAction::UpdateField { value, .. } => value.reads_field(field),
+            IrAction::SendEvent { target, fields, .. } => {
+                target.reads_field(field) || fields.values().any(|value| value.reads_field(field))
+            }
+            IrAction::SpawnProcess { .. } => false,
+            IrAction::Repeat { body, .. } => body.iter().any(|action| action.reads_field(field)),
+            IrAction::Branch { arms } => arms.iter().any(|arm| {
+                arm.guard.reads_field(field) || arm.actions.iter().any(|action| action.reads_field(field))
+            }),
+        }
+    }
+}
+
+/// Target coordinate of a `SendEvent`, evaluated when the event is sent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum IrCoordExpression {
+    /// A fixed coordinate
+    Literal(Coord),
+    /// The sending process's own coordinate
+    SelfCoord,
+    /// An expression yielding a `Coord` value, such as a `Coord` field
+    Value(IrExpression),
+    /// `base` moved by integer offsets, wrapped onto the kernel's 32-node torus
+    Offset {
+        base: Box<IrCoordExpression>,
+        dx: IrExpression,
+        dy: IrExpression,
+        dz: IrExpression,
+    },
+}
+
+impl IrCoordExpression {
+    fn reads_field(&self, field: &str) -> bool {
+        match self {
+            IrCoordExpression::Literal(_) | IrCoordExpression::SelfCoord => false,
+            IrCoordExpression::Value(value) => value.reads_field(field),
+            IrCoordExpression::Offset { base, dx, dy, dz } => {
+                base.reads_field(field)
+                    || dx.reads_field(field)
+                    || dy.reads_field(field)
+                    || dz.reads_field(field)
+            }
+        }
+    }
+}
+
+impl From<Coord> for IrCoordExpression {
+    fn from(coord: Coord) -> Self {
+        IrCoordExpression::Literal(coord)
+    }
+}
+
+/// One guarded arm of an `IrAction::Branch`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IrBranchArm {
+    pub guard: IrExpression,
+    pub actions: Vec<IrAction>,
 }
 
 /// IR expressions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum IrExpression {
     Constant(IrValue),
     FieldAccess(String),
@@ -125,10 +784,48 @@ pub enum IrExpression {
         left: Box<IrExpression>,
         right: Box<IrExpression>,
     },
+    Not(Box<IrExpression>),
+}
+
+impl IrExpression {
+    /// Height of the expression tree; constants and field reads have depth 1.
+    pub fn depth(&self) -> usize {
+        match self {
+            IrExpression::Constant(_) | IrExpression::FieldAccess(_) => 1,
+            IrExpression::Arithmetic { left, right, .. }
+            | IrExpression::Comparison { left, right, .. } => 1 + left.depth().max(right.depth()),
+            IrExpression::Not(inner) => 1 + inner.depth(),
+        }
+    }
+
+    /// Type of the expression's result, when it can be derived from `fields`.
+    ///
+    /// Names that are not process fields (e.g. event fields) have no known type.
+    fn ir_type(&self, fields: &HashMap<String, IrType>) -> Option<IrType> {
+        match self {
+            IrExpression::Constant(value) => Some(value.type_of()),
+            IrExpression::FieldAccess(name) => fields.get(name).cloned(),
+            IrExpression::Arithmetic { .. } => Some(IrType::Int),
+            IrExpression::Comparison { .. } | IrExpression::Not(_) => Some(IrType::Bool),
+        }
+    }
+
+    /// Whether the expression reads the process field or event field `field`
+    fn reads_field(&self, field: &str) -> bool {
+        match self {
+            IrExpression::Constant(_) => false,
+            IrExpression::FieldAccess(name) => name == field,
+            IrExpression::Arithmetic { left, right, .. }
+            | IrExpression::Comparison { left, right, .. } => {
+                left.reads_field(field) || right.reads_field(field)
+            }
+            IrExpression::Not(inner) => inner.reads_field(field),
+        }
+    }
 }
 
 /// Arithmetic operations
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum IrArithmeticOp {
     Add,
     Subtract,
@@ -138,7 +835,7 @@ pub enum IrArithmeticOp {
 }
 
 /// Comparison operations
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum IrComparisonOp {
     Equal,
     NotEqual,
@@ -149,7 +846,7 @@ pub enum IrComparisonOp {
 }
 
 /// IR values
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum IrValue {
     Integer(i64),
     String(String),
@@ -157,6 +854,40 @@ pub enum IrValue {
     Coord(Coord),
 }
 
+impl IrValue {
+    /// The IR type of this value
+    pub fn type_of(&self) -> IrType {
+        match self {
+            IrValue::Integer(_) => IrType::Int,
+            IrValue::String(_) => IrType::String,
+            IrValue::Boolean(_) => IrType::Bool,
+            IrValue::Coord(_) => IrType::Coord,
+        }
+    }
+
+    /// The `i32` word this value occupies in the kernel, if it has one.
+    ///
+    /// The kernel has no boolean type: `true` is `1` and `false` is `0`, so
+    /// comparison and `Not` results reach it in that form.
+    pub fn to_kernel_word(&self) -> Option<i32> {
+        match self {
+            IrValue::Integer(i) => i32::try_from(*i).ok(),
+            IrValue::Boolean(b) => Some(i32::from(*b)),
+            IrValue::String(_) | IrValue::Coord(_) => None,
+        }
+    }
+
+    /// Whether the value gates a transition: `true` or a non-zero integer,
+    /// matching the kernel's 0/1 boolean encoding.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            IrValue::Boolean(b) => *b,
+            IrValue::Integer(i) => *i != 0,
+            IrValue::String(_) | IrValue::Coord(_) => false,
+        }
+    }
+}
+
 /// IR types
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum IrType {
@@ -166,8 +897,33 @@ pub enum IrType {
     Coord,
 }
 
+impl IrType {
+    /// Zero value a field of this type starts with when it has no initializer
+    pub fn default_value(&self) -> IrValue {
+        match self {
+            IrType::Int => IrValue::Integer(0),
+            IrType::String => IrValue::String(String::new()),
+            IrType::Bool => IrValue::Boolean(false),
+            IrType::Coord => IrValue::Coord(Coord::new(0, 0, 0)),
+        }
+    }
+
+    /// Fixed storage size of a value of this type; strings count at their
+    /// `STRING_FIELD_CAPACITY` cap. Taken from the front end's size table, so the
+    /// O(1) memory budget and the IR footprint agree.
+    pub fn size_bytes(&self) -> usize {
+        let source_type = match self {
+            IrType::Int => grey_lang::types::Type::Int,
+            IrType::String => grey_lang::types::Type::String,
+            IrType::Bool => grey_lang::types::Type::Bool,
+            IrType::Coord => grey_lang::types::Type::Coord,
+        };
+        source_type.static_size_bytes()
+    }
+}
+
 /// Resource bounds for O(1) memory validation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IrResourceBounds {
     pub max_processes: usize,
     pub max_events_per_tick: usize,
@@ -184,20 +940,165 @@ impl Default for IrResourceBounds {
     }
 }
 
-/// IR Builder for constructing programs from typed AST
-pub struct IrBuilder {
-    programs: HashMap<String, IrProgram>,
+/// Event ordering guarantees
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EventOrdering {
+    /// FIFO ordering within same timestamp
+    Fifo,
+    
+    /// Deterministic tie-breaking by coordinates
+    Deterministic,
 }
 
-impl IrBuilder {
-    pub fn new() -> Self {
+/// Pending events ordered by timestamp, then as `EventOrdering` dictates.
+///
+/// Ties within a timestamp go to the lower node id in `Deterministic` mode and
+/// to the earlier push in `Fifo` mode; equal keys always keep push order.
+pub struct EventQueue<T> {
+    ordering: EventOrdering,
+    tie_break_seed: Option<u64>,
+    heap: BinaryHeap<QueueEntry<T>>,
+    next_seq: u64,
+}
+
+struct QueueEntry<T> {
+    key: (u64, u64, u64),
+    payload: T,
+}
+
+impl<T> PartialEq for QueueEntry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<T> Eq for QueueEntry<T> {}
+
+impl<T> PartialOrd for QueueEntry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for QueueEntry<T> {
+    // Reversed so `BinaryHeap` pops the smallest key first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.cmp(&self.key)
+    }
+}
+
+impl<T> EventQueue<T> {
+    pub fn new(ordering: EventOrdering) -> Self {
         Self {
-            programs: HashMap::new(),
+            ordering,
+            tie_break_seed: None,
+            heap: BinaryHeap::new(),
+            next_seq: 0,
         }
     }
-    
-    /// Build IR from a typed Grey program
-    pub fn build_program(
+
+    /// Break `Deterministic` ties by a permutation of node ids drawn from `seed`
+    /// instead of by node id. Set it before pushing any events.
+    pub fn with_tie_break_seed(mut self, seed: u64) -> Self {
+        self.tie_break_seed = Some(seed);
+        self
+    }
+
+    /// Queue `payload` for delivery to `node_id` at `timestamp`
+    pub fn push(&mut self, timestamp: u64, node_id: usize, payload: T) {
+        let node_key = match (&self.ordering, self.tie_break_seed) {
+            (EventOrdering::Fifo, _) => 0,
+            (EventOrdering::Deterministic, None) => node_id as u64,
+            (EventOrdering::Deterministic, Some(seed)) => permute(node_id as u64 ^ seed),
+        };
+        self.heap.push(QueueEntry {
+            key: (timestamp, node_key, self.next_seq),
+            payload,
+        });
+        self.next_seq += 1;
+    }
+
+    /// Remove and return the next event to deliver
+    pub fn pop(&mut self) -> Option<T> {
+        self.heap.pop().map(|entry| entry.payload)
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+/// Levenshtein distance between two names, counted in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// SplitMix64 finalizer: a bijection on `u64` that scatters nearby inputs
+fn permute(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// IR Builder for constructing programs from typed AST
+pub struct IrBuilder {
+    programs: HashMap<String, IrProgram>,
+    warnings: Vec<IrWarning>,
+    /// Constants of the program being built, for resolving loop bounds
+    constants: HashMap<String, IrValue>,
+    warn_on_inert_processes: bool,
+    warn_on_defaulted_fields: bool,
+    duplicate_transitions: DuplicateTransitionPolicy,
+}
+
+impl IrBuilder {
+    pub fn new() -> Self {
+        Self {
+            programs: HashMap::new(),
+            warnings: Vec::new(),
+            constants: HashMap::new(),
+            warn_on_inert_processes: true,
+            warn_on_defaulted_fields: true,
+            duplicate_transitions: DuplicateTransitionPolicy::default(),
+        }
+    }
+
+    /// Choose how processes with several handlers for one event are normalized
+    pub fn set_duplicate_transition_policy(&mut self, policy: DuplicateTransitionPolicy) {
+        self.duplicate_transitions = policy;
+    }
+
+    /// Stop reporting processes that declare no event handlers
+    pub fn suppress_inert_process_warnings(&mut self) {
+        self.warn_on_inert_processes = false;
+    }
+
+    /// Stop reporting fields that `init()` leaves at their implicit default
+    pub fn suppress_defaulted_field_warnings(&mut self) {
+        self.warn_on_defaulted_fields = false;
+    }
+
+    /// Warnings collected by the most recent `build_program` call
+    pub fn warnings(&self) -> &[IrWarning] {
+        &self.warnings
+    }
+    
+    /// Build IR from a typed Grey program
+    pub fn build_program(
         &mut self,
         name: &str,
         typed_program: &grey_lang::types::TypedProgram,
@@ -209,30 +1110,152 @@ impl IrBuilder {
             constants: HashMap::new(),
             resources: IrResourceBounds::default(),
         };
+        self.warnings.clear();
+
+        // Build constants first so loop bounds in any module can refer to them
+        for module in &typed_program.modules {
+            for constant in &module.constants {
+                let value = self.build_constant(&constant.value)?;
+                program.constants.insert(constant.name.clone(), value);
+            }
+        }
+        self.constants = program.constants.clone();
         
         // Build events first
         for module in &typed_program.modules {
+            if module.constants.is_empty() && module.events.is_empty() && module.processes.is_empty() {
+                self.warnings.push(IrWarning::EmptyModule(module.name.clone()));
+            }
+
             for event in &module.events {
                 let ir_event = self.build_event(event)?;
+                if let Some(existing) = program.events.iter().find(|e| e.name == ir_event.name) {
+                    if existing.fields != ir_event.fields {
+                        return Err(IrError::EventFieldMismatch(format!(
+                            "event {} is declared more than once with different fields",
+                            ir_event.name
+                        )));
+                    }
+                    continue;
+                }
                 program.events.push(ir_event);
             }
             
             // Build processes
             for process in &module.processes {
-                let ir_process = self.build_process(process)?;
+                let mut ir_process = self.build_process(process)?;
+                ir_process.merge_duplicate_transitions(self.duplicate_transitions)?;
+                if self.warn_on_inert_processes && ir_process.transitions.is_empty() {
+                    self.warnings
+                        .push(IrWarning::ProcessWithoutTransitions(ir_process.name.clone()));
+                }
+                for transition in &ir_process.transitions {
+                    self.warnings.extend(transition.overwritten_fields().into_iter().map(|field| {
+                        IrWarning::OverwrittenField {
+                            process: ir_process.name.clone(),
+                            event: transition.event_type.clone(),
+                            field: field.to_string(),
+                        }
+                    }));
+                }
+                if self.warn_on_defaulted_fields {
+                    let assigned = init_assigned_fields(&process.methods);
+                    let mut defaulted: Vec<&String> = ir_process
+                        .fields
+                        .keys()
+                        .filter(|field| !assigned.contains(field.as_str()))
+                        .collect();
+                    defaulted.sort();
+                    self.warnings.extend(defaulted.into_iter().map(|field| IrWarning::DefaultedField {
+                        process: ir_process.name.clone(),
+                        field: field.clone(),
+                    }));
+                }
                 program.processes.push(ir_process);
             }
-            
-            // Build constants
-            for constant in &module.constants {
-                let value = self.build_constant(&constant.value)?;
-                program.constants.insert(constant.name.clone(), value);
-            }
         }
         
+        program.validate_event_sends()?;
+
         self.programs.insert(name.to_string(), program);
         Ok(self.programs.get(name).unwrap())
     }
+
+    /// Merge separately built programs into one, named after the first.
+    ///
+    /// Process names must be unique across programs. Events and constants may be
+    /// repeated only with identical definitions. Resource bounds take the minimum
+    /// of each limit, and cross-program event sends are validated on the result.
+    pub fn link(&mut self, programs: &[IrProgram]) -> Result<IrProgram> {
+        let (first, _) = programs
+            .split_first()
+            .ok_or_else(|| IrError::Link("no programs to link".to_string()))?;
+
+        let mut linked = IrProgram {
+            name: first.name.clone(),
+            processes: Vec::new(),
+            events: Vec::new(),
+            constants: HashMap::new(),
+            resources: first.resources.clone(),
+        };
+
+        for program in programs {
+            for process in &program.processes {
+                if linked.processes.iter().any(|p| p.name == process.name) {
+                    return Err(IrError::Link(format!(
+                        "process {} is defined in more than one program",
+                        process.name
+                    )));
+                }
+                linked.processes.push(process.clone());
+            }
+
+            for event in &program.events {
+                match linked.events.iter().find(|e| e.name == event.name) {
+                    Some(existing) if existing != event => {
+                        return Err(IrError::Link(format!(
+                            "event {} is declared with different fields",
+                            event.name
+                        )));
+                    }
+                    Some(_) => {}
+                    None => linked.events.push(event.clone()),
+                }
+            }
+
+            let mut names: Vec<_> = program.constants.keys().collect();
+            names.sort();
+            for name in names {
+                let value = &program.constants[name];
+                match linked.constants.get(name) {
+                    Some(existing) if existing != value => {
+                        return Err(IrError::Link(format!(
+                            "constant {} has conflicting values",
+                            name
+                        )));
+                    }
+                    Some(_) => {}
+                    None => {
+                        linked.constants.insert(name.clone(), value.clone());
+                    }
+                }
+            }
+
+            let bounds = &mut linked.resources;
+            bounds.max_processes = bounds.max_processes.min(program.resources.max_processes);
+            bounds.max_events_per_tick = bounds
+                .max_events_per_tick
+                .min(program.resources.max_events_per_tick);
+            bounds.max_coordinate_value = bounds
+                .max_coordinate_value
+                .min(program.resources.max_coordinate_value);
+        }
+
+        linked.validate_event_sends()?;
+
+        self.programs.insert(linked.name.clone(), linked.clone());
+        Ok(linked)
+    }
     
     fn build_event(&self, event: &grey_lang::types::TypedEventDefinition) -> Result<IrEvent> {
         let mut fields = HashMap::new();
@@ -258,7 +1281,8 @@ impl IrBuilder {
         let initial_state = self.extract_initial_state(&process.methods, &fields)?;
         
         // Extract transitions from handler methods
-        let transitions = self.extract_transitions(&process.methods)?;
+        let transitions = self.extract_transitions(&process.methods, &fields)?;
+        check_field_updates(&process.name, &fields, &transitions)?;
         
         Ok(IrProcess {
             name: process.name.clone(),
@@ -271,17 +1295,31 @@ impl IrBuilder {
     
     fn extract_initial_state(&self, methods: &[grey_lang::types::TypedFunctionDefinition], fields: &HashMap<String, IrType>) -> Result<IrState> {
         let mut values = HashMap::new();
+        let mut locals: HashMap<String, IrValue> = HashMap::new();
         
         // Look for init() method
         if let Some(init_method) = methods.iter().find(|m| m.name == "init") {
             // Extract initial values from init method body
             for statement in &init_method.body.statements {
                 if let grey_lang::types::TypedStatement::Let { pattern, value } = statement {
+                    let ir_value = match &value.expression {
+                        grey_lang::ast::Expression::Identifier(name) if locals.contains_key(name) => {
+                            locals[name].clone()
+                        }
+                        expression => self.expression_to_value(expression)?,
+                    };
                     match pattern {
-                        grey_lang::ast::Pattern::Identifier(field_name) => {
-                            let ir_value = self.expression_to_value(&value.expression)?;
+                        grey_lang::ast::Pattern::Field(field_name) => {
                             values.insert(field_name.clone(), ir_value);
                         }
+                        grey_lang::ast::Pattern::Identifier(name)
+                            if fields.contains_key(name) && !locals.contains_key(name) =>
+                        {
+                            values.insert(name.clone(), ir_value);
+                        }
+                        grey_lang::ast::Pattern::Identifier(name) => {
+                            locals.insert(name.clone(), ir_value);
+                        }
                     }
                 }
             }
@@ -290,28 +1328,31 @@ impl IrBuilder {
         // Initialize missing fields with sensible defaults
         for (field_name, field_type) in fields {
             if !values.contains_key(field_name) {
-                let default_value = match field_type {
-                    IrType::Int => IrValue::Integer(0),
-                    IrType::String => IrValue::String(String::new()),
-                    IrType::Bool => IrValue::Boolean(false),
-                    IrType::Coord => IrValue::Coord(Coord::new(0, 0, 0)),
-                };
-                values.insert(field_name.clone(), default_value);
+                values.insert(field_name.clone(), field_type.default_value());
             }
         }
         
         Ok(IrState { values })
     }
     
-    fn extract_transitions(&self, methods: &[grey_lang::types::TypedFunctionDefinition]) -> Result<Vec<IrTransition>> {
+    fn extract_transitions(
+        &self,
+        methods: &[grey_lang::types::TypedFunctionDefinition],
+        fields: &HashMap<String, IrType>,
+    ) -> Result<Vec<IrTransition>> {
         let mut transitions = Vec::new();
         
         for method in methods {
             // Handler methods typically start with "handle_"
             if method.name.starts_with("handle_") {
-                // Extract event type from method name (e.g., handle_shipment -> Shipment)
+                // The event parameter's type names the event; without one, derive it
+                // from the method name (e.g., handle_shipment -> Shipment)
                 let event_name_lower = method.name.strip_prefix("handle_").unwrap_or("");
-                let event_type = if !event_name_lower.is_empty() {
+                let event_type = if let Some(grey_lang::types::Type::Named(event)) =
+                    method.parameters.first().map(|p| &p.param_type)
+                {
+                    event.clone()
+                } else if !event_name_lower.is_empty() {
                     // Capitalize first letter
                     let mut chars = event_name_lower.chars();
                     match chars.next() {
@@ -325,12 +1366,14 @@ impl IrBuilder {
                 };
                 
                 // Extract actions from method body
-                let actions = self.extract_actions(&method.body.statements)?;
+                let mut scope = LocalScope::new(fields);
+                let actions = self.extract_actions(&method.body.statements, &mut scope)?;
                 
                 transitions.push(IrTransition {
                     event_type,
                     condition: None,
                     actions,
+                    priority: transitions.len() as i32,
                 });
             }
         }
@@ -338,103 +1381,247 @@ impl IrBuilder {
         Ok(transitions)
     }
     
-    fn extract_actions(&self, statements: &[grey_lang::types::TypedStatement]) -> Result<Vec<IrAction>> {
+    fn extract_actions(
+        &self,
+        statements: &[grey_lang::types::TypedStatement],
+        scope: &mut LocalScope,
+    ) -> Result<Vec<IrAction>> {
         let mut actions = Vec::new();
 
         for statement in statements {
             match statement {
-                grey_lang::types::TypedStatement::Let { pattern, value } => match pattern {
-                    grey_lang::ast::Pattern::Identifier(field_name) => {
-                        let expr = self.expression_to_ir_expression(&value.expression)?;
-                        actions.push(IrAction::UpdateField {
-                            field: field_name.clone(),
-                            value: expr,
-                        });
+                grey_lang::types::TypedStatement::Let { pattern, value } => {
+                    actions.extend(self.lower_assignment(pattern, &value.expression, scope)?);
+                }
+                grey_lang::types::TypedStatement::Expression(expr) => match &expr.expression {
+                    grey_lang::ast::Expression::Block { statements: inner } => {
+                        actions.extend(self.extract_nested_actions(inner, scope)?);
                     }
-                },
-                grey_lang::types::TypedStatement::Expression(expr) => {
-                    if let grey_lang::ast::Expression::Block { statements: inner } = &expr.expression {
-                        actions.extend(self.extract_actions_from_ast(inner)?);
+                    grey_lang::ast::Expression::If { .. } => {
+                        actions.push(IrAction::Branch { arms: self.branch_arms(&expr.expression, scope)? });
                     }
-                }
+                    _ => {}
+                },
                 grey_lang::types::TypedStatement::Return(_) => {}
+                grey_lang::types::TypedStatement::For { start, end, body, .. } => {
+                    let count = self.static_loop_count(&start.expression, &end.expression)?;
+                    let body = self.extract_loop_body(body, scope, Self::extract_actions)?;
+                    actions.push(IrAction::Repeat { count, body });
+                }
             }
         }
 
         Ok(actions)
     }
 
-    fn extract_actions_from_ast(&self, statements: &[grey_lang::ast::Statement]) -> Result<Vec<IrAction>> {
+    fn extract_actions_from_ast(
+        &self,
+        statements: &[grey_lang::ast::Statement],
+        scope: &mut LocalScope,
+    ) -> Result<Vec<IrAction>> {
         let mut actions = Vec::new();
 
         for statement in statements {
             match statement {
-                grey_lang::ast::Statement::Let { pattern, value } => match pattern {
-                    grey_lang::ast::Pattern::Identifier(field_name) => {
-                        let expr = self.expression_to_ir_expression(value)?;
-                        actions.push(IrAction::UpdateField {
-                            field: field_name.clone(),
-                            value: expr,
-                        });
+                grey_lang::ast::Statement::Let { pattern, value } => {
+                    actions.extend(self.lower_assignment(pattern, value, scope)?);
+                }
+                grey_lang::ast::Statement::Expression(expr) => match expr {
+                    grey_lang::ast::Expression::Block { statements: inner } => {
+                        actions.extend(self.extract_nested_actions(inner, scope)?);
                     }
-                },
-                grey_lang::ast::Statement::Expression(expr) => {
-                    if let grey_lang::ast::Expression::Block { statements: inner } = expr {
-                        actions.extend(self.extract_actions_from_ast(inner)?);
+                    grey_lang::ast::Expression::If { .. } => {
+                        actions.push(IrAction::Branch { arms: self.branch_arms(expr, scope)? });
                     }
-                }
+                    _ => {}
+                },
                 grey_lang::ast::Statement::Return(_) => {}
+                grey_lang::ast::Statement::For { start, end, body, .. } => {
+                    let count = self.static_loop_count(start, end)?;
+                    let body = self.extract_loop_body(body, scope, Self::extract_actions_from_ast)?;
+                    actions.push(IrAction::Repeat { count, body });
+                }
             }
         }
 
         Ok(actions)
     }
+
+    /// Actions of a block, branch or loop body, whose local bindings go out of scope at its end
+    fn extract_nested_actions(
+        &self,
+        statements: &[grey_lang::ast::Statement],
+        scope: &mut LocalScope,
+    ) -> Result<Vec<IrAction>> {
+        let mut inner = scope.nested();
+        let actions = self.extract_actions_from_ast(statements, &mut inner)?;
+        scope.absorb(inner);
+        Ok(actions)
+    }
+
+    /// Actions of a loop body. A field assigned anywhere in the body changes what
+    /// an enclosing local reading it evaluates to on the next iteration, so the
+    /// body is lowered once to find those fields and again with them marked stale.
+    fn extract_loop_body<S>(
+        &self,
+        body: &[S],
+        scope: &mut LocalScope,
+        extract: impl Fn(&Self, &[S], &mut LocalScope) -> Result<Vec<IrAction>>,
+    ) -> Result<Vec<IrAction>> {
+        let mut probe = scope.nested();
+        extract(self, body, &mut probe)?;
+
+        let mut inner = scope.nested();
+        inner.stale.extend(probe.stale.into_iter().filter(|(name, _)| scope.bindings.contains_key(name)));
+        let actions = extract(self, body, &mut inner)?;
+        scope.absorb(inner);
+        Ok(actions)
+    }
+
+    /// Lower `target = value`. Assignments to `this.field` and to a bare name that
+    /// is a declared field (and not a local) update the field; any other name is
+    /// bound as a local, which emits no action.
+    fn lower_assignment(
+        &self,
+        pattern: &grey_lang::ast::Pattern,
+        value: &grey_lang::ast::Expression,
+        scope: &mut LocalScope,
+    ) -> Result<Option<IrAction>> {
+        let value = self.expression_to_ir_expression(value, scope)?;
+        let field = match pattern {
+            grey_lang::ast::Pattern::Field(field) => field,
+            grey_lang::ast::Pattern::Identifier(name) if scope.is_field(name) => name,
+            grey_lang::ast::Pattern::Identifier(name) => {
+                scope.bind(name, value)?;
+                return Ok(None);
+            }
+        };
+
+        scope.field_updated(field);
+        Ok(Some(IrAction::UpdateField { field: field.clone(), value }))
+    }
+
+    /// Arms of an `if`/`else if`/`else` chain, one per condition in source order,
+    /// plus a `true`-guarded arm for a trailing `else`.
+    fn branch_arms(&self, expr: &grey_lang::ast::Expression, scope: &mut LocalScope) -> Result<Vec<IrBranchArm>> {
+        let mut arms = Vec::new();
+        let mut next = Some(expr);
+
+        while let Some(grey_lang::ast::Expression::If { condition, then_branch, else_branch }) = next {
+            arms.push(IrBranchArm {
+                guard: self.expression_to_ir_expression(condition, scope)?,
+                actions: self.extract_nested_actions(then_branch, scope)?,
+            });
+
+            next = match else_branch.as_slice() {
+                [grey_lang::ast::Statement::Expression(nested @ grey_lang::ast::Expression::If { .. })] => Some(nested),
+                [] => None,
+                statements => {
+                    arms.push(IrBranchArm {
+                        guard: IrExpression::Constant(IrValue::Boolean(true)),
+                        actions: self.extract_nested_actions(statements, scope)?,
+                    });
+                    None
+                }
+            };
+        }
+
+        Ok(arms)
+    }
+
+    /// Iteration count of `for _ in start..end`, whose bounds must be integer
+    /// literals or integer constants. An empty or reversed range runs zero times.
+    fn static_loop_count(&self, start: &grey_lang::ast::Expression, end: &grey_lang::ast::Expression) -> Result<u32> {
+        let bound = |expr: &grey_lang::ast::Expression| match expr {
+            grey_lang::ast::Expression::Integer(i) => Ok(*i),
+            grey_lang::ast::Expression::Identifier(name) => match self.constants.get(name) {
+                Some(IrValue::Integer(i)) => Ok(*i),
+                _ => Err(IrError::UnboundedLoop(name.clone())),
+            },
+            other => Err(IrError::UnboundedLoop(format!("{:?}", other))),
+        };
+
+        let count = bound(end)?.saturating_sub(bound(start)?).max(0);
+        u32::try_from(count).map_err(|_| IrError::UnboundedLoop(format!("{} iterations", count)))
+    }
     
+    /// Static value of an initializer. Identifiers naming a module constant
+    /// resolve to that constant's value.
     fn expression_to_value(&self, expr: &grey_lang::ast::Expression) -> Result<IrValue> {
         match expr {
             grey_lang::ast::Expression::Integer(i) => Ok(IrValue::Integer(*i)),
             grey_lang::ast::Expression::Boolean(b) => Ok(IrValue::Boolean(*b)),
             grey_lang::ast::Expression::String(s) => Ok(IrValue::String(s.clone())),
-            grey_lang::ast::Expression::CoordLiteral => Ok(IrValue::Coord(Coord::new(0, 0, 0))),
+            grey_lang::ast::Expression::CoordLiteral { x, y, z } => Ok(IrValue::Coord(Coord::new(*x, *y, *z))),
+            grey_lang::ast::Expression::Identifier(name) if self.constants.contains_key(name) => {
+                Ok(self.constants[name].clone())
+            }
             _ => Ok(IrValue::Integer(0)),
         }
     }
 
-    fn expression_to_ir_expression(&self, expr: &grey_lang::ast::Expression) -> Result<IrExpression> {
+    /// Lower `expr` with the locals bound in `scope` substituted for their uses
+    fn expression_to_ir_expression(&self, expr: &grey_lang::ast::Expression, scope: &LocalScope) -> Result<IrExpression> {
+        self.lower_expression(expr, scope, 1)
+    }
+
+    /// Lower `expr`, found at `depth` in its expression tree (the root is 1)
+    fn lower_expression(
+        &self,
+        expr: &grey_lang::ast::Expression,
+        scope: &LocalScope,
+        depth: usize,
+    ) -> Result<IrExpression> {
+        if depth > MAX_EXPRESSION_DEPTH {
+            return Err(IrError::ExpressionTooDeep(MAX_EXPRESSION_DEPTH));
+        }
+
+        let arithmetic = |op, left, right| -> Result<IrExpression> {
+            Ok(IrExpression::Arithmetic {
+                op,
+                left: Box::new(self.lower_expression(left, scope, depth + 1)?),
+                right: Box::new(self.lower_expression(right, scope, depth + 1)?),
+            })
+        };
+
         match expr {
             grey_lang::ast::Expression::Integer(i) => Ok(IrExpression::Constant(IrValue::Integer(*i))),
             grey_lang::ast::Expression::Boolean(b) => Ok(IrExpression::Constant(IrValue::Boolean(*b))),
             grey_lang::ast::Expression::String(s) => Ok(IrExpression::Constant(IrValue::String(s.clone()))),
-            grey_lang::ast::Expression::Identifier(name) => Ok(IrExpression::FieldAccess(name.clone())),
-            grey_lang::ast::Expression::Add { left, right } => Ok(IrExpression::Arithmetic {
-                op: IrArithmeticOp::Add,
-                left: Box::new(self.expression_to_ir_expression(left)?),
-                right: Box::new(self.expression_to_ir_expression(right)?),
-            }),
-            grey_lang::ast::Expression::Subtract { left, right } => Ok(IrExpression::Arithmetic {
-                op: IrArithmeticOp::Subtract,
-                left: Box::new(self.expression_to_ir_expression(left)?),
-                right: Box::new(self.expression_to_ir_expression(right)?),
-            }),
-            grey_lang::ast::Expression::Multiply { left, right } => Ok(IrExpression::Arithmetic {
-                op: IrArithmeticOp::Multiply,
-                left: Box::new(self.expression_to_ir_expression(left)?),
-                right: Box::new(self.expression_to_ir_expression(right)?),
-            }),
-            grey_lang::ast::Expression::Divide { left, right } => Ok(IrExpression::Arithmetic {
-                op: IrArithmeticOp::Divide,
-                left: Box::new(self.expression_to_ir_expression(left)?),
-                right: Box::new(self.expression_to_ir_expression(right)?),
-            }),
-            grey_lang::ast::Expression::CoordLiteral => Ok(IrExpression::Constant(IrValue::Coord(Coord::new(0, 0, 0)))),
-            _ => Ok(IrExpression::Constant(IrValue::Integer(0))),
+            grey_lang::ast::Expression::Identifier(name) => match scope.lookup(name)? {
+                Some(bound) if depth + bound.depth() - 1 > MAX_EXPRESSION_DEPTH => {
+                    Err(IrError::ExpressionTooDeep(MAX_EXPRESSION_DEPTH))
+                }
+                Some(bound) => Ok(bound.clone()),
+                None => Ok(IrExpression::FieldAccess(name.clone())),
+            },
+            grey_lang::ast::Expression::Add { left, right } => arithmetic(IrArithmeticOp::Add, left, right),
+            grey_lang::ast::Expression::Subtract { left, right } => {
+                arithmetic(IrArithmeticOp::Subtract, left, right)
+            }
+            grey_lang::ast::Expression::Multiply { left, right } => {
+                arithmetic(IrArithmeticOp::Multiply, left, right)
+            }
+            grey_lang::ast::Expression::Divide { left, right } => arithmetic(IrArithmeticOp::Divide, left, right),
+            grey_lang::ast::Expression::CoordLiteral { x, y, z } => {
+                Ok(IrExpression::Constant(IrValue::Coord(Coord::new(*x, *y, *z))))
+            }
+            grey_lang::ast::Expression::Not(operand) => {
+                Ok(IrExpression::Not(Box::new(self.lower_expression(operand, scope, depth + 1)?)))
+            }
+            other => Err(IrError::UnsupportedExpression(format!("{:?}", other))),
         }
     }
     
+    /// Value of a module constant. Its type must be one the IR represents, and
+    /// its initializer a literal of that type.
     fn build_constant(&self, expr: &grey_lang::types::TypedExpression) -> Result<IrValue> {
+        self.convert_type(&expr.type_)?;
         match &expr.expression {
             grey_lang::ast::Expression::Integer(i) => Ok(IrValue::Integer(*i)),
+            grey_lang::ast::Expression::Boolean(b) => Ok(IrValue::Boolean(*b)),
             grey_lang::ast::Expression::String(s) => Ok(IrValue::String(s.clone())),
+            grey_lang::ast::Expression::CoordLiteral { x, y, z } => Ok(IrValue::Coord(Coord::new(*x, *y, *z))),
             _ => Err(IrError::TypeMismatch("Unsupported constant type".to_string())),
         }
     }
@@ -445,11 +1632,262 @@ impl IrBuilder {
             grey_lang::types::Type::String => Ok(IrType::String),
             grey_lang::types::Type::Bool => Ok(IrType::Bool),
             grey_lang::types::Type::Coord => Ok(IrType::Coord),
+            grey_lang::types::Type::Array(..) => Err(IrError::TypeMismatch(format!(
+                "Array type {} is not yet supported by the IR",
+                ty.type_name()
+            ))),
             _ => Err(IrError::TypeMismatch(format!("Unsupported type: {:?}", ty))),
         }
     }
 }
 
+/// Local bindings visible while lowering a handler body.
+///
+/// The IR has no locals, so each use of one is replaced by the expression it was
+/// bound to. A binding whose expression reads a field that is assigned after it
+/// was bound would then see the new value, so using it afterwards is an error.
+#[derive(Debug, Clone, Default)]
+struct LocalScope {
+    fields: HashSet<String>,
+    bindings: HashMap<String, IrExpression>,
+    /// Bindings from enclosing scopes, which a branch or loop body may read but not rebind
+    enclosing: HashSet<String>,
+    /// Bindings invalidated by a later assignment to a field they read, with that field
+    stale: HashMap<String, String>,
+}
+
+impl LocalScope {
+    fn new(fields: &HashMap<String, IrType>) -> Self {
+        Self { fields: fields.keys().cloned().collect(), ..Self::default() }
+    }
+
+    /// Scope for a block, branch or loop body nested in this one
+    fn nested(&self) -> Self {
+        Self { enclosing: self.bindings.keys().cloned().collect(), ..self.clone() }
+    }
+
+    /// Carry over what a nested scope learned about enclosing bindings: a field it
+    /// may have assigned invalidates them here as well.
+    fn absorb(&mut self, inner: LocalScope) {
+        for (name, field) in inner.stale {
+            if self.bindings.contains_key(&name) {
+                self.stale.entry(name).or_insert(field);
+            }
+        }
+    }
+
+    /// Whether an assignment to bare `name` updates a field rather than a local
+    fn is_field(&self, name: &str) -> bool {
+        self.fields.contains(name) && !self.bindings.contains_key(name)
+    }
+
+    fn bind(&mut self, name: &str, value: IrExpression) -> Result<()> {
+        if self.enclosing.contains(name) {
+            return Err(IrError::UnsupportedExpression(format!(
+                "local {} is reassigned inside a branch or loop",
+                name
+            )));
+        }
+        self.stale.remove(name);
+        self.bindings.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    fn field_updated(&mut self, field: &str) {
+        for (name, value) in &self.bindings {
+            if value.reads_field(field) {
+                self.stale.entry(name.clone()).or_insert_with(|| field.to_string());
+            }
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<Option<&IrExpression>> {
+        if let Some(field) = self.stale.get(name) {
+            return Err(IrError::UnsupportedExpression(format!(
+                "local {} reads field {}, which is assigned after {} is bound",
+                name, field, name
+            )));
+        }
+        Ok(self.bindings.get(name))
+    }
+}
+
+/// Check that every field a transition updates is declared and assigned a value of its type
+fn check_field_updates(
+    process: &str,
+    fields: &HashMap<String, IrType>,
+    transitions: &[IrTransition],
+) -> Result<()> {
+    for transition in transitions {
+        for action in transition.flattened_actions() {
+            if let IrAction::UpdateField { field, value } = action {
+                let Some(expected) = fields.get(field) else {
+                    return Err(IrError::UnknownField {
+                        process: process.to_string(),
+                        event: transition.event_type.clone(),
+                        field: field.clone(),
+                    });
+                };
+
+                if let Some(actual) = value.ir_type(fields) {
+                    if &actual != expected {
+                        return Err(IrError::TypeMismatch(format!(
+                            "{} handler for {} assigns {:?} to {:?} field {}",
+                            process, transition.event_type, actual, expected, field
+                        )));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fields that `init()` assigns at its top level, which is what
+/// `extract_initial_state` takes initial values from.
+fn init_assigned_fields(methods: &[grey_lang::types::TypedFunctionDefinition]) -> HashSet<&str> {
+    methods
+        .iter()
+        .filter(|m| m.name == "init")
+        .flat_map(|m| &m.body.statements)
+        .filter_map(|statement| match statement {
+            grey_lang::types::TypedStatement::Let {
+                pattern: grey_lang::ast::Pattern::Identifier(field) | grey_lang::ast::Pattern::Field(field),
+                ..
+            } => Some(field.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+impl Default for IrBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fluent construction of an `IrProgram` without going through the source pipeline.
+///
+/// `field` and `transition` apply to the most recently added process. Fields start
+/// at their type's default value. `build` runs the same checks source lowering
+/// does, plus placement and resource bounds.
+pub struct IrProgramBuilder {
+    program: IrProgram,
+    misuse: Option<IrError>,
+}
+
+impl IrProgramBuilder {
+    pub fn new(name: &str) -> Self {
+        Self {
+            program: IrProgram {
+                name: name.to_string(),
+                processes: Vec::new(),
+                events: Vec::new(),
+                constants: HashMap::new(),
+                resources: IrResourceBounds::default(),
+            },
+            misuse: None,
+        }
+    }
+
+    /// Add a process at `coord` with no fields or transitions
+    pub fn process(mut self, name: &str, coord: Coord) -> Self {
+        self.program.processes.push(IrProcess {
+            name: name.to_string(),
+            coord,
+            fields: HashMap::new(),
+            initial_state: IrState { values: HashMap::new() },
+            transitions: Vec::new(),
+        });
+        self
+    }
+
+    /// Declare a field on the current process, initialized to the type's default
+    pub fn field(mut self, name: &str, ty: IrType) -> Self {
+        match self.program.processes.last_mut() {
+            Some(process) => {
+                process.initial_state.values.insert(name.to_string(), ty.default_value());
+                process.fields.insert(name.to_string(), ty);
+            }
+            None => self.record_misuse(format!("field {} declared before any process", name)),
+        }
+        self
+    }
+
+    /// Declare an event type with the given fields
+    pub fn event(mut self, name: &str, fields: &[(&str, IrType)]) -> Self {
+        self.program.events.push(IrEvent {
+            name: name.to_string(),
+            fields: fields
+                .iter()
+                .map(|(field, ty)| (field.to_string(), ty.clone()))
+                .collect(),
+        });
+        self
+    }
+
+    /// Add an unconditional transition for `event` to the current process
+    pub fn transition(mut self, event: &str, actions: Vec<IrAction>) -> Self {
+        match self.program.processes.last_mut() {
+            Some(process) => process.transitions.push(IrTransition {
+                event_type: event.to_string(),
+                condition: None,
+                actions,
+                priority: 0,
+            }),
+            None => self.record_misuse(format!("transition for {} declared before any process", event)),
+        }
+        self
+    }
+
+    fn record_misuse(&mut self, message: String) {
+        self.misuse.get_or_insert(IrError::ProcessNotFound(message));
+    }
+
+    /// Validate and return the program
+    pub fn build(self) -> Result<IrProgram> {
+        if let Some(err) = self.misuse {
+            return Err(err);
+        }
+        let program = self.program;
+        let bounds = &program.resources;
+
+        if program.processes.len() > bounds.max_processes {
+            return Err(IrError::ResourceConstraint(format!(
+                "{} processes exceed the limit of {}",
+                program.processes.len(),
+                bounds.max_processes
+            )));
+        }
+
+        let mut names = HashSet::new();
+        for process in &program.processes {
+            if !names.insert(process.name.as_str()) {
+                return Err(IrError::DuplicateProcess(process.name.clone()));
+            }
+            if !process.coord.is_valid_within(bounds.max_coordinate_value) {
+                return Err(IrError::InvalidCoordinate(format!(
+                    "process {} at {:?} is outside 0..={}",
+                    process.name, process.coord, bounds.max_coordinate_value
+                )));
+            }
+            for event in process.handled_events() {
+                if !program.events.iter().any(|e| e.name == event) {
+                    return Err(IrError::EventNotFound(event.to_string()));
+                }
+            }
+            check_field_updates(&process.name, &process.fields, &process.transitions)?;
+        }
+
+        if program.complexity().max_expression_depth > MAX_EXPRESSION_DEPTH {
+            return Err(IrError::ExpressionTooDeep(MAX_EXPRESSION_DEPTH));
+        }
+        program.validate_event_sends()?;
+
+        Ok(program)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -463,10 +1901,1020 @@ mod tests {
         assert!(!invalid.is_valid());
     }
     
+    #[test]
+    fn test_value_type_of() {
+        assert_eq!(IrValue::Integer(7).type_of(), IrType::Int);
+        assert_eq!(IrValue::String("s".to_string()).type_of(), IrType::String);
+        assert_eq!(IrValue::Boolean(true).type_of(), IrType::Bool);
+        assert_eq!(IrValue::Coord(Coord::new(1, 2, 3)).type_of(), IrType::Coord);
+
+        for ty in [IrType::Int, IrType::String, IrType::Bool, IrType::Coord] {
+            assert_eq!(ty.default_value().type_of(), ty);
+        }
+    }
+
+    #[test]
+    fn test_process_footprint_sums_field_sizes() {
+        let program = IrProgramBuilder::new("sized")
+            .process("Node", Coord::new(0, 0, 0))
+            .field("count", IrType::Int)
+            .field("active", IrType::Bool)
+            .field("home", IrType::Coord)
+            .field("label", IrType::String)
+            .process("Empty", Coord::new(1, 0, 0))
+            .build()
+            .unwrap();
+
+        assert_eq!(program.processes[0].footprint_bytes(), 8 + 1 + 12 + STRING_FIELD_CAPACITY);
+        assert_eq!(program.processes[1].footprint_bytes(), 0);
+    }
+
+    #[test]
+    fn test_footprint_matches_front_end_memory_budget() {
+        let source = "module M { process Node { count: Int, alive: Bool, home: Coord, name: String } }";
+        let typed = grey_lang::compile(source).unwrap();
+        let mut builder = IrBuilder::new();
+        let footprint = builder.build_program("sizes", &typed).unwrap().processes[0].footprint_bytes();
+
+        let validator = |budget| grey_lang::constraints::O1Validator::new().with_max_process_bytes(budget);
+        assert!(validator(footprint).validate_program(&typed).is_ok());
+        assert!(validator(footprint - 1).validate_program(&typed).is_err());
+    }
+
+    #[test]
+    fn test_interior_neighbors() {
+        let neighbors = Coord::new(5, 5, 5).neighbors(32);
+
+        assert_eq!(
+            neighbors,
+            vec![
+                Coord::new(4, 5, 5),
+                Coord::new(6, 5, 5),
+                Coord::new(5, 4, 5),
+                Coord::new(5, 6, 5),
+                Coord::new(5, 5, 4),
+                Coord::new(5, 5, 6),
+            ]
+        );
+        assert_eq!(Coord::new(5, 5, 5).moore_neighbors(32).len(), 26);
+    }
+
+    #[test]
+    fn test_edge_neighbors_wrap_around() {
+        let neighbors = Coord::new(0, 31, 0).neighbors(32);
+
+        assert!(neighbors.contains(&Coord::new(31, 31, 0)));
+        assert!(neighbors.contains(&Coord::new(0, 0, 0)));
+        assert!(neighbors.contains(&Coord::new(0, 31, 31)));
+        assert!(neighbors.iter().all(Coord::is_valid));
+
+        let moore = Coord::new(31, 31, 31).moore_neighbors(32);
+        assert!(moore.contains(&Coord::new(0, 0, 0)));
+        assert_eq!(moore.len(), 26);
+    }
+
+    #[test]
+    fn test_node_id_round_trips_over_lattice() {
+        let mut seen = HashSet::new();
+        for x in -32..64 {
+            for y in [-1, 0, 17, 31, 32] {
+                for z in 0..32 {
+                    let coord = Coord::new(x, y, z);
+                    assert_eq!(Coord::from_node_id(coord.to_node_id()), coord.wrapped());
+                }
+            }
+        }
+
+        for id in 0..32 * 32 * 32 {
+            let coord = Coord::from_node_id(id);
+            assert!(coord.is_valid());
+            assert_eq!(coord.to_node_id(), id);
+            assert!(seen.insert(coord));
+        }
+        assert_eq!(Coord::new(1, 2, 3).to_node_id(), 1024 + 64 + 3);
+    }
+
+    #[test]
+    fn test_neighbors_deduplicated_on_small_torus() {
+        assert_eq!(Coord::new(0, 0, 0).neighbors(2).len(), 3);
+        assert!(Coord::new(0, 0, 0).neighbors(1).is_empty());
+    }
+
+    #[test]
+    fn test_same_source_builds_equal_ir() {
+        let source = r#"
+            module Counter {
+                const LIMIT = 10;
+                event Tick { amount: Int }
+                process Node {
+                    count: Int,
+                    method init() {
+                        this.count = 0;
+                    }
+                    method handle_tick(event: Tick) {
+                        this.count = this.count + 1;
+                    }
+                }
+            }
+        "#;
+        let build = || {
+            let typed = grey_lang::compile(source).unwrap();
+            IrBuilder::new().build_program("counter", &typed).unwrap().clone()
+        };
+
+        let (first, second) = (build(), build());
+
+        assert_eq!(first, second);
+    }
+
     #[test]
     fn test_ir_builder() {
         let builder = IrBuilder::new();
         // Basic builder construction test
         assert_eq!(builder.programs.len(), 0);
     }
-}
\ No newline at end of file
+
+    const INERT_PROCESS_SOURCE: &str = r#"
+        module Inert {
+            process Idle {
+                count: Int,
+                method init() {
+                    this.count = 0;
+                }
+            }
+        }
+    "#;
+
+    fn send_program(fields: HashMap<String, IrExpression>) -> IrProgram {
+        let mut event_fields = HashMap::new();
+        event_fields.insert("strain".to_string(), IrType::Int);
+        event_fields.insert("severity".to_string(), IrType::Int);
+
+        IrProgram {
+            name: "send".to_string(),
+            processes: vec![IrProcess {
+                name: "Person".to_string(),
+                coord: Coord::new(0, 0, 0),
+                fields: HashMap::new(),
+                initial_state: IrState { values: HashMap::new() },
+                transitions: vec![IrTransition {
+                    event_type: "Infection".to_string(),
+                    condition: None,
+                    priority: 0,
+                    actions: vec![IrAction::SendEvent {
+                        event_type: "Infection".to_string(),
+                        target: Coord::new(1, 0, 0).into(),
+                        fields,
+                    }],
+                }],
+            }],
+            events: vec![IrEvent {
+                name: "Infection".to_string(),
+                fields: event_fields,
+            }],
+            constants: HashMap::new(),
+            resources: IrResourceBounds::default(),
+        }
+    }
+
+    #[test]
+    fn test_send_event_with_all_fields_validates() {
+        let mut fields = HashMap::new();
+        fields.insert("strain".to_string(), IrExpression::Constant(IrValue::Integer(1)));
+        fields.insert("severity".to_string(), IrExpression::FieldAccess("severity".to_string()));
+
+        assert!(send_program(fields).validate_event_sends().is_ok());
+    }
+
+    #[test]
+    fn test_send_event_missing_field_errors() {
+        let mut fields = HashMap::new();
+        fields.insert("strain".to_string(), IrExpression::Constant(IrValue::Integer(1)));
+
+        let err = send_program(fields).validate_event_sends().unwrap_err();
+        assert!(matches!(err, IrError::EventFieldMismatch(_)));
+        assert!(err.to_string().contains("'severity'"));
+    }
+
+    #[test]
+    fn test_send_event_field_type_mismatch_errors() {
+        let mut fields = HashMap::new();
+        fields.insert("strain".to_string(), IrExpression::Constant(IrValue::Boolean(true)));
+        fields.insert("severity".to_string(), IrExpression::Constant(IrValue::Integer(2)));
+
+        let err = send_program(fields).validate_event_sends().unwrap_err();
+        assert!(err.to_string().contains("Infection.strain"));
+    }
+
+    #[test]
+    fn test_send_event_misspelled_field_errors() {
+        let send = IrAction::SendEvent {
+            event_type: "Move".to_string(),
+            target: Coord::new(1, 0, 0).into(),
+            fields: HashMap::from([
+                ("destintaion".to_string(), IrExpression::Constant(IrValue::Coord(Coord::new(2, 0, 0)))),
+                ("speed".to_string(), IrExpression::Constant(IrValue::Integer(1))),
+            ]),
+        };
+
+        let err = IrProgramBuilder::new("typo")
+            .event("Start", &[])
+            .event("Move", &[("destination", IrType::Coord), ("speed", IrType::Int)])
+            .process("Mover", Coord::new(0, 0, 0))
+            .transition("Start", vec![send])
+            .build()
+            .unwrap_err();
+
+        assert!(matches!(err, IrError::EventFieldMismatch(_)));
+        assert_eq!(
+            err.to_string(),
+            "Event field mismatch: Mover sends Move with undeclared field 'destintaion'; did you mean 'destination'?"
+        );
+    }
+
+    fn relay_process(name: &str, handles: &str, sends: Option<&str>) -> IrProcess {
+        let actions = sends
+            .map(|event_type| IrAction::SendEvent {
+                event_type: event_type.to_string(),
+                target: Coord::new(0, 0, 0).into(),
+                fields: HashMap::new(),
+            })
+            .into_iter()
+            .collect();
+
+        IrProcess {
+            name: name.to_string(),
+            coord: Coord::new(0, 0, 0),
+            fields: HashMap::new(),
+            initial_state: IrState { values: HashMap::new() },
+            transitions: vec![IrTransition {
+                event_type: handles.to_string(),
+                condition: None,
+                priority: 0,
+                actions,
+            }],
+        }
+    }
+
+    fn relay_program(processes: Vec<IrProcess>) -> IrProgram {
+        IrProgram {
+            name: "relay".to_string(),
+            processes,
+            events: Vec::new(),
+            constants: HashMap::new(),
+            resources: IrResourceBounds::default(),
+        }
+    }
+
+    #[test]
+    fn test_remap_coords_translates_processes_and_optionally_targets() {
+        let mut producer = relay_process("Producer", "Start", Some("Ping"));
+        producer.coord = Coord::new(1, 0, 0);
+        let IrAction::SendEvent { target, .. } = &mut producer.transitions[0].actions[0] else {
+            panic!("expected a send");
+        };
+        *target = Coord::new(2, 0, 0).into();
+        let mut consumer = relay_process("Consumer", "Ping", None);
+        consumer.coord = Coord::new(2, 0, 0);
+        let original = relay_program(vec![producer, consumer]);
+
+        let shift = |_: &str, c: Coord| Coord::new(c.x + 3, c.y, c.z + 1);
+        let target_of = |program: &IrProgram| match &program.processes[0].transitions[0].actions[0] {
+            IrAction::SendEvent { target, .. } => target.clone(),
+            other => panic!("expected a send, got {:?}", other),
+        };
+
+        let mut program = original.clone();
+        program.remap_coords(shift).unwrap();
+        let coords: Vec<_> = program.processes.iter().map(|p| p.coord.clone()).collect();
+        assert_eq!(coords, vec![Coord::new(4, 0, 1), Coord::new(5, 0, 1)]);
+        assert!(coords.iter().all(|c| c.is_valid_within(program.resources.max_coordinate_value)));
+        assert_eq!(target_of(&program), Coord::new(2, 0, 0).into());
+
+        let mut program = original.clone();
+        program.remap_coords_and_targets(shift).unwrap();
+        assert_eq!(target_of(&program), Coord::new(5, 0, 1).into());
+
+        let mut program = original.clone();
+        let err = program.remap_coords(|_, c| Coord::new(c.x + 30, c.y, c.z)).unwrap_err();
+        assert!(matches!(err, IrError::InvalidCoordinate(ref msg) if msg.contains("process Consumer")));
+        assert_eq!(program, original);
+    }
+
+    #[test]
+    fn test_program_builder_produces_validated_program() {
+        let ping = IrAction::SendEvent {
+            event_type: "Ping".to_string(),
+            target: Coord::new(1, 0, 0).into(),
+            fields: HashMap::from([("hops".to_string(), IrExpression::Constant(IrValue::Integer(1)))]),
+        };
+        let count = IrAction::UpdateField {
+            field: "received".to_string(),
+            value: IrExpression::Arithmetic {
+                op: IrArithmeticOp::Add,
+                left: Box::new(IrExpression::FieldAccess("received".to_string())),
+                right: Box::new(IrExpression::Constant(IrValue::Integer(1))),
+            },
+        };
+
+        let builder = || {
+            IrProgramBuilder::new("pair")
+                .event("Start", &[])
+                .event("Ping", &[("hops", IrType::Int)])
+                .process("Sender", Coord::new(0, 0, 0))
+                .transition("Start", vec![ping.clone()])
+                .process("Receiver", Coord::new(1, 0, 0))
+                .field("received", IrType::Int)
+                .transition("Ping", vec![count.clone()])
+        };
+
+        let program = builder().build().unwrap();
+        assert_eq!(program.processes.len(), 2);
+        assert_eq!(program.processes_handling("Ping")[0].name, "Receiver");
+        assert_eq!(program.processes[1].initial_state.values["received"], IrValue::Integer(0));
+        assert!(program.validate_event_sends().is_ok());
+
+        let err = builder().process("Sender", Coord::new(2, 0, 0)).build().unwrap_err();
+        assert!(matches!(err, IrError::DuplicateProcess(ref name) if name == "Sender"));
+        let err = builder().transition("Missing", vec![]).build().unwrap_err();
+        assert!(matches!(err, IrError::EventNotFound(ref name) if name == "Missing"));
+        let err = IrProgramBuilder::new("empty").field("x", IrType::Int).build().unwrap_err();
+        assert!(matches!(err, IrError::ProcessNotFound(_)));
+    }
+
+    #[test]
+    fn test_topological_order_follows_send_chain() {
+        let program = relay_program(vec![
+            relay_process("Sink", "Second", None),
+            relay_process("Middle", "First", Some("Second")),
+            relay_process("Source", "Start", Some("First")),
+        ]);
+
+        let names: Vec<_> = program
+            .topologically_ordered_processes()
+            .unwrap()
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Source", "Middle", "Sink"]);
+    }
+
+    #[test]
+    fn test_event_dependency_graph() {
+        let program = relay_program(vec![
+            relay_process("Producer", "Start", Some("Ping")),
+            relay_process("Consumer", "Ping", None),
+        ]);
+
+        let graph = program.event_dependency_graph();
+        assert_eq!(graph.len(), 2);
+        assert_eq!(graph["Ping"], (vec!["Producer".to_string()], vec!["Consumer".to_string()]));
+        assert_eq!(graph["Start"], (Vec::new(), vec!["Producer".to_string()]));
+    }
+
+    #[test]
+    fn test_topological_order_rejects_cycle() {
+        let program = relay_program(vec![
+            relay_process("Ping", "PingEvent", Some("PongEvent")),
+            relay_process("Pong", "PongEvent", Some("PingEvent")),
+        ]);
+
+        let err = program.topologically_ordered_processes().unwrap_err();
+        assert!(matches!(err, IrError::DependencyCycle(ref names) if names == "Ping, Pong"));
+    }
+
+    #[test]
+    fn test_process_without_transitions_warns() {
+        let typed = grey_lang::compile(INERT_PROCESS_SOURCE).unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("inert", &typed).unwrap();
+
+        assert_eq!(program.processes.len(), 1);
+        assert_eq!(
+            builder.warnings(),
+            &[IrWarning::ProcessWithoutTransitions("Idle".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_overwritten_field_update_warns() {
+        let source = |body: &str| {
+            format!(
+                "module M {{ event Tick {{}} process Node {{ x: Int, \
+                 method init() {{ let x = 0; }} \
+                 method handle_tick(event: Tick) {{ {} }} }} }}",
+                body
+            )
+        };
+        let warnings = |body: &str| {
+            let typed = grey_lang::compile(&source(body)).unwrap();
+            let mut builder = IrBuilder::new();
+            builder.build_program("overwrite", &typed).unwrap();
+            builder.warnings().to_vec()
+        };
+
+        assert_eq!(
+            warnings("this.x = 1; this.x = 2;"),
+            vec![IrWarning::OverwrittenField {
+                process: "Node".to_string(),
+                event: "Tick".to_string(),
+                field: "x".to_string(),
+            }]
+        );
+        assert!(warnings("this.x = this.x + 1; this.x = this.x * 2;").is_empty());
+    }
+
+    #[test]
+    fn test_fields_without_init_warn() {
+        let typed = grey_lang::compile(
+            r#"
+            module M {
+                event Tick {}
+                process Node {
+                    count: Int,
+                    label: String,
+                    active: Bool,
+                    method handle_tick(event: Tick) {
+                        this.count = this.count + 1;
+                    }
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let mut builder = IrBuilder::new();
+        builder.build_program("defaults", &typed).unwrap();
+        let defaulted = |field: &str| IrWarning::DefaultedField {
+            process: "Node".to_string(),
+            field: field.to_string(),
+        };
+        assert_eq!(
+            builder.warnings(),
+            &[defaulted("active"), defaulted("count"), defaulted("label")]
+        );
+
+        builder.suppress_defaulted_field_warnings();
+        builder.build_program("defaults", &typed).unwrap();
+        assert!(builder.warnings().is_empty());
+    }
+
+    const DUPLICATE_HANDLER_SOURCE: &str = r#"
+        module Dup {
+            event Tick { amount: Int }
+            process Node {
+                count: Int,
+                total: Int,
+                method handle_tick(event: Tick) {
+                    this.count = this.count + 1;
+                }
+                method handle_Tick(event: Tick) {
+                    this.total = this.total + 1;
+                }
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_duplicate_handlers_merged() {
+        let typed = grey_lang::compile(DUPLICATE_HANDLER_SOURCE).unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("dup", &typed).unwrap();
+
+        let transitions = &program.processes[0].transitions;
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].event_type, "Tick");
+
+        let fields: Vec<_> = transitions[0]
+            .actions
+            .iter()
+            .map(|a| match a {
+                IrAction::UpdateField { field, .. } => field.as_str(),
+                other => panic!("unexpected action {:?}", other),
+            })
+            .collect();
+        assert_eq!(fields, vec!["count", "total"]);
+    }
+
+    #[test]
+    fn test_duplicate_handlers_rejected_by_error_policy() {
+        let typed = grey_lang::compile(DUPLICATE_HANDLER_SOURCE).unwrap();
+        let mut builder = IrBuilder::new();
+        builder.set_duplicate_transition_policy(DuplicateTransitionPolicy::Error);
+
+        let err = builder.build_program("dup", &typed).unwrap_err();
+        assert!(matches!(
+            err,
+            IrError::DuplicateTransition { ref process, ref event } if process == "Node" && event == "Tick"
+        ));
+    }
+
+    #[test]
+    fn test_empty_module_warns() {
+        let typed = grey_lang::compile("module Empty {}").unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("empty", &typed).unwrap();
+
+        assert!(program.processes.is_empty());
+        assert_eq!(builder.warnings(), &[IrWarning::EmptyModule("Empty".to_string())]);
+    }
+
+    #[test]
+    fn test_inert_process_warning_suppressed() {
+        let typed = grey_lang::compile(INERT_PROCESS_SOURCE).unwrap();
+        let mut builder = IrBuilder::new();
+        builder.suppress_inert_process_warnings();
+        builder.build_program("inert", &typed).unwrap();
+
+        assert!(builder.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_event_payload_encoding_is_canonical() {
+        let event = IrEvent {
+            name: "Move".to_string(),
+            fields: HashMap::from([
+                ("steps".to_string(), IrType::Int),
+                ("active".to_string(), IrType::Bool),
+                ("target".to_string(), IrType::Coord),
+            ]),
+        };
+
+        let forward = HashMap::from([
+            ("steps".to_string(), IrValue::Integer(7)),
+            ("active".to_string(), IrValue::Boolean(true)),
+            ("target".to_string(), IrValue::Coord(Coord::new(1, 2, 3))),
+        ]);
+        let reversed = HashMap::from([
+            ("target".to_string(), IrValue::Coord(Coord::new(1, 2, 3))),
+            ("active".to_string(), IrValue::Boolean(true)),
+            ("steps".to_string(), IrValue::Integer(7)),
+        ]);
+
+        assert_eq!(event.wire_field_order(), vec!["active", "steps", "target"]);
+        assert_eq!(event.encode_payload(&forward).unwrap(), vec![1, 7, 1, 2, 3]);
+        assert_eq!(event.encode_payload(&reversed).unwrap(), vec![1, 7, 1, 2, 3]);
+
+        let mut missing = forward.clone();
+        missing.remove("steps");
+        assert!(matches!(event.encode_payload(&missing), Err(IrError::EventFieldMismatch(_))));
+    }
+
+    #[test]
+    fn test_constant_bounded_for_loop_lowers_to_repeat() {
+        let source = |bound: &str| {
+            format!(
+                r#"
+                module M {{
+                    const STEPS = 4;
+                    event Tick {{}}
+                    process Node {{
+                        count: Int,
+                        method handle_tick(event: Tick) {{
+                            for i in 0..{} {{
+                                this.count = this.count + 1;
+                            }}
+                        }}
+                    }}
+                }}
+                "#,
+                bound
+            )
+        };
+
+        let typed = grey_lang::compile(&source("10")).unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("loop", &typed).unwrap();
+        let increment = IrAction::UpdateField {
+            field: "count".to_string(),
+            value: IrExpression::Arithmetic {
+                op: IrArithmeticOp::Add,
+                left: Box::new(IrExpression::FieldAccess("count".to_string())),
+                right: Box::new(IrExpression::Constant(IrValue::Integer(1))),
+            },
+        };
+        assert_eq!(
+            program.processes[0].transitions[0].actions,
+            vec![IrAction::Repeat { count: 10, body: vec![increment] }]
+        );
+
+        let typed = grey_lang::compile(&source("STEPS")).unwrap();
+        let program = builder.build_program("loop", &typed).unwrap();
+        assert!(matches!(
+            program.processes[0].transitions[0].actions[0],
+            IrAction::Repeat { count: 4, .. }
+        ));
+
+        let typed = grey_lang::compile(&source("this.count")).unwrap();
+        let err = builder.build_program("loop", &typed).unwrap_err();
+        assert!(matches!(err, IrError::UnboundedLoop(ref bound) if bound == "count"));
+    }
+
+    #[test]
+    fn test_handler_updating_undeclared_field_rejected() {
+        let typed = grey_lang::compile(
+            r#"
+            module M {
+                event Tick {}
+                process Node {
+                    count: Int,
+                    method handle_tick(event: Tick) {
+                        this.total = this.count + 1;
+                    }
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let err = IrBuilder::new().build_program("unknown_field", &typed).unwrap_err();
+        assert!(matches!(
+            err,
+            IrError::UnknownField { ref process, ref field, .. } if process == "Node" && field == "total"
+        ));
+    }
+
+    #[test]
+    fn test_handler_update_type_checked_against_field() {
+        let source = |body: &str| {
+            format!(
+                r#"
+                module M {{
+                    event Tick {{}}
+                    process Node {{
+                        count: Int,
+                        label: String,
+                        method handle_tick(event: Tick) {{
+                            {}
+                        }}
+                    }}
+                }}
+                "#,
+                body
+            )
+        };
+
+        let typed = grey_lang::compile(&source("this.count = this.count + 1;")).unwrap();
+        assert!(IrBuilder::new().build_program("typed_update", &typed).is_ok());
+
+        let typed = grey_lang::compile(&source("this.count = this.label;")).unwrap();
+        let err = IrBuilder::new().build_program("typed_update", &typed).unwrap_err();
+        assert!(matches!(err, IrError::TypeMismatch(ref msg) if msg.contains("assigns String to Int field count")));
+    }
+
+    #[test]
+    fn test_link_merges_programs_and_rejects_clashes() {
+        let build = |name: &str, source: &str| {
+            let typed = grey_lang::compile(source).unwrap();
+            IrBuilder::new().build_program(name, &typed).unwrap().clone()
+        };
+
+        let sender = build(
+            "sender",
+            "module A { event Tick {} process Clock { ticks: Int, method handle_tick(event: Tick) { this.ticks = this.ticks + 1; } } }",
+        );
+        let mut receiver = build(
+            "receiver",
+            "module B { event Tick {} process Counter { seen: Int, method handle_tick(event: Tick) { this.seen = this.seen + 1; } } }",
+        );
+        receiver.resources.max_processes = 8;
+
+        let linked = IrBuilder::new().link(&[sender.clone(), receiver]).unwrap();
+        let names: Vec<_> = linked.processes.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Clock", "Counter"]);
+        assert_eq!(linked.events.len(), 1);
+        assert_eq!(linked.resources.max_processes, 8);
+
+        let err = IrBuilder::new().link(&[sender.clone(), sender]).unwrap_err();
+        assert!(matches!(err, IrError::Link(ref msg) if msg.contains("process Clock")));
+    }
+
+    fn drain<T>(queue: &mut EventQueue<T>) -> Vec<T> {
+        std::iter::from_fn(|| queue.pop()).collect()
+    }
+
+    #[test]
+    fn test_fifo_queue_keeps_push_order_within_timestamp() {
+        let mut queue = EventQueue::new(EventOrdering::Fifo);
+        queue.push(1, 0, "late");
+        queue.push(0, 900, "first");
+        queue.push(0, 5, "second");
+        queue.push(0, 40, "third");
+        assert_eq!(queue.len(), 4);
+
+        assert_eq!(drain(&mut queue), vec!["first", "second", "third", "late"]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_deterministic_queue_breaks_ties_by_node_id() {
+        let mut queue = EventQueue::new(EventOrdering::Deterministic);
+        queue.push(1, 0, "late");
+        queue.push(0, 900, "node 900");
+        queue.push(0, 5, "node 5, first");
+        queue.push(0, 40, "node 40");
+        queue.push(0, 5, "node 5, second");
+
+        assert_eq!(
+            drain(&mut queue),
+            vec!["node 5, first", "node 5, second", "node 40", "node 900", "late"]
+        );
+    }
+
+    #[test]
+    fn test_subtraction_lowered_end_to_end() {
+        let source = r#"
+            module M {
+                event Tick { amount: Int }
+                process Counter {
+                    a: Int,
+                    b: Int,
+                    method handle_tick(event: Tick) { this.a = this.a - this.b; }
+                }
+            }
+        "#;
+        let typed = grey_lang::compile(source).unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("subtract", &typed).unwrap();
+
+        let actions = &program.processes[0].transitions[0].actions;
+        let [IrAction::UpdateField { field, value }] = actions.as_slice() else {
+            panic!("expected a single field update, got {:?}", actions);
+        };
+        assert_eq!(field, "a");
+        assert!(matches!(value, IrExpression::Arithmetic { op: IrArithmeticOp::Subtract, .. }));
+    }
+
+    #[test]
+    fn test_deeply_nested_expression_rejected_when_lowered() {
+        use grey_lang::ast::Expression;
+
+        let mut expr = Expression::Integer(1);
+        for _ in 0..5000 {
+            expr = Expression::Add { left: Box::new(expr), right: Box::new(Expression::Integer(1)) };
+        }
+
+        let err = IrBuilder::new().expression_to_ir_expression(&expr, &LocalScope::default()).unwrap_err();
+        assert!(matches!(err, IrError::ExpressionTooDeep(MAX_EXPRESSION_DEPTH)));
+
+        let mut shallow = Expression::Integer(1);
+        for _ in 1..MAX_EXPRESSION_DEPTH {
+            shallow = Expression::Add { left: Box::new(shallow), right: Box::new(Expression::Integer(1)) };
+        }
+        let lowered = IrBuilder::new().expression_to_ir_expression(&shallow, &LocalScope::default()).unwrap();
+        assert_eq!(lowered.depth(), MAX_EXPRESSION_DEPTH);
+    }
+
+    #[test]
+    fn test_handled_events_follow_event_parameters() {
+        // The contagion demo's handlers are named after the short event names,
+        // so the declared event types must come from the `event` parameter.
+        let source = r#"
+            module ContagionDemo {
+                event InfectionSpread { source_node: Int, target_node: Int }
+                event RecoveryComplete { node_id: Int }
+                process Node {
+                    infected: Bool,
+                    method handle_infection(event: InfectionSpread) { this.infected = true; }
+                    method handle_recovery(event: RecoveryComplete) { this.infected = false; }
+                }
+                process Observer {
+                    recoveries: Int,
+                    method handle_recovery(event: RecoveryComplete) {
+                        this.recoveries = this.recoveries + 1;
+                    }
+                }
+            }
+        "#;
+        let typed = grey_lang::compile(source).unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("contagion", &typed).unwrap();
+
+        let node = program.processes.iter().find(|p| p.name == "Node").unwrap();
+        assert_eq!(node.handled_events(), vec!["InfectionSpread", "RecoveryComplete"]);
+
+        let names = |event| {
+            program.processes_handling(event).iter().map(|p| p.name.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(names("InfectionSpread"), vec!["Node"]);
+        assert_eq!(names("RecoveryComplete"), vec!["Node", "Observer"]);
+        assert!(names("Infection").is_empty());
+    }
+
+    #[test]
+    fn test_sir_demo_complexity() {
+        let source = include_str!("../../../examples/sir_demo.grey");
+        let typed = grey_lang::compile(source).unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("sir_demo", &typed).unwrap();
+
+        // handle_infection and handle_recovery, two field updates each; the deepest
+        // expression is `this.infection_count + 1`. Person has 3 fields, Infection 2
+        // and Recovery 1.
+        assert_eq!(
+            program.complexity(),
+            IrComplexity {
+                total_transitions: 2,
+                max_actions_per_transition: 2,
+                max_expression_depth: 2,
+                field_count: 6,
+            }
+        );
+    }
+
+    #[test]
+    fn test_else_if_chain_lowers_to_exclusive_arms() {
+        let source = r#"
+            module M {
+                event Tick {}
+                process Node {
+                    hot: Bool,
+                    warm: Bool,
+                    level: Int,
+                    method handle_tick(event: Tick) {
+                        if (this.hot) {
+                            this.level = 3;
+                        } else if (this.warm) {
+                            this.level = 2;
+                        } else {
+                            this.level = 1;
+                        }
+                    }
+                }
+            }
+        "#;
+        let typed = grey_lang::compile(source).unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("branches", &typed).unwrap();
+
+        let actions = &program.processes[0].transitions[0].actions;
+        let [IrAction::Branch { arms }] = actions.as_slice() else {
+            panic!("expected a single branch, got {:?}", actions);
+        };
+
+        let guards: Vec<_> = arms.iter().map(|arm| &arm.guard).collect();
+        assert_eq!(
+            guards,
+            vec![
+                &IrExpression::FieldAccess("hot".to_string()),
+                &IrExpression::FieldAccess("warm".to_string()),
+                &IrExpression::Constant(IrValue::Boolean(true)),
+            ]
+        );
+        for (arm, level) in arms.iter().zip([3, 2, 1]) {
+            assert_eq!(
+                arm.actions,
+                vec![IrAction::UpdateField {
+                    field: "level".to_string(),
+                    value: IrExpression::Constant(IrValue::Integer(level)),
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_init_resolves_module_constants() {
+        let source = r#"
+            module M {
+                const VERSION = "1.0";
+                const START = 5;
+                process FileProcessor {
+                    version: String,
+                    count: Int,
+                    method init() {
+                        this.version = VERSION;
+                        this.count = START;
+                    }
+                }
+            }
+        "#;
+        let typed = grey_lang::compile(source).unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("constants", &typed).unwrap();
+
+        let state = &program.processes[0].initial_state.values;
+        assert_eq!(state["version"], IrValue::String("1.0".to_string()));
+        assert_eq!(state["count"], IrValue::Integer(5));
+    }
+
+    #[test]
+    fn test_boolean_and_coord_constants_build() {
+        let source = r#"
+            module M {
+                const ENABLED = true;
+                const HOME = <1, 2, 3>;
+                process Node {
+                    enabled: Bool,
+                    home: Coord,
+                    method init() {
+                        this.enabled = ENABLED;
+                        this.home = HOME;
+                    }
+                }
+            }
+        "#;
+        let typed = grey_lang::compile(source).unwrap();
+        let program = IrBuilder::new().build_program("constants", &typed).unwrap().clone();
+
+        assert_eq!(program.constants["ENABLED"], IrValue::Boolean(true));
+        assert_eq!(program.constants["HOME"], IrValue::Coord(Coord::new(1, 2, 3)));
+        let state = &program.processes[0].initial_state.values;
+        assert_eq!(state["enabled"], IrValue::Boolean(true));
+        assert_eq!(state["home"], program.constants["HOME"]);
+    }
+
+    #[test]
+    fn test_unlowerable_expression_is_an_error() {
+        let typed = grey_lang::compile(
+            "module M { event Tick {} process Node { count: Int, method handle_tick(event: Tick) { this.count = next(); } } }",
+        )
+        .unwrap();
+        let err = IrBuilder::new().build_program("calls", &typed).unwrap_err();
+
+        assert!(matches!(err, IrError::UnsupportedExpression(_)), "{err}");
+    }
+
+    #[test]
+    fn test_local_let_is_substituted_not_stored() {
+        let typed = grey_lang::compile(
+            "module M { event Tick {} process Node { count: Int, method handle_tick(event: Tick) { let step = 2; this.count = this.count + step; } } }",
+        )
+        .unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("locals", &typed).unwrap();
+        let process = &program.processes[0];
+
+        assert!(!process.fields.contains_key("step"));
+        assert_eq!(
+            process.transitions[0].actions,
+            vec![IrAction::UpdateField {
+                field: "count".to_string(),
+                value: IrExpression::Arithmetic {
+                    op: IrArithmeticOp::Add,
+                    left: Box::new(IrExpression::FieldAccess("count".to_string())),
+                    right: Box::new(IrExpression::Constant(IrValue::Integer(2))),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn test_local_read_after_its_field_changes_is_an_error() {
+        let typed = grey_lang::compile(
+            "module M { event Tick {} process Node { count: Int, total: Int, method handle_tick(event: Tick) { let old = this.count; this.count = 0; this.total = old; } } }",
+        )
+        .unwrap();
+        let err = IrBuilder::new().build_program("locals", &typed).unwrap_err();
+
+        assert!(matches!(err, IrError::UnsupportedExpression(_)), "{err}");
+    }
+
+    #[test]
+    fn test_array_field_rejected_before_lowering() {
+        let source = "module M { process Grid { cells: [16] of int } }";
+        let err = grey_lang::compile(source).unwrap_err();
+        assert!(err.message().contains("not yet supported by the IR"), "{}", err.message());
+
+        // A program that skipped the check still gets a clear error from the builder.
+        let typed = grey_lang::type_check_program(&grey_lang::parse_source(source).unwrap()).unwrap();
+        let err = IrBuilder::new().build_program("arrays", &typed).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Type mismatch: Array type [16] of int is not yet supported by the IR"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_constant_type_rejected() {
+        let typed = grey_lang::compile("module M { const RATE = 1.5; process Node { count: Int } }").unwrap();
+        let err = IrBuilder::new().build_program("constants", &typed).unwrap_err();
+
+        assert!(matches!(err, IrError::TypeMismatch(_)), "{err}");
+    }
+
+    #[test]
+    fn test_transition_priorities_follow_source_order() {
+        let source = r#"
+            module M {
+                event Tick {}
+                event Tock {}
+                process Node {
+                    count: Int,
+                    method handle_tock(event: Tock) { this.count = 0; }
+                    method bump() { this.count = this.count + 1; }
+                    method handle_tick(event: Tick) { this.count = 1; }
+                }
+            }
+        "#;
+        let typed = grey_lang::compile(source).unwrap();
+        let mut builder = IrBuilder::new();
+        let program = builder.build_program("priorities", &typed).unwrap();
+
+        let order: Vec<_> = program.processes[0]
+            .transitions
+            .iter()
+            .map(|t| (t.event_type.as_str(), t.priority))
+            .collect();
+        assert_eq!(order, vec![("Tock", 0), ("Tick", 1)]);
+    }
+}
@@ -2,8 +2,10 @@
 //!
 //! This module defines the basic AST structures for Grey programs.
 
+use serde::{Deserialize, Serialize};
+
 /// Source location information
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SourceLocation {
     pub line: usize,
     pub column: usize,
@@ -11,13 +13,15 @@ pub struct SourceLocation {
 }
 
 /// Top-level program structure
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Program {
+    /// Files imported with `use <name>;`, resolved to `<name>.grey`
+    pub uses: Vec<String>,
     pub modules: Vec<Module>,
 }
 
 /// Module definition
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Module {
     pub name: String,
     pub constants: Vec<ConstantDeclaration>,
@@ -26,14 +30,14 @@ pub struct Module {
 }
 
 /// Constant declaration
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConstantDeclaration {
     pub name: String,
     pub value: Expression,
 }
 
 /// Process definition
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProcessDefinition {
     pub name: String,
     pub fields: Vec<FieldDeclaration>,
@@ -41,21 +45,21 @@ pub struct ProcessDefinition {
 }
 
 /// Field declaration in process/event
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FieldDeclaration {
     pub name: String,
     pub field_type: Type,
 }
 
 /// Event definition
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EventDefinition {
     pub name: String,
     pub fields: Vec<FieldDeclaration>,
 }
 
 /// Function definition
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionDefinition {
     pub name: String,
     pub parameters: Vec<FunctionParameter>,
@@ -64,20 +68,22 @@ pub struct FunctionDefinition {
 }
 
 /// Function parameter
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionParameter {
     pub name: String,
     pub param_type: Type,
 }
 
 /// Expressions
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Expression {
     Integer(i64),
+    Float(f64),
     Boolean(bool),
     String(String),
     Identifier(String),
-    CoordLiteral,
+    /// `<x, y, z>`; `z` is 0 when the literal has two components
+    CoordLiteral { x: i32, y: i32, z: i32 },
 
     Add {
         left: Box<Expression>,
@@ -96,6 +102,9 @@ pub enum Expression {
         right: Box<Expression>,
     },
 
+    /// `!operand`, logical negation of a `Bool`
+    Not(Box<Expression>),
+
     Call {
         function: Box<Expression>,
         arguments: Vec<Expression>,
@@ -104,10 +113,18 @@ pub enum Expression {
     Block {
         statements: Vec<Statement>,
     },
+
+    /// `if (condition) { ... } else { ... }`. An `else if` is represented as an
+    /// `else_branch` holding a single nested `If` statement.
+    If {
+        condition: Box<Expression>,
+        then_branch: Vec<Statement>,
+        else_branch: Vec<Statement>,
+    },
 }
 
 /// Statements
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Statement {
     Expression(Expression),
     Let {
@@ -115,27 +132,44 @@ pub enum Statement {
         value: Expression,
     },
     Return(Option<Expression>),
+    /// `for variable in start..end { body }`, iterating over the half-open range
+    For {
+        variable: String,
+        start: Expression,
+        end: Expression,
+        body: Vec<Statement>,
+    },
 }
 
 /// Patterns for destructuring
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Pattern {
     Identifier(String),
+    /// `this.field` or `self.field` as an assignment target
+    Field(String),
 }
 
 /// Block expression
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BlockExpression {
     pub statements: Vec<Statement>,
     pub result: Option<Box<Expression>>,
 }
 
 /// Type representations
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Type {
     Int,
+    Float,
     String,
     Bool,
     Coord,
     Named(String),
+    /// Fixed-capacity array, written `[N] of T`
+    Array {
+        length: usize,
+        element: Box<Type>,
+    },
+    /// Growable collection, written `[] of T`; rejected by O(1) validation
+    List(Box<Type>),
 }
@@ -2,24 +2,147 @@
 //! 
 //! This module provides basic validation for Grey programs against O(1) constraints.
 
+use crate::ast::{Expression, Statement};
 use crate::types::*;
-use crate::diagnostics::Diagnostic;
+use crate::diagnostics::{Diagnostic, DiagnosticError, SourceLocation};
+use std::collections::{BTreeSet, HashMap};
+
+/// Default per-process static memory budget, in bytes
+pub const DEFAULT_MAX_PROCESS_BYTES: usize = 1024 * 1024;
+
+/// A specific O(1) constraint a program breaks, naming where it does so
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConstraintViolation {
+    #[error("process {process} field {field} is a growable collection with no fixed capacity")]
+    UnboundedCollection { process: String, field: String },
+
+    #[error("recursive method calls in process {process}: {}", .cycle.join(" -> "))]
+    Recursion { process: String, cycle: Vec<String> },
+
+    #[error("process {process} needs {bytes} bytes of state, over the {budget} byte budget")]
+    OversizedFootprint { process: String, bytes: usize, budget: usize },
+}
+
+impl ConstraintViolation {
+    /// Short name of the constraint, for grouping violations
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConstraintViolation::UnboundedCollection { .. } => "unbounded collection",
+            ConstraintViolation::Recursion { .. } => "recursion",
+            ConstraintViolation::OversizedFootprint { .. } => "oversized footprint",
+        }
+    }
+
+    /// The process the violation occurs in
+    pub fn process(&self) -> &str {
+        match self {
+            ConstraintViolation::UnboundedCollection { process, .. }
+            | ConstraintViolation::Recursion { process, .. }
+            | ConstraintViolation::OversizedFootprint { process, .. } => process,
+        }
+    }
+}
 
 /// O(1) Constraint Validator
 pub struct O1Validator {
-    // Basic validator state
+    max_process_bytes: usize,
 }
 
 impl O1Validator {
     /// Create a new O(1) validator
     pub fn new() -> Self {
-        Self {}
+        Self {
+            max_process_bytes: DEFAULT_MAX_PROCESS_BYTES,
+        }
+    }
+
+    /// Set the maximum static memory footprint allowed per process
+    pub fn with_max_process_bytes(mut self, max_process_bytes: usize) -> Self {
+        self.max_process_bytes = max_process_bytes;
+        self
     }
     
-    /// Validate a typed program against O(1) constraints
-    pub fn validate_program(&mut self, _program: &TypedProgram) -> Result<(), Box<dyn Diagnostic>> {
-        // For now, just pass through - O(1) validation will be implemented later
-        Ok(())
+    /// Validate a typed program against O(1) constraints, reporting the first violation
+    pub fn validate_program(&mut self, program: &TypedProgram) -> Result<(), Box<dyn Diagnostic>> {
+        match self.violations(program).into_iter().next() {
+            Some(violation) => Err(Box::new(DiagnosticError::constraint(violation, SourceLocation::dummy()))),
+            None => Ok(()),
+        }
+    }
+
+    /// Every O(1) violation in the program, process by process in declaration order
+    pub fn violations(&self, program: &TypedProgram) -> Vec<ConstraintViolation> {
+        let mut violations = Vec::new();
+        for module in &program.modules {
+            for process in &module.processes {
+                violations.extend(self.check_bounded_collections(process));
+                violations.extend(self.check_memory_budget(process));
+                violations.extend(self.check_bounded_call_depth(process));
+            }
+        }
+        violations
+    }
+
+    /// Reject fields whose type holds a growable collection anywhere inside it
+    fn check_bounded_collections(&self, process: &TypedProcessDefinition) -> Vec<ConstraintViolation> {
+        fn unbounded(ty: &Type) -> bool {
+            match ty {
+                Type::List(_) => true,
+                Type::Array(element, _) => unbounded(element),
+                _ => false,
+            }
+        }
+
+        process
+            .fields
+            .iter()
+            .filter(|field| unbounded(&field.field_type))
+            .map(|field| ConstraintViolation::UnboundedCollection {
+                process: process.name.clone(),
+                field: field.name.clone(),
+            })
+            .collect()
+    }
+
+    /// Reject processes whose fields need more static memory than the budget allows
+    fn check_memory_budget(&self, process: &TypedProcessDefinition) -> Option<ConstraintViolation> {
+        let bytes = process
+            .fields
+            .iter()
+            .fold(0usize, |total, field| total.saturating_add(field.field_type.static_size_bytes()));
+
+        (bytes > self.max_process_bytes).then(|| ConstraintViolation::OversizedFootprint {
+            process: process.name.clone(),
+            bytes,
+            budget: self.max_process_bytes,
+        })
+    }
+
+    /// Reject direct or mutual recursion among a process's methods, since the
+    /// call depth (and with it memory) would be unbounded.
+    fn check_bounded_call_depth(&self, process: &TypedProcessDefinition) -> Option<ConstraintViolation> {
+        let calls: HashMap<&str, BTreeSet<String>> = process
+            .methods
+            .iter()
+            .map(|method| {
+                let mut callees = BTreeSet::new();
+                collect_block_calls(&method.body, &mut callees);
+                callees.retain(|name| process.methods.iter().any(|m| &m.name == name));
+                (method.name.as_str(), callees)
+            })
+            .collect();
+
+        let mut finished = BTreeSet::new();
+        for method in &process.methods {
+            let mut path = Vec::new();
+            if let Some(cycle) = find_call_cycle(&method.name, &calls, &mut path, &mut finished) {
+                return Some(ConstraintViolation::Recursion {
+                    process: process.name.clone(),
+                    cycle,
+                });
+            }
+        }
+        None
     }
 }
 
@@ -27,4 +150,185 @@ impl Default for O1Validator {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// Depth-first search for a call cycle reachable from `method`, returning it as a
+/// path that starts and ends with the same method name.
+fn find_call_cycle(
+    method: &str,
+    calls: &HashMap<&str, BTreeSet<String>>,
+    path: &mut Vec<String>,
+    finished: &mut BTreeSet<String>,
+) -> Option<Vec<String>> {
+    if let Some(start) = path.iter().position(|m| m == method) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(method.to_string());
+        return Some(cycle);
+    }
+    if finished.contains(method) {
+        return None;
+    }
+
+    path.push(method.to_string());
+    for callee in calls.get(method).into_iter().flatten() {
+        if let Some(cycle) = find_call_cycle(callee, calls, path, finished) {
+            return Some(cycle);
+        }
+    }
+    path.pop();
+    finished.insert(method.to_string());
+    None
+}
+
+fn collect_block_calls(block: &TypedBlockExpression, callees: &mut BTreeSet<String>) {
+    for statement in &block.statements {
+        collect_typed_statement_calls(statement, callees);
+    }
+    if let Some(result) = &block.result {
+        collect_calls(&result.expression, callees);
+    }
+}
+
+fn collect_typed_statement_calls(statement: &TypedStatement, callees: &mut BTreeSet<String>) {
+    match statement {
+        TypedStatement::Expression(expr) | TypedStatement::Let { value: expr, .. } => {
+            collect_calls(&expr.expression, callees)
+        }
+        TypedStatement::Return(expr) => {
+            if let Some(expr) = expr {
+                collect_calls(&expr.expression, callees);
+            }
+        }
+        TypedStatement::For { start, end, body, .. } => {
+            collect_calls(&start.expression, callees);
+            collect_calls(&end.expression, callees);
+            for statement in body {
+                collect_typed_statement_calls(statement, callees);
+            }
+        }
+    }
+}
+
+fn collect_statement_calls(statement: &Statement, callees: &mut BTreeSet<String>) {
+    match statement {
+        Statement::Expression(expr) | Statement::Let { value: expr, .. } => collect_calls(expr, callees),
+        Statement::Return(expr) => {
+            if let Some(expr) = expr {
+                collect_calls(expr, callees);
+            }
+        }
+        Statement::For { start, end, body, .. } => {
+            collect_calls(start, callees);
+            collect_calls(end, callees);
+            for statement in body {
+                collect_statement_calls(statement, callees);
+            }
+        }
+    }
+}
+
+fn collect_calls(expression: &Expression, callees: &mut BTreeSet<String>) {
+    match expression {
+        Expression::Call { function, arguments } => {
+            if let Expression::Identifier(name) = function.as_ref() {
+                callees.insert(name.clone());
+            }
+            collect_calls(function, callees);
+            for argument in arguments {
+                collect_calls(argument, callees);
+            }
+        }
+        Expression::Add { left, right }
+        | Expression::Subtract { left, right }
+        | Expression::Multiply { left, right }
+        | Expression::Divide { left, right } => {
+            collect_calls(left, callees);
+            collect_calls(right, callees);
+        }
+        Expression::Not(operand) => collect_calls(operand, callees),
+        Expression::Block { statements } => {
+            for statement in statements {
+                collect_statement_calls(statement, callees);
+            }
+        }
+        Expression::If { condition, then_branch, else_branch } => {
+            collect_calls(condition, callees);
+            for statement in then_branch.iter().chain(else_branch) {
+                collect_statement_calls(statement, callees);
+            }
+        }
+        Expression::Integer(_)
+        | Expression::Float(_)
+        | Expression::Boolean(_)
+        | Expression::String(_)
+        | Expression::Identifier(_)
+        | Expression::CoordLiteral { .. } => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process_source(methods: &str) -> String {
+        format!("module M {{ process Node {{ count: Int, {} }} }}", methods)
+    }
+
+    #[test]
+    fn test_recursive_methods_violate_o1() {
+        let err = crate::compile(&process_source("method tick() { this.tick(); }")).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "O(1) violation: recursive method calls in process Node: tick -> tick"
+        );
+
+        let mutual = process_source("method ping() { this.pong(); } method pong() { this.ping(); }");
+        let err = crate::compile(&mutual).unwrap_err();
+        assert!(err.message().contains("ping -> pong -> ping"));
+    }
+
+    #[test]
+    fn test_non_recursive_calls_pass() {
+        let source = process_source(
+            "method tick() { this.bump(); this.bump(); } method bump() { this.count = this.count + 1; }",
+        );
+        assert!(crate::compile(&source).is_ok());
+    }
+
+    #[test]
+    fn test_process_memory_budget() {
+        let err = crate::compile("module M { process Big { cells: [1000000] of int } }").unwrap_err();
+        assert_eq!(
+            err.message(),
+            "O(1) violation: process Big needs 8000000 bytes of state, over the 1048576 byte budget"
+        );
+
+        let small = crate::parse_source("module M { process Small { cells: [16] of int } }").unwrap();
+        let typed = crate::type_check_program(&small).unwrap();
+        assert!(O1Validator::new().validate_program(&typed).is_ok());
+        assert!(O1Validator::new().with_max_process_bytes(64).validate_program(&typed).is_err());
+    }
+
+    #[test]
+    fn test_unbounded_collection_violation_names_process_and_field() {
+        let err = crate::compile("module M { process Log { count: Int, entries: [] of int } }").unwrap_err();
+        assert_eq!(
+            err.constraint_violation(),
+            Some(&ConstraintViolation::UnboundedCollection {
+                process: "Log".to_string(),
+                field: "entries".to_string(),
+            })
+        );
+        assert_eq!(err.constraint_violation().unwrap().kind(), "unbounded collection");
+        assert_eq!(
+            err.message(),
+            "O(1) violation: process Log field entries is a growable collection with no fixed capacity"
+        );
+
+        let source = process_source("grid: [4] of [] of int, method tick() { this.tick(); }");
+        let program = crate::parse_source(&source).unwrap();
+        let typed = crate::type_check_program(&program).unwrap();
+        let kinds: Vec<_> = O1Validator::new().violations(&typed).iter().map(|v| v.kind()).collect();
+        assert_eq!(kinds, vec!["unbounded collection", "recursion"]);
+    }
+}
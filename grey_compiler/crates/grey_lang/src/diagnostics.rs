@@ -5,6 +5,8 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::constraints::ConstraintViolation;
+
 /// Main diagnostic error type
 #[derive(Debug, thiserror::Error)]
 pub enum DiagnosticError {
@@ -13,24 +15,48 @@ pub enum DiagnosticError {
         message: String,
         location: SourceLocation,
     },
+
+    /// A program that type checks but breaks an O(1) constraint
+    #[error("Compiler error: {message}")]
+    Constraint {
+        message: String,
+        violation: ConstraintViolation,
+        location: SourceLocation,
+    },
 }
 
 /// Diagnostic trait for compile errors
 pub trait Diagnostic: std::error::Error + fmt::Display {
     fn message(&self) -> &str;
     fn location(&self) -> &SourceLocation;
+
+    /// The O(1) constraint this diagnostic reports, if it is a validation failure
+    fn constraint_violation(&self) -> Option<&ConstraintViolation> {
+        None
+    }
 }
 
 impl Diagnostic for DiagnosticError {
     fn message(&self) -> &str {
         match self {
-            DiagnosticError::General { message, .. } => message,
+            DiagnosticError::General { message, .. } | DiagnosticError::Constraint { message, .. } => {
+                message
+            }
         }
     }
     
     fn location(&self) -> &SourceLocation {
         match self {
-            DiagnosticError::General { location, .. } => location,
+            DiagnosticError::General { location, .. } | DiagnosticError::Constraint { location, .. } => {
+                location
+            }
+        }
+    }
+
+    fn constraint_violation(&self) -> Option<&ConstraintViolation> {
+        match self {
+            DiagnosticError::Constraint { violation, .. } => Some(violation),
+            DiagnosticError::General { .. } => None,
         }
     }
 }
@@ -43,8 +69,20 @@ impl DiagnosticError {
             location,
         }
     }
+
+    /// Create an O(1) constraint error whose message describes `violation`
+    pub fn constraint(violation: ConstraintViolation, location: SourceLocation) -> Self {
+        Self::Constraint {
+            message: format!("O(1) violation: {}", violation),
+            violation,
+            location,
+        }
+    }
 }
 
+/// Columns a tab advances to the next multiple of when computing locations
+pub const TAB_WIDTH: usize = 4;
+
 /// Source location information for diagnostics
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SourceLocation {
@@ -59,6 +97,34 @@ impl SourceLocation {
         Self { line, column, span }
     }
     
+    /// Locate a byte span in `source`, with a 1-based line and column for its start.
+    ///
+    /// `\r\n` counts as a single line break, a tab advances the column to the next
+    /// multiple of [`TAB_WIDTH`], and every other character, however many bytes it
+    /// takes, is one column.
+    pub fn from_span(source: &str, span: (usize, usize)) -> Self {
+        let mut line = 1;
+        let mut column = 1;
+        let mut chars = source.char_indices().peekable();
+
+        while let Some((offset, ch)) = chars.next() {
+            if offset >= span.0 {
+                break;
+            }
+            match ch {
+                '\r' if matches!(chars.peek(), Some((_, '\n'))) => {}
+                '\n' => {
+                    line += 1;
+                    column = 1;
+                }
+                '\t' => column += TAB_WIDTH - (column - 1) % TAB_WIDTH,
+                _ => column += 1,
+            }
+        }
+
+        Self { line, column, span }
+    }
+
     /// Create a dummy location for errors without source context
     pub fn dummy() -> Self {
         Self {
@@ -73,4 +139,19 @@ impl fmt::Display for SourceLocation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "line {}, column {}", self.line, self.column)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_counts_crlf_once_and_expands_tabs() {
+        let source = "a\r\nb\r\n\tc";
+        let location = SourceLocation::from_span(source, (7, 8));
+        assert_eq!((location.line, location.column), (3, 5));
+
+        let location = SourceLocation::from_span("ab\t\tx", (4, 5));
+        assert_eq!((location.line, location.column), (1, 9));
+    }
+}
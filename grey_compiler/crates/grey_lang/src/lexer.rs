@@ -2,13 +2,14 @@
 //!
 //! This module provides basic tokenization of Grey source code.
 
-use crate::diagnostics::{Diagnostic, DiagnosticError};
+use crate::diagnostics::{Diagnostic, DiagnosticError, SourceLocation};
 
 /// All possible tokens in Grey
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Identifier(String),
     Integer(i64),
+    Float(f64),
     Boolean(bool),
     String(String),
 
@@ -22,7 +23,9 @@ pub enum Token {
     Else,
     While,
     For,
+    In,
     Return,
+    Use,
 
     LParen,
     RParen,
@@ -53,11 +56,13 @@ pub enum Token {
     Arrow,
 
     Dot,
+    DotDot,
     At,
 
     Bang,
 
-    CoordLiteral,
+    /// `<x, y, z>`; a two-component literal `<x, y>` has `z = 0`
+    CoordLiteral(i32, i32, i32),
     Eof,
 }
 
@@ -68,12 +73,151 @@ pub struct SpannedToken {
     pub span: (usize, usize), // (start, end) byte positions
 }
 
+/// Build the token for a numeric literal and its optional type suffix.
+///
+/// Integer literals accept `i32`, `i64`, `f`, `f32` and `f64`; a float suffix turns
+/// the literal into a float. Literals with a fractional part only accept float suffixes.
+fn numeric_literal_token(
+    digits: &str,
+    suffix: &str,
+    is_float: bool,
+    location: &SourceLocation,
+) -> Result<Token, Box<dyn Diagnostic>> {
+    let error = |message: String| -> Box<dyn Diagnostic> {
+        Box::new(DiagnosticError::general(&message, location.clone()))
+    };
+
+    let float = || {
+        digits
+            .parse::<f64>()
+            .map(Token::Float)
+            .map_err(|_| error(format!("Invalid float: {}", digits)))
+    };
+
+    match suffix {
+        "f" | "f32" | "f64" => float(),
+        "" if is_float => float(),
+        "i32" | "i64" | "" if !is_float => {
+            let value = digits
+                .parse::<i64>()
+                .map_err(|_| error(format!("Invalid integer: {}", digits)))?;
+            if suffix == "i32" && i32::try_from(value).is_err() {
+                return Err(error(format!("Integer literal {}i32 does not fit in i32", digits)));
+            }
+            Ok(Token::Integer(value))
+        }
+        "i32" | "i64" => Err(error(format!(
+            "Invalid suffix '{}' on float literal {}",
+            suffix, digits
+        ))),
+        _ => Err(error(format!(
+            "Invalid numeric literal suffix '{}' on {}",
+            suffix, digits
+        ))),
+    }
+}
+
+/// A source comment, kept out of the token stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// Full comment text including its `//` or `/* */` delimiters
+    pub text: String,
+    pub span: (usize, usize),
+    /// Whether this is a `/* ... */` comment rather than a `//` line comment
+    pub block: bool,
+}
+
+/// Token for the coordinate literal whose text between `<` and `>` is `inner`,
+/// as delimited by `coord_literal_end`.
+fn coord_literal_token(inner: &str, location: SourceLocation) -> Result<Token, Box<dyn Diagnostic>> {
+    let error = |message: String| -> Box<dyn Diagnostic> {
+        Box::new(DiagnosticError::general(&message, location.clone()))
+    };
+
+    let components = inner
+        .split(',')
+        .map(|component| {
+            let component = component.trim();
+            component
+                .parse::<i32>()
+                .map_err(|_| error(format!("Coordinate component {} does not fit in i32", component)))
+        })
+        .collect::<Result<Vec<i32>, _>>()?;
+
+    match components[..] {
+        [x, y] => Ok(Token::CoordLiteral(x, y, 0)),
+        [x, y, z] => Ok(Token::CoordLiteral(x, y, z)),
+        _ => Err(error(format!(
+            "Coordinate literal has {} components; expected 2 or 3",
+            components.len()
+        ))),
+    }
+}
+
 /// Main lexing function
+///
+/// Token spans are byte offsets into `source`, so `&source[span.0..span.1]` is the
+/// token's text even when earlier comments or strings contain non-ASCII characters.
 pub fn lex(source: &str) -> Result<Vec<SpannedToken>, Box<dyn Diagnostic>> {
+    lex_with_trivia(source).map(|(tokens, _)| tokens)
+}
+
+/// End (exclusive) of the coordinate literal opening at `chars[open]`, a `<`.
+///
+/// A coordinate literal is two or more comma-separated integers, each optionally
+/// negative, with whitespace allowed around components: `<1, 2>`, `< -5, 0, 0 >`.
+fn coord_literal_end(chars: &[char], open: usize) -> Option<usize> {
+    let skip_whitespace = |mut i: usize| {
+        while i < chars.len() && chars[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        i
+    };
+
+    let mut i = open + 1;
+    let mut components = 0;
+    loop {
+        i = skip_whitespace(i);
+        if chars.get(i) == Some(&'-') {
+            i += 1;
+        }
+        let digits_start = i;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == digits_start {
+            return None;
+        }
+        components += 1;
+
+        i = skip_whitespace(i);
+        match chars.get(i) {
+            Some(',') => i += 1,
+            Some('>') if components >= 2 => return Some(i + 1),
+            _ => return None,
+        }
+    }
+}
+
+/// Lex `source`, also returning its comments in source order.
+///
+/// The token stream is identical to [`lex`]; this is for tools such as the
+/// formatter that need to reattach comments.
+pub fn lex_with_trivia(source: &str) -> Result<(Vec<SpannedToken>, Vec<Comment>), Box<dyn Diagnostic>> {
     let mut tokens = Vec::new();
+    let mut comments = Vec::new();
     let chars: Vec<char> = source.chars().collect();
     let mut pos = 0;
 
+    // Scanning works in char indices; spans and locations are byte offsets, so
+    // multi-byte characters map through this table (with one past-the-end entry).
+    let offsets: Vec<usize> = source
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(source.len()))
+        .collect();
+    let locate = |start: usize, end: usize| SourceLocation::from_span(source, (offsets[start], offsets[end]));
+
     while pos < chars.len() {
         let c = chars[pos];
 
@@ -84,9 +228,34 @@ pub fn lex(source: &str) -> Result<Vec<SpannedToken>, Box<dyn Diagnostic>> {
             }
             // Comments
             '/' if pos + 1 < chars.len() && chars[pos + 1] == '/' => {
+                let start = pos;
                 while pos < chars.len() && chars[pos] != '\n' {
                     pos += 1;
                 }
+                comments.push(Comment {
+                    text: chars[start..pos].iter().collect(),
+                    span: (start, pos),
+                    block: false,
+                });
+            }
+            '/' if pos + 1 < chars.len() && chars[pos + 1] == '*' => {
+                let start = pos;
+                pos += 2;
+                while pos + 1 < chars.len() && !(chars[pos] == '*' && chars[pos + 1] == '/') {
+                    pos += 1;
+                }
+                if pos + 1 >= chars.len() {
+                    return Err(Box::new(DiagnosticError::general(
+                        "Unterminated block comment",
+                        locate(start, chars.len()),
+                    )));
+                }
+                pos += 2;
+                comments.push(Comment {
+                    text: chars[start..pos].iter().collect(),
+                    span: (start, pos),
+                    block: true,
+                });
             }
             // Identifiers and keywords
             'a'..='z' | 'A'..='Z' | '_' => {
@@ -111,7 +280,9 @@ pub fn lex(source: &str) -> Result<Vec<SpannedToken>, Box<dyn Diagnostic>> {
                     "else" => Token::Else,
                     "while" => Token::While,
                     "for" => Token::For,
+                    "in" => Token::In,
                     "return" => Token::Return,
+                    "use" => Token::Use,
                     "true" => Token::Boolean(true),
                     "false" => Token::Boolean(false),
                     _ => Token::Identifier(identifier),
@@ -122,25 +293,32 @@ pub fn lex(source: &str) -> Result<Vec<SpannedToken>, Box<dyn Diagnostic>> {
                     span: (start, pos),
                 });
             }
-            // Integer literals
+            // Numeric literals, with an optional fractional part and type suffix
             '0'..='9' => {
                 let start = pos;
                 while pos < chars.len() && chars[pos].is_ascii_digit() {
                     pos += 1;
                 }
+
+                let is_float = pos + 1 < chars.len() && chars[pos] == '.' && chars[pos + 1].is_ascii_digit();
+                if is_float {
+                    pos += 1;
+                    while pos < chars.len() && chars[pos].is_ascii_digit() {
+                        pos += 1;
+                    }
+                }
                 let num_str = chars[start..pos].iter().collect::<String>();
 
-                if let Ok(num) = num_str.parse::<i64>() {
-                    tokens.push(SpannedToken {
-                        token: Token::Integer(num),
-                        span: (start, pos),
-                    });
-                } else {
-                    return Err(Box::new(DiagnosticError::general(
-                        &format!("Invalid integer: {}", num_str),
-                        crate::diagnostics::SourceLocation::dummy(),
-                    )));
+                let suffix_start = pos;
+                while pos < chars.len() && (chars[pos].is_ascii_alphanumeric() || chars[pos] == '_') {
+                    pos += 1;
                 }
+                let suffix = chars[suffix_start..pos].iter().collect::<String>();
+
+                tokens.push(SpannedToken {
+                    token: numeric_literal_token(&num_str, &suffix, is_float, &locate(start, pos))?,
+                    span: (start, pos),
+                });
             }
             // String literals
             '"' => {
@@ -168,7 +346,7 @@ pub fn lex(source: &str) -> Result<Vec<SpannedToken>, Box<dyn Diagnostic>> {
                 if pos >= chars.len() {
                     return Err(Box::new(DiagnosticError::general(
                         "Unterminated string literal",
-                        crate::diagnostics::SourceLocation::dummy(),
+                        locate(start, chars.len()),
                     )));
                 }
 
@@ -192,43 +370,32 @@ pub fn lex(source: &str) -> Result<Vec<SpannedToken>, Box<dyn Diagnostic>> {
                     continue;
                 }
 
-                // Try to lex a coordinate literal: '<' ... '>' with only digits, minus, commas,
-                // and whitespace inside. If it doesn't match, fall back to a LessThan token.
-                let mut i = pos + 1;
-                while i < chars.len() && chars[i].is_ascii_whitespace() {
-                    i += 1;
-                }
-
-                let mut saw_digit = false;
-                while i < chars.len() {
-                    let ch = chars[i];
-                    if ch.is_ascii_digit() {
-                        saw_digit = true;
-                        i += 1;
-                        continue;
-                    }
-                    if ch == '-' || ch == ',' || ch.is_ascii_whitespace() {
-                        i += 1;
-                        continue;
-                    }
-                    if ch == '>' && saw_digit {
-                        pos = i + 1;
-                        tokens.push(SpannedToken {
-                            token: Token::CoordLiteral,
-                            span: (start, pos),
-                        });
-                        break;
-                    }
-
+                // A '<' right after an operand compares it (`x<1, 2>` is not a
+                // coordinate); elsewhere, try to lex a coordinate literal and fall back
+                // to a LessThan token.
+                let after_operand = matches!(
+                    tokens.last().map(|t| &t.token),
+                    Some(
+                        Token::Identifier(_)
+                            | Token::Integer(_)
+                            | Token::Float(_)
+                            | Token::Boolean(_)
+                            | Token::String(_)
+                            | Token::RParen
+                            | Token::RBracket
+                            | Token::CoordLiteral(..)
+                    )
+                );
+                let coord_end = if after_operand { None } else { coord_literal_end(&chars, pos) };
+
+                if let Some(end) = coord_end {
+                    let inner: String = chars[start + 1..end - 1].iter().collect();
                     tokens.push(SpannedToken {
-                        token: Token::LessThan,
-                        span: (pos, pos + 1),
+                        token: coord_literal_token(&inner, locate(start, end))?,
+                        span: (start, end),
                     });
-                    pos += 1;
-                    break;
-                }
-
-                if i >= chars.len() {
+                    pos = end;
+                } else {
                     tokens.push(SpannedToken {
                         token: Token::LessThan,
                         span: (pos, pos + 1),
@@ -388,11 +555,19 @@ pub fn lex(source: &str) -> Result<Vec<SpannedToken>, Box<dyn Diagnostic>> {
                 }
             }
             '.' => {
-                tokens.push(SpannedToken {
-                    token: Token::Dot,
-                    span: (pos, pos + 1),
-                });
-                pos += 1;
+                if pos + 1 < chars.len() && chars[pos + 1] == '.' {
+                    tokens.push(SpannedToken {
+                        token: Token::DotDot,
+                        span: (pos, pos + 2),
+                    });
+                    pos += 2;
+                } else {
+                    tokens.push(SpannedToken {
+                        token: Token::Dot,
+                        span: (pos, pos + 1),
+                    });
+                    pos += 1;
+                }
             }
             '@' => {
                 tokens.push(SpannedToken {
@@ -404,7 +579,7 @@ pub fn lex(source: &str) -> Result<Vec<SpannedToken>, Box<dyn Diagnostic>> {
             _ => {
                 return Err(Box::new(DiagnosticError::general(
                     &format!("Unexpected character: {}", c),
-                    crate::diagnostics::SourceLocation::dummy(),
+                    locate(pos, pos + 1),
                 )));
             }
         }
@@ -415,5 +590,114 @@ pub fn lex(source: &str) -> Result<Vec<SpannedToken>, Box<dyn Diagnostic>> {
         span: (chars.len(), chars.len()),
     });
 
-    Ok(tokens)
+    for token in &mut tokens {
+        token.span = (offsets[token.span.0], offsets[token.span.1]);
+    }
+    for comment in &mut comments {
+        comment.span = (offsets[comment.span.0], offsets[comment.span.1]);
+    }
+
+    Ok((tokens, comments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn single_token(source: &str) -> Token {
+        lex(source).unwrap().remove(0).token
+    }
+
+    #[test]
+    fn test_numeric_suffixes_select_literal_type() {
+        assert_eq!(single_token("42i64"), Token::Integer(42));
+        assert_eq!(single_token("42i32"), Token::Integer(42));
+        assert_eq!(single_token("42f"), Token::Float(42.0));
+        assert_eq!(single_token("2.5f"), Token::Float(2.5));
+        assert_eq!(single_token("2.5"), Token::Float(2.5));
+    }
+
+    #[test]
+    fn test_invalid_numeric_suffixes_rejected() {
+        let err = lex("42.0i64").unwrap_err();
+        assert!(err.message().contains("Invalid suffix 'i64' on float literal 42.0"));
+
+        assert!(lex("42u8").is_err());
+        assert!(lex("3000000000i32").is_err());
+    }
+
+    #[test]
+    fn test_coordinate_literals_and_less_than() {
+        let kinds = |source: &str| -> Vec<Token> {
+            lex(source).unwrap().into_iter().map(|t| t.token).collect()
+        };
+
+        assert_eq!(kinds("<-5,-5,-5>"), vec![Token::CoordLiteral(-5, -5, -5), Token::Eof]);
+        assert_eq!(kinds("< -5, 0, 7 >"), vec![Token::CoordLiteral(-5, 0, 7), Token::Eof]);
+        assert_eq!(kinds("<3, 4>"), vec![Token::CoordLiteral(3, 4, 0), Token::Eof]);
+        assert!(lex("<1, 2, 3, 4>").is_err());
+        assert!(lex("<1, 3000000000, 0>").is_err());
+        assert_eq!(lex("< -5, 0, 0 >").unwrap()[0].span, (0, 12));
+
+        let ident = |name: &str| Token::Identifier(name.to_string());
+        assert_eq!(kinds("x<y"), vec![ident("x"), Token::LessThan, ident("y"), Token::Eof]);
+        assert_eq!(
+            kinds("x<1, 2>y"),
+            vec![
+                ident("x"),
+                Token::LessThan,
+                Token::Integer(1),
+                Token::Comma,
+                Token::Integer(2),
+                Token::GreaterThan,
+                ident("y"),
+                Token::Eof
+            ]
+        );
+        assert_eq!(kinds("<5>")[0], Token::LessThan);
+        assert_eq!(kinds("<1-2, 3>")[0], Token::LessThan);
+    }
+
+    #[test]
+    fn test_lex_with_trivia_captures_comments() {
+        let source = "// leading\nmodule M { /* inner */ }";
+        let (tokens, comments) = lex_with_trivia(source).unwrap();
+
+        assert_eq!(tokens, lex(source).unwrap());
+        assert_eq!(
+            comments,
+            vec![
+                Comment { text: "// leading".to_string(), span: (0, 10), block: false },
+                Comment { text: "/* inner */".to_string(), span: (22, 33), block: true },
+            ]
+        );
+
+        assert!(lex("/* never closed").is_err());
+    }
+
+    #[test]
+    fn test_spans_and_locations_with_crlf_and_multibyte_chars() {
+        let source = "// café\r\nmodule M {\r\n\t# }";
+        let err = lex(source).unwrap_err();
+
+        assert_eq!(err.message(), "Unexpected character: #");
+        assert_eq!(err.location().span, (23, 24));
+        assert_eq!((err.location().line, err.location().column), (3, 5));
+
+        let tokens = lex("// café\r\nmodule M {}").unwrap();
+        assert_eq!(tokens[0].span, (10, 16));
+    }
+
+    #[test]
+    fn test_span_after_non_ascii_comment_is_in_bytes() {
+        let source = "// 🚀 launch\nprocess \"né\" ;";
+        let tokens = lex(source).unwrap();
+
+        assert_eq!(tokens[0].token, Token::Process);
+        assert_eq!(&source[tokens[0].span.0..tokens[0].span.1], "process");
+        assert_eq!(tokens[0].span, (15, 22));
+        assert_eq!(&source[tokens[1].span.0..tokens[1].span.1], "\"né\"");
+        assert_eq!(tokens[2].span, (29, 30));
+        assert_eq!(tokens[3].span, (source.len(), source.len()));
+    }
 }
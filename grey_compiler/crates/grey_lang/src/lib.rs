@@ -9,8 +9,16 @@ pub mod ast;
 pub mod types;
 pub mod diagnostics;
 pub mod constraints;
+pub mod pipeline;
+pub mod resolver;
+pub mod samples;
 
-use crate::diagnostics::Diagnostic;
+use std::time::{Duration, Instant};
+
+use crate::diagnostics::{Diagnostic, DiagnosticError, SourceLocation};
+
+/// Default `CompileOptions::max_source_bytes`: 16 MiB
+pub const DEFAULT_MAX_SOURCE_BYTES: usize = 16 * 1024 * 1024;
 
 /// Parse Grey source code into an AST
 pub fn parse_source(source: &str) -> Result<ast::Program, Box<dyn Diagnostic>> {
@@ -18,6 +26,34 @@ pub fn parse_source(source: &str) -> Result<ast::Program, Box<dyn Diagnostic>> {
     parser::parse_program(&tokens)
 }
 
+/// Wall-clock time spent in each compile stage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageTimings {
+    pub lex: Duration,
+    pub parse: Duration,
+    pub type_check: Duration,
+    /// Zero when the O(1) validation pass is disabled
+    pub validate: Duration,
+}
+
+impl StageTimings {
+    pub fn total(&self) -> Duration {
+        self.lex + self.parse + self.type_check + self.validate
+    }
+}
+
+/// `parse_source`, adding the lexing and parsing time to `timings`
+fn parse_source_timed(source: &str, timings: &mut StageTimings) -> Result<ast::Program, Box<dyn Diagnostic>> {
+    let start = Instant::now();
+    let tokens = lexer::lex(source);
+    timings.lex += start.elapsed();
+
+    let start = Instant::now();
+    let program = parser::parse_program(&tokens?);
+    timings.parse += start.elapsed();
+    program
+}
+
 /// Type check a parsed Grey program
 pub fn type_check_program(program: &ast::Program) -> Result<types::TypedProgram, Box<dyn Diagnostic>> {
     let mut typechecker = types::TypeChecker::new();
@@ -30,10 +66,194 @@ pub fn validate_program(program: &types::TypedProgram) -> Result<(), Box<dyn Dia
     validator.validate_program(program)
 }
 
+/// Options for the compile pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompileOptions {
+    /// Run the O(1) constraint validator after type checking
+    pub validate: bool,
+
+    /// Largest source accepted, in bytes; larger input is rejected before lexing.
+    ///
+    /// The lexer and parser hold the whole source in memory, as a `Vec<char>` of
+    /// 4 bytes per character plus the token stream, so peak memory is several
+    /// times the input size.
+    pub max_source_bytes: usize,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            validate: true,
+            max_source_bytes: DEFAULT_MAX_SOURCE_BYTES,
+        }
+    }
+}
+
+/// Reject a source of `len` bytes that is over `options.max_source_bytes`
+fn check_source_size(len: usize, options: CompileOptions) -> Result<(), Box<dyn Diagnostic>> {
+    if len > options.max_source_bytes {
+        return Err(Box::new(DiagnosticError::general(
+            &format!(
+                "Source is {} bytes, over the {}-byte limit",
+                len, options.max_source_bytes
+            ),
+            SourceLocation::dummy(),
+        )));
+    }
+    Ok(())
+}
+
 /// Compile pipeline: parse -> type check -> validate
 pub fn compile(source: &str) -> Result<types::TypedProgram, Box<dyn Diagnostic>> {
-    let program = parse_source(source)?;
-    let typed_program = type_check_program(&program)?;
-    validate_program(&typed_program)?;
+    compile_with_options(source, CompileOptions::default())
+}
+
+/// Compile pipeline with the validation pass controlled by `options`
+pub fn compile_with_options(
+    source: &str,
+    options: CompileOptions,
+) -> Result<types::TypedProgram, Box<dyn Diagnostic>> {
+    compile_traced_with_options(source, options).map(|(typed, _)| typed)
+}
+
+/// `compile`, also reporting how long each stage took
+pub fn compile_traced(source: &str) -> Result<(types::TypedProgram, StageTimings), Box<dyn Diagnostic>> {
+    compile_traced_with_options(source, CompileOptions::default())
+}
+
+/// `compile_with_options`, also reporting how long each stage took
+pub fn compile_traced_with_options(
+    source: &str,
+    options: CompileOptions,
+) -> Result<(types::TypedProgram, StageTimings), Box<dyn Diagnostic>> {
+    check_source_size(source.len(), options)?;
+    let mut timings = StageTimings::default();
+    let program = parse_source_timed(source, &mut timings)?;
+    let typed = finish_compile(&program, options, &mut timings)?;
+    Ok((typed, timings))
+}
+
+/// Compile a source file and the files it imports with `use`, searching
+/// `include_dirs` after the importing file's own directory
+pub fn compile_file(
+    path: &std::path::Path,
+    include_dirs: &[std::path::PathBuf],
+) -> Result<types::TypedProgram, Box<dyn Diagnostic>> {
+    compile_file_with_options(path, include_dirs, CompileOptions::default())
+}
+
+/// `compile_file` with the validation pass and size limit controlled by `options`.
+///
+/// The size limit applies to the root file.
+pub fn compile_file_with_options(
+    path: &std::path::Path,
+    include_dirs: &[std::path::PathBuf],
+    options: CompileOptions,
+) -> Result<types::TypedProgram, Box<dyn Diagnostic>> {
+    compile_file_traced(path, include_dirs, options).map(|(typed, _)| typed)
+}
+
+/// `compile_file_with_options`, also reporting how long each stage took.
+/// Lexing and parsing time is summed over the root file and its imports.
+pub fn compile_file_traced(
+    path: &std::path::Path,
+    include_dirs: &[std::path::PathBuf],
+    options: CompileOptions,
+) -> Result<(types::TypedProgram, StageTimings), Box<dyn Diagnostic>> {
+    if let Ok(metadata) = std::fs::metadata(path) {
+        check_source_size(metadata.len() as usize, options)?;
+    }
+    let resolver = resolver_for(include_dirs);
+    let program = resolver.resolve(path)?;
+    let mut timings = resolver.timings();
+    let typed = finish_compile(&program, options, &mut timings)?;
+    Ok((typed, timings))
+}
+
+/// Compile `source` as if it were the file at `origin`, resolving its `use`
+/// imports next to `origin` and then in `include_dirs`. For source that does not
+/// come from a file, such as stdin.
+pub fn compile_source_at(
+    source: &str,
+    origin: &std::path::Path,
+    include_dirs: &[std::path::PathBuf],
+    options: CompileOptions,
+) -> Result<types::TypedProgram, Box<dyn Diagnostic>> {
+    compile_source_at_traced(source, origin, include_dirs, options).map(|(typed, _)| typed)
+}
+
+/// `compile_source_at`, also reporting how long each stage took
+pub fn compile_source_at_traced(
+    source: &str,
+    origin: &std::path::Path,
+    include_dirs: &[std::path::PathBuf],
+    options: CompileOptions,
+) -> Result<(types::TypedProgram, StageTimings), Box<dyn Diagnostic>> {
+    check_source_size(source.len(), options)?;
+    let resolver = resolver_for(include_dirs);
+    let program = resolver.resolve_source(source, origin)?;
+    let mut timings = resolver.timings();
+    let typed = finish_compile(&program, options, &mut timings)?;
+    Ok((typed, timings))
+}
+
+fn resolver_for(include_dirs: &[std::path::PathBuf]) -> resolver::ModuleResolver {
+    let mut resolver = resolver::ModuleResolver::new();
+    for dir in include_dirs {
+        resolver.add_search_path(dir);
+    }
+    resolver
+}
+
+fn finish_compile(
+    program: &ast::Program,
+    options: CompileOptions,
+    timings: &mut StageTimings,
+) -> Result<types::TypedProgram, Box<dyn Diagnostic>> {
+    let start = Instant::now();
+    let typed_program = type_check_program(program);
+    timings.type_check += start.elapsed();
+    let typed_program = typed_program?;
+
+    if options.validate {
+        let start = Instant::now();
+        let validated = validate_program(&typed_program);
+        timings.validate += start.elapsed();
+        validated?;
+    }
+    types::check_ir_field_types(&typed_program)?;
     Ok(typed_program)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oversized_source_rejected_before_parsing() {
+        let options = CompileOptions { max_source_bytes: 16, ..CompileOptions::default() };
+
+        // Unparseable on purpose: a size error proves the parser never ran.
+        let err = compile_with_options("this is not a grey program at all", options).unwrap_err();
+        assert!(err.message().contains("over the 16-byte limit"), "{}", err.message());
+
+        assert!(compile_with_options("module M { }", options).is_ok());
+    }
+
+    #[test]
+    fn test_traced_compile_times_every_stage() {
+        let source = samples::scaling_module_source(64);
+        let (typed, timings) = compile_traced(&source).unwrap();
+
+        assert_eq!(typed, compile(&source).unwrap());
+        for (stage, duration) in [
+            ("lex", timings.lex),
+            ("parse", timings.parse),
+            ("type_check", timings.type_check),
+            ("validate", timings.validate),
+        ] {
+            assert!(duration > Duration::ZERO, "{stage} took no time");
+        }
+        assert_eq!(timings.total(), timings.lex + timings.parse + timings.type_check + timings.validate);
+    }
+}
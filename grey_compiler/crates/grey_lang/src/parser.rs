@@ -6,20 +6,37 @@ use crate::ast::*;
 use crate::diagnostics::{Diagnostic, DiagnosticError};
 use crate::lexer::{SpannedToken, Token};
 
+/// Default limit on nested expressions and blocks
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 128;
+
 /// Parser implementation
 pub struct Parser<'a> {
     tokens: &'a [SpannedToken],
     current: usize,
+    depth: usize,
+    max_depth: usize,
 }
 
 impl<'a> Parser<'a> {
     /// Create a new parser with the given token stream
     pub fn new(tokens: &'a [SpannedToken]) -> Self {
-        Self { tokens, current: 0 }
+        Self {
+            tokens,
+            current: 0,
+            depth: 0,
+            max_depth: DEFAULT_MAX_NESTING_DEPTH,
+        }
+    }
+
+    /// Set the maximum nesting depth of expressions and blocks
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
     }
 
     /// Parse the complete program
     pub fn parse_program(mut self) -> Result<Program, Box<dyn Diagnostic>> {
+        let mut uses = Vec::new();
         let mut modules = Vec::new();
 
         while !self.is_at_end() {
@@ -27,10 +44,39 @@ impl<'a> Parser<'a> {
                 break;
             }
 
+            if self.consume_if(&Token::Use) {
+                uses.push(self.consume_identifier("Expected module file name after 'use'")?);
+                self.consume(&Token::Semicolon, "Expected ';' after use declaration")?;
+                continue;
+            }
+
+            if !self.check(&Token::Module) {
+                let stray = self.peek();
+                return Err(Box::new(DiagnosticError::general(
+                    &format!("expected 'module' at top level, found {:?}", stray.token),
+                    crate::diagnostics::SourceLocation::new(0, 0, stray.span),
+                )));
+            }
+
             modules.push(self.parse_module()?);
         }
 
-        Ok(Program { modules })
+        if uses.is_empty() && modules.is_empty() {
+            // The EOF token sits at the end of the source, so it starts at 0 only
+            // for genuinely empty input
+            let eof = self.peek();
+            let detail = if eof.span.0 == 0 {
+                "input is empty"
+            } else {
+                "input contains only whitespace and comments"
+            };
+            return Err(Box::new(DiagnosticError::general(
+                &format!("no module found in source: {}", detail),
+                crate::diagnostics::SourceLocation::new(0, 0, eof.span),
+            )));
+        }
+
+        Ok(Program { uses, modules })
     }
 
     fn parse_module(&mut self) -> Result<Module, Box<dyn Diagnostic>> {
@@ -87,16 +133,11 @@ impl<'a> Parser<'a> {
         while !self.check(&Token::RBrace) && !self.is_at_end() {
             match &self.peek().token {
                 Token::Fn => methods.push(self.parse_method()?),
-                Token::Identifier(_) => {
-                    if self.peek_n(1).map(|t| &t.token) == Some(&Token::Colon) {
-                        fields.push(self.parse_field_declaration()?);
-                        self.consume_optional_field_separator();
-                    } else {
-                        return Err(Box::new(DiagnosticError::general(
-                            "Expected field declaration or method definition",
-                            crate::diagnostics::SourceLocation::dummy(),
-                        )));
-                    }
+                Token::Identifier(_) if self.peek_n(1).map(|t| &t.token) == Some(&Token::Colon) => {
+                    let field = self.parse_field_declaration()?;
+                    check_duplicate_field(&fields, &field, "process", &name)?;
+                    fields.push(field);
+                    self.consume_optional_field_separator();
                 }
                 Token::Comma | Token::Semicolon => {
                     self.advance();
@@ -125,7 +166,9 @@ impl<'a> Parser<'a> {
         while !self.check(&Token::RBrace) && !self.is_at_end() {
             match &self.peek().token {
                 Token::Identifier(_) => {
-                    fields.push(self.parse_field_declaration()?);
+                    let field = self.parse_field_declaration()?;
+                    check_duplicate_field(&fields, &field, "event", &name)?;
+                    fields.push(field);
                     self.consume_optional_field_separator();
                 }
                 Token::Comma | Token::Semicolon => {
@@ -190,7 +233,12 @@ impl<'a> Parser<'a> {
         }
 
         loop {
-            let name = self.consume_identifier("Expected parameter name")?;
+            // `event` is a keyword but is also the conventional handler parameter name.
+            let name = if self.consume_if(&Token::Event) {
+                "event".to_string()
+            } else {
+                self.consume_identifier("Expected parameter name")?
+            };
             self.consume(&Token::Colon, "Expected ':' after parameter name")?;
             let param_type = self.parse_type()?;
             parameters.push(FunctionParameter { name, param_type });
@@ -216,16 +264,52 @@ impl<'a> Parser<'a> {
 
                 Ok(match name.as_str() {
                     "Int" | "int" => Type::Int,
+                    "Float" | "float" => Type::Float,
                     "String" | "string" => Type::String,
                     "Bool" | "bool" => Type::Bool,
                     "Coord" | "coord" => Type::Coord,
                     _ => Type::Named(name),
                 })
             }
-            Token::CoordLiteral => {
+            Token::CoordLiteral(..) => {
                 self.advance();
                 Ok(Type::Coord)
             }
+            Token::LBracket => {
+                self.advance();
+                if self.peek().token == Token::RBracket {
+                    self.advance();
+                    if self.consume_identifier("Expected 'of' after '[]'")? != "of" {
+                        return Err(Box::new(DiagnosticError::general(
+                            "Expected 'of' after '[]'",
+                            crate::diagnostics::SourceLocation::dummy(),
+                        )));
+                    }
+                    return Ok(Type::List(Box::new(self.parse_type()?)));
+                }
+                let length = match &self.peek().token {
+                    Token::Integer(n) if *n >= 0 => *n as usize,
+                    _ => {
+                        return Err(Box::new(DiagnosticError::general(
+                            "Expected array length",
+                            crate::diagnostics::SourceLocation::dummy(),
+                        )))
+                    }
+                };
+                self.advance();
+                self.consume(&Token::RBracket, "Expected ']' after array length")?;
+                if self.consume_identifier("Expected 'of' after array length")? != "of" {
+                    return Err(Box::new(DiagnosticError::general(
+                        "Expected 'of' after array length",
+                        crate::diagnostics::SourceLocation::dummy(),
+                    )));
+                }
+                let element = self.parse_type()?;
+                Ok(Type::Array {
+                    length,
+                    element: Box::new(element),
+                })
+            }
             _ => Err(Box::new(DiagnosticError::general(
                 "Expected type specification",
                 crate::diagnostics::SourceLocation::dummy(),
@@ -234,6 +318,13 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_block_expression(&mut self) -> Result<BlockExpression, Box<dyn Diagnostic>> {
+        self.enter_nesting("block too deeply nested")?;
+        let block = self.parse_block_contents();
+        self.depth -= 1;
+        block
+    }
+
+    fn parse_block_contents(&mut self) -> Result<BlockExpression, Box<dyn Diagnostic>> {
         self.consume(&Token::LBrace, "Expected '{' to start block")?;
 
         let mut statements = Vec::new();
@@ -270,9 +361,21 @@ impl<'a> Parser<'a> {
                 self.consume(&Token::Semicolon, "Expected ';' after return statement")?;
                 Ok(Statement::Return(value))
             }
-            Token::If => {
-                let merged = self.parse_if_statement_to_statements()?;
-                Ok(Statement::Expression(Expression::Block { statements: merged }))
+            Token::If => Ok(Statement::Expression(self.parse_if_expression()?)),
+            Token::For => {
+                self.advance();
+                let variable = self.consume_identifier("Expected loop variable after 'for'")?;
+                self.consume(&Token::In, "Expected 'in' after loop variable")?;
+                let start = self.parse_expression()?;
+                self.consume(&Token::DotDot, "Expected '..' in loop range")?;
+                let end = self.parse_expression()?;
+                let body = self.parse_block_expression()?.statements;
+                Ok(Statement::For {
+                    variable,
+                    start,
+                    end,
+                    body,
+                })
             }
             _ => {
                 if let Some(stmt) = self.try_parse_assignment_statement()? {
@@ -286,44 +389,48 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_if_statement_to_statements(&mut self) -> Result<Vec<Statement>, Box<dyn Diagnostic>> {
+    fn parse_if_expression(&mut self) -> Result<Expression, Box<dyn Diagnostic>> {
         self.consume(&Token::If, "Expected 'if'")?;
         self.consume(&Token::LParen, "Expected '(' after 'if'")?;
-        let _condition = self.parse_expression()?;
+        let condition = self.parse_expression()?;
         self.consume(&Token::RParen, "Expected ')' after if condition")?;
 
-        let then_block = self.parse_block_expression()?;
-        let mut statements = then_block.statements;
+        let then_branch = self.parse_block_expression()?.statements;
 
-        if self.consume_if(&Token::Else) {
-            if self.check(&Token::If) {
-                let else_branch = self.parse_if_statement_to_statements()?;
-                statements.extend(else_branch);
-            } else {
-                let else_block = self.parse_block_expression()?;
-                statements.extend(else_block.statements);
-            }
-        }
+        let else_branch = if !self.consume_if(&Token::Else) {
+            Vec::new()
+        } else if self.check(&Token::If) {
+            self.enter_nesting("else-if chain too deeply nested")?;
+            let nested = self.parse_if_expression();
+            self.depth -= 1;
+            vec![Statement::Expression(nested?)]
+        } else {
+            self.parse_block_expression()?.statements
+        };
 
-        Ok(statements)
+        Ok(Expression::If {
+            condition: Box::new(condition),
+            then_branch,
+            else_branch,
+        })
     }
 
     fn try_parse_assignment_statement(&mut self) -> Result<Option<Statement>, Box<dyn Diagnostic>> {
-        // this.field = expr;
+        // this.field = expr; (or self.field = expr;)
         if let Some(Token::Identifier(name)) = self.peek_n(0).map(|t| &t.token) {
-            if name == "this"
+            if is_receiver(name)
                 && matches!(self.peek_n(1).map(|t| &t.token), Some(Token::Dot))
                 && matches!(self.peek_n(2).map(|t| &t.token), Some(Token::Identifier(_)))
                 && matches!(self.peek_n(3).map(|t| &t.token), Some(Token::Assign))
             {
-                self.advance(); // this
-                self.consume(&Token::Dot, "Expected '.' after 'this'")?;
+                self.advance(); // this / self
+                self.consume(&Token::Dot, "Expected '.' after receiver")?;
                 let field = self.consume_identifier("Expected field name")?;
                 self.consume(&Token::Assign, "Expected '=' after field")?;
                 let value = self.parse_expression()?;
                 self.consume(&Token::Semicolon, "Expected ';' after assignment")?;
                 return Ok(Some(Statement::Let {
-                    pattern: Pattern::Identifier(field),
+                    pattern: Pattern::Field(field),
                     value,
                 }));
             }
@@ -347,7 +454,10 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_expression(&mut self) -> Result<Expression, Box<dyn Diagnostic>> {
-        self.parse_term()
+        self.enter_nesting("expression too deeply nested")?;
+        let expr = self.parse_term();
+        self.depth -= 1;
+        expr
     }
 
     fn parse_term(&mut self) -> Result<Expression, Box<dyn Diagnostic>> {
@@ -395,15 +505,22 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_unary(&mut self) -> Result<Expression, Box<dyn Diagnostic>> {
+        self.enter_nesting("expression too deeply nested")?;
+        let expr = self.parse_unary_operand();
+        self.depth -= 1;
+        expr
+    }
+
+    fn parse_unary_operand(&mut self) -> Result<Expression, Box<dyn Diagnostic>> {
         if self.consume_if(&Token::Bang) {
-            // Minimal semantics: parse and discard the '!' operator.
-            return self.parse_unary();
+            return Ok(Expression::Not(Box::new(self.parse_unary()?)));
         }
 
         if self.consume_if(&Token::Minus) {
             let expr = self.parse_unary()?;
             return Ok(match expr {
                 Expression::Integer(i) => Expression::Integer(-i),
+                Expression::Float(f) => Expression::Float(-f),
                 other => Expression::Subtract {
                     left: Box::new(Expression::Integer(0)),
                     right: Box::new(other),
@@ -421,6 +538,11 @@ impl<'a> Parser<'a> {
                 self.advance();
                 Ok(Expression::Integer(value))
             }
+            Token::Float(value) => {
+                let value = *value;
+                self.advance();
+                Ok(Expression::Float(value))
+            }
             Token::Boolean(value) => {
                 let value = *value;
                 self.advance();
@@ -435,8 +557,8 @@ impl<'a> Parser<'a> {
                 let mut identifier = name.clone();
                 self.advance();
 
-                // this.<field> lowers to identifier "<field>" for now.
-                if identifier == "this" && self.consume_if(&Token::Dot) {
+                // this.<field> and self.<field> lower to identifier "<field>" for now.
+                if is_receiver(&identifier) && self.consume_if(&Token::Dot) {
                     identifier = self.consume_identifier("Expected field name")?;
                 }
 
@@ -453,9 +575,10 @@ impl<'a> Parser<'a> {
 
                 Ok(expr)
             }
-            Token::CoordLiteral => {
+            Token::CoordLiteral(x, y, z) => {
+                let (x, y, z) = (*x, *y, *z);
                 self.advance();
-                Ok(Expression::CoordLiteral)
+                Ok(Expression::CoordLiteral { x, y, z })
             }
             Token::LParen => {
                 self.advance();
@@ -494,6 +617,18 @@ impl<'a> Parser<'a> {
 
     // === Utility Methods ===
 
+    fn enter_nesting(&mut self, message: &str) -> Result<(), Box<dyn Diagnostic>> {
+        if self.depth >= self.max_depth {
+            return Err(Box::new(DiagnosticError::general(
+                message,
+                crate::diagnostics::SourceLocation::dummy(),
+            )));
+        }
+
+        self.depth += 1;
+        Ok(())
+    }
+
     fn consume_identifier(&mut self, message: &str) -> Result<String, Box<dyn Diagnostic>> {
         if let Token::Identifier(name) = &self.peek().token {
             let name = name.clone();
@@ -568,3 +703,124 @@ impl<'a> Parser<'a> {
 pub fn parse_program(tokens: &[SpannedToken]) -> Result<Program, Box<dyn Diagnostic>> {
     Parser::new(tokens).parse_program()
 }
+
+/// Reject a field whose name is already declared on the same process or event
+fn check_duplicate_field(
+    fields: &[FieldDeclaration],
+    field: &FieldDeclaration,
+    owner_kind: &str,
+    owner_name: &str,
+) -> Result<(), Box<dyn Diagnostic>> {
+    if fields.iter().any(|f| f.name == field.name) {
+        return Err(Box::new(DiagnosticError::general(
+            &format!("Duplicate field '{}' in {} {}", field.name, owner_kind, owner_name),
+            crate::diagnostics::SourceLocation::dummy(),
+        )));
+    }
+    Ok(())
+}
+
+/// `this` and `self` both name the current process
+fn is_receiver(name: &str) -> bool {
+    name == "this" || name == "self"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::lex;
+
+    fn method_source(body: &str) -> String {
+        format!(
+            "module M {{ process P {{ value: Int, method init() {{ {} }} }} }}",
+            body
+        )
+    }
+
+    #[test]
+    fn test_deeply_nested_parentheses_error_cleanly() {
+        let nested = format!("this.value = {}1{};", "(".repeat(10_000), ")".repeat(10_000));
+        let tokens = lex(&method_source(&nested)).unwrap();
+
+        let err = parse_program(&tokens).unwrap_err();
+        assert_eq!(err.message(), "expression too deeply nested");
+    }
+
+    #[test]
+    fn test_deeply_nested_unary_errors_cleanly() {
+        let nested = format!("this.value = {}1;", "!".repeat(10_000));
+        let tokens = lex(&method_source(&nested)).unwrap();
+
+        let err = parse_program(&tokens).unwrap_err();
+        assert_eq!(err.message(), "expression too deeply nested");
+    }
+
+    #[test]
+    fn test_max_depth_is_configurable() {
+        let tokens = lex(&method_source("this.value = ((1));")).unwrap();
+
+        assert!(Parser::new(&tokens).parse_program().is_ok());
+        assert!(Parser::new(&tokens).with_max_depth(3).parse_program().is_err());
+    }
+
+    #[test]
+    fn test_duplicate_process_field_rejected() {
+        let tokens = lex("module M { process P { value: Int, value: String } }").unwrap();
+
+        let err = parse_program(&tokens).unwrap_err();
+        assert_eq!(err.message(), "Duplicate field 'value' in process P");
+    }
+
+    #[test]
+    fn test_duplicate_event_field_rejected() {
+        let tokens = lex("module M { event Tick { amount: Int, amount: Int } }").unwrap();
+
+        let err = parse_program(&tokens).unwrap_err();
+        assert_eq!(err.message(), "Duplicate field 'amount' in event Tick");
+    }
+
+    #[test]
+    fn test_stray_top_level_token_rejected() {
+        let tokens = lex("module M {}\nconst LIMIT = 3;").unwrap();
+
+        let err = parse_program(&tokens).unwrap_err();
+        assert_eq!(err.message(), "expected 'module' at top level, found Const");
+        assert_eq!(err.location().span, (12, 17));
+    }
+
+    #[test]
+    fn test_source_without_modules_rejected() {
+        let err = crate::compile("").unwrap_err();
+        assert_eq!(err.message(), "no module found in source: input is empty");
+
+        for source in ["   \n\t", "// nothing here\n/* or here */\n"] {
+            let err = crate::compile(source).unwrap_err();
+            assert_eq!(
+                err.message(),
+                "no module found in source: input contains only whitespace and comments"
+            );
+        }
+    }
+
+    #[test]
+    fn test_bang_parses_as_not() {
+        let parse = |body: &str| parse_program(&lex(&method_source(body)).unwrap()).unwrap();
+
+        assert_ne!(parse("this.value = !this.flag;"), parse("this.value = this.flag;"));
+        assert_eq!(parse("this.value = !(this.flag);"), parse("this.value = !this.flag;"));
+    }
+
+    #[test]
+    fn test_self_and_this_are_equivalent() {
+        let parse = |body: &str| parse_program(&lex(&method_source(body)).unwrap()).unwrap();
+
+        assert_eq!(
+            parse("self.position = <1,2,3>;"),
+            parse("this.position = <1,2,3>;")
+        );
+        assert_eq!(
+            parse("self.value = self.value + 1;"),
+            parse("this.value = this.value + 1;")
+        );
+    }
+}
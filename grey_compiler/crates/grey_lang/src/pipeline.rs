@@ -0,0 +1,97 @@
+//! Staged compilation pipeline
+//!
+//! `Pipeline` runs the same stages as `compile_with_options` but keeps every
+//! intermediate artifact, so a caller can stop after lexing or parsing and later
+//! continue without redoing the earlier stages.
+
+use crate::ast::Program;
+use crate::diagnostics::Diagnostic;
+use crate::lexer::{self, SpannedToken};
+use crate::parser;
+use crate::types::TypedProgram;
+use crate::{CompileOptions, StageTimings};
+
+/// Compilation pipeline over a single source, caching each stage's output
+pub struct Pipeline {
+    source: String,
+    options: CompileOptions,
+    tokens: Option<Vec<SpannedToken>>,
+    program: Option<Program>,
+    typed_program: Option<TypedProgram>,
+}
+
+impl Pipeline {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self::with_options(source, CompileOptions::default())
+    }
+
+    /// A pipeline whose size limit and validation pass follow `options`
+    pub fn with_options(source: impl Into<String>, options: CompileOptions) -> Self {
+        Self {
+            source: source.into(),
+            options,
+            tokens: None,
+            program: None,
+            typed_program: None,
+        }
+    }
+
+    /// Tokenize the source, rejecting it first if it is over the size limit
+    pub fn lex(&mut self) -> Result<&[SpannedToken], Box<dyn Diagnostic>> {
+        if self.tokens.is_none() {
+            crate::check_source_size(self.source.len(), self.options)?;
+            self.tokens = Some(lexer::lex(&self.source)?);
+        }
+        Ok(self.tokens.as_deref().unwrap())
+    }
+
+    /// Parse the tokens into an untyped AST
+    pub fn parse(&mut self) -> Result<&Program, Box<dyn Diagnostic>> {
+        if self.program.is_none() {
+            let program = parser::parse_program(self.lex()?)?;
+            self.program = Some(program);
+        }
+        Ok(self.program.as_ref().unwrap())
+    }
+
+    /// Run the checks `compile_with_options` runs after parsing
+    pub fn check(&mut self) -> Result<&TypedProgram, Box<dyn Diagnostic>> {
+        if self.typed_program.is_none() {
+            let options = self.options;
+            let typed_program =
+                crate::finish_compile(self.parse()?, options, &mut StageTimings::default())?;
+            self.typed_program = Some(typed_program);
+        }
+        Ok(self.typed_program.as_ref().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Token;
+
+    #[test]
+    fn test_pipeline_exposes_each_stage() {
+        let source = "module Staged { process Node { count: Int } }";
+        let mut pipeline = Pipeline::new(source);
+
+        assert_eq!(pipeline.lex().unwrap()[0].token, Token::Module);
+        assert_eq!(pipeline.parse().unwrap().modules[0].name, "Staged");
+
+        let typed = pipeline.check().unwrap().clone();
+        assert_eq!(typed.modules[0].processes[0].fields[0].name, "count");
+        assert_eq!(typed, crate::compile(source).unwrap());
+    }
+
+    #[test]
+    fn test_pipeline_check_matches_compile_rejections() {
+        let source = "module M { process Grid { cells: [16] of int } }";
+        let err = Pipeline::new(source).check().unwrap_err();
+        assert_eq!(err.message(), crate::compile(source).unwrap_err().message());
+
+        let options = CompileOptions { max_source_bytes: 16, ..CompileOptions::default() };
+        let err = Pipeline::with_options("module Staged { }", options).lex().unwrap_err();
+        assert!(err.message().contains("over the 16-byte limit"), "{}", err.message());
+    }
+}
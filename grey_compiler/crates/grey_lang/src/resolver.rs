@@ -0,0 +1,186 @@
+//! File-level module resolution
+//!
+//! A source file may import other files with `use <name>;`. The resolver looks
+//! for `<name>.grey` next to the importing file and then in each registered
+//! search path, parses every file once and merges all their modules into a
+//! single `Program`. Circular imports are rejected.
+
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+
+use crate::ast::Program;
+use crate::diagnostics::{Diagnostic, DiagnosticError, SourceLocation};
+use crate::StageTimings;
+
+/// Resolves `use` declarations across files
+#[derive(Debug, Default)]
+pub struct ModuleResolver {
+    search_paths: Vec<PathBuf>,
+    timings: Cell<StageTimings>,
+}
+
+impl ModuleResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a directory to search for imported files
+    pub fn add_search_path(&mut self, dir: impl Into<PathBuf>) {
+        self.search_paths.push(dir.into());
+    }
+
+    /// Lexing and parsing time summed over every file resolved so far
+    pub fn timings(&self) -> StageTimings {
+        self.timings.get()
+    }
+
+    fn parse(&self, source: &str) -> Result<Program, Box<dyn Diagnostic>> {
+        let mut timings = self.timings.get();
+        let parsed = crate::parse_source_timed(source, &mut timings);
+        self.timings.set(timings);
+        parsed
+    }
+
+    /// Parse `root` and everything it imports, transitively, into one program.
+    ///
+    /// Imported modules come before the modules of the files that import them.
+    pub fn resolve(&self, root: &Path) -> Result<Program, Box<dyn Diagnostic>> {
+        let mut program = Program { uses: Vec::new(), modules: Vec::new() };
+        let mut loaded = Vec::new();
+        let mut stack = Vec::new();
+        self.load(root, &mut stack, &mut loaded, &mut program)?;
+        Ok(program)
+    }
+
+    /// Like `resolve`, for source that was not read from disk (e.g. stdin).
+    ///
+    /// `origin` stands in for the source's file path: imports are looked up next
+    /// to it first. It does not have to exist.
+    pub fn resolve_source(&self, source: &str, origin: &Path) -> Result<Program, Box<dyn Diagnostic>> {
+        let mut program = Program { uses: Vec::new(), modules: Vec::new() };
+        let mut loaded = Vec::new();
+        let mut stack = Vec::new();
+        let parsed = self.parse(source)?;
+        self.load_parsed(origin.to_path_buf(), parsed, &mut stack, &mut loaded, &mut program)?;
+        Ok(program)
+    }
+
+    fn load(
+        &self,
+        path: &Path,
+        stack: &mut Vec<PathBuf>,
+        loaded: &mut Vec<PathBuf>,
+        program: &mut Program,
+    ) -> Result<(), Box<dyn Diagnostic>> {
+        let path = path.canonicalize().map_err(|e| error(format!("Cannot open {}: {}", path.display(), e)))?;
+
+        if let Some(start) = stack.iter().position(|p| p == &path) {
+            let cycle: Vec<_> = stack[start..]
+                .iter()
+                .chain(std::iter::once(&path))
+                .map(|p| file_stem(p))
+                .collect();
+            return Err(error(format!("Circular import: {}", cycle.join(" -> "))));
+        }
+        if loaded.contains(&path) {
+            return Ok(());
+        }
+
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| error(format!("Cannot read {}: {}", path.display(), e)))?;
+        let parsed = self.parse(&source)?;
+        self.load_parsed(path, parsed, stack, loaded, program)
+    }
+
+    fn load_parsed(
+        &self,
+        path: PathBuf,
+        parsed: Program,
+        stack: &mut Vec<PathBuf>,
+        loaded: &mut Vec<PathBuf>,
+        program: &mut Program,
+    ) -> Result<(), Box<dyn Diagnostic>> {
+        stack.push(path.clone());
+        for name in &parsed.uses {
+            let import = self.find(name, &path)?;
+            self.load(&import, stack, loaded, program)?;
+        }
+        stack.pop();
+
+        loaded.push(path);
+        program.modules.extend(parsed.modules);
+        Ok(())
+    }
+
+    fn find(&self, name: &str, importer: &Path) -> Result<PathBuf, Box<dyn Diagnostic>> {
+        let file_name = format!("{}.grey", name);
+        importer
+            .parent()
+            .into_iter()
+            .chain(self.search_paths.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(&file_name))
+            .find(|candidate| candidate.is_file())
+            .ok_or_else(|| {
+                error(format!(
+                    "Cannot find '{}' imported by {}",
+                    file_name,
+                    importer.display()
+                ))
+            })
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn error(message: String) -> Box<dyn Diagnostic> {
+    Box::new(DiagnosticError::general(&message, SourceLocation::dummy()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("grey_resolver_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_use_resolves_from_search_path() {
+        let root_dir = temp_dir("root");
+        let lib_dir = temp_dir("lib");
+        fs::write(lib_dir.join("events.grey"), "module Events { event Tick {} }").unwrap();
+        fs::write(
+            root_dir.join("main.grey"),
+            "use events;\nmodule Main { process Clock { ticks: Int, method handle_tick(event: Tick) {} } }",
+        )
+        .unwrap();
+
+        let mut resolver = ModuleResolver::new();
+        resolver.add_search_path(&lib_dir);
+        let program = resolver.resolve(&root_dir.join("main.grey"));
+        fs::remove_dir_all(&root_dir).ok();
+        fs::remove_dir_all(&lib_dir).ok();
+
+        let names: Vec<_> = program.unwrap().modules.into_iter().map(|m| m.name).collect();
+        assert_eq!(names, vec!["Events", "Main"]);
+    }
+
+    #[test]
+    fn test_circular_use_rejected() {
+        let dir = temp_dir("cycle");
+        fs::write(dir.join("a.grey"), "use b;\nmodule A {}").unwrap();
+        fs::write(dir.join("b.grey"), "use a;\nmodule B {}").unwrap();
+
+        let err = ModuleResolver::new().resolve(&dir.join("a.grey")).unwrap_err();
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(err.message(), "Circular import: a -> b -> a");
+    }
+}
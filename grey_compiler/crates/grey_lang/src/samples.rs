@@ -0,0 +1,47 @@
+//! Generated sample programs
+//!
+//! Synthetic sources for benchmarks and scaling studies, where the size of the
+//! program matters more than what it computes.
+
+/// Source of a module with `process_count` processes that all handle one event.
+///
+/// Each process has two fields, an `init` method and a `handle_tick` handler, so
+/// every compiler stage has per-process work proportional to `process_count`.
+pub fn scaling_module_source(process_count: usize) -> String {
+    let mut source = String::from("module Scaling {\n    const LIMIT = 100;\n\n    event Tick {\n        amount: Int,\n    }\n");
+
+    for i in 0..process_count {
+        source.push_str(&format!(
+            r#"
+    process Node{i} {{
+        count: Int,
+        total: Int,
+
+        method init() {{
+            this.count = 0;
+            this.total = {i};
+        }}
+
+        method handle_tick(event: Tick) {{
+            this.count = this.count + 1;
+            this.total = this.total + this.count * 2;
+        }}
+    }}
+"#
+        ));
+    }
+
+    source.push_str("}\n");
+    source
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaling_module_compiles() {
+        let typed = crate::compile(&scaling_module_source(3)).unwrap();
+        assert_eq!(typed.modules[0].processes.len(), 3);
+    }
+}
@@ -3,16 +3,23 @@
 //! This module provides basic type checking for Grey programs.
 
 use crate::ast::*;
-use crate::diagnostics::Diagnostic;
+use crate::diagnostics::{Diagnostic, DiagnosticError, SourceLocation};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Bytes reserved for a `String` field when sizing process state statically.
+///
+/// The IR and the generated C structs size strings the same way.
+pub const STRING_FIELD_CAPACITY: usize = 64;
 
 /// Typed program with all types resolved
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypedProgram {
     pub modules: Vec<TypedModule>,
 }
 
 /// Typed module
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypedModule {
     pub name: String,
     pub constants: Vec<TypedConstantDeclaration>,
@@ -21,14 +28,14 @@ pub struct TypedModule {
 }
 
 /// Typed constant declaration
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypedConstantDeclaration {
     pub name: String,
     pub value: TypedExpression,
 }
 
 /// Typed process definition
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypedProcessDefinition {
     pub name: String,
     pub fields: Vec<TypedFieldDeclaration>,
@@ -36,21 +43,21 @@ pub struct TypedProcessDefinition {
 }
 
 /// Typed field declaration
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypedFieldDeclaration {
     pub name: String,
     pub field_type: Type,
 }
 
 /// Typed event definition
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypedEventDefinition {
     pub name: String,
     pub fields: Vec<TypedFieldDeclaration>,
 }
 
 /// Typed function definition
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypedFunctionDefinition {
     pub name: String,
     pub parameters: Vec<TypedFunctionParameter>,
@@ -59,21 +66,21 @@ pub struct TypedFunctionDefinition {
 }
 
 /// Typed function parameter
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypedFunctionParameter {
     pub name: String,
     pub param_type: Type,
 }
 
 /// Typed expression with resolved types
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypedExpression {
     pub expression: Expression,
     pub type_: Type,
 }
 
 /// Typed block expression
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypedBlockExpression {
     pub statements: Vec<TypedStatement>,
     pub result: Option<Box<TypedExpression>>,
@@ -81,7 +88,7 @@ pub struct TypedBlockExpression {
 }
 
 /// Typed statement
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TypedStatement {
     Expression(TypedExpression),
     Let {
@@ -89,16 +96,25 @@ pub enum TypedStatement {
         value: TypedExpression,
     },
     Return(Option<TypedExpression>),
+    For {
+        variable: String,
+        start: TypedExpression,
+        end: TypedExpression,
+        body: Vec<TypedStatement>,
+    },
 }
 
 /// Type representation for the type system
-#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+#[derive(Debug, Clone, PartialEq, Hash, Eq, Serialize, Deserialize)]
 pub enum Type {
     Int,
+    Float,
     String,
     Bool,
     Coord,
     Named(String),
+    Array(Box<Type>, usize),
+    List(Box<Type>),
     Unit,
 }
 
@@ -107,19 +123,64 @@ impl Type {
     pub fn type_name(&self) -> String {
         match self {
             Type::Int => "int".to_string(),
+            Type::Float => "float".to_string(),
             Type::String => "string".to_string(),
             Type::Bool => "bool".to_string(),
             Type::Coord => "coord".to_string(),
             Type::Named(name) => name.clone(),
+            Type::Array(element, length) => format!("[{}] of {}", length, element.type_name()),
+            Type::List(element) => format!("[] of {}", element.type_name()),
             Type::Unit => "()".to_string(),
         }
     }
+
+    /// Static memory footprint of a value of this type, in bytes.
+    ///
+    /// Strings count at `STRING_FIELD_CAPACITY`; lists and named types count as a
+    /// fixed-size handle; arrays multiply their capacity by the element size.
+    pub fn static_size_bytes(&self) -> usize {
+        match self {
+            Type::Int | Type::Float => 8,
+            Type::Bool => 1,
+            Type::Coord => 12,
+            Type::String => STRING_FIELD_CAPACITY,
+            Type::Named(_) => 8,
+            Type::Array(element, length) => element.static_size_bytes().saturating_mul(*length),
+            Type::List(_) => 24,
+            Type::Unit => 0,
+        }
+    }
+
+    /// Check whether a value of this type may be used where `target` is expected.
+    ///
+    /// Every type is assignable to itself; the only implicit conversion is int → float.
+    pub fn is_assignable_to(&self, target: &Type) -> bool {
+        self == target || matches!((self, target), (Type::Int, Type::Float))
+    }
 }
 
 /// Type checking context
 pub struct TypeChecker {
     /// Errors encountered during type checking
     errors: Vec<Box<dyn Diagnostic>>,
+    /// Declared field types of the process currently being checked
+    current_fields: HashMap<String, Type>,
+    /// Declared return type of the method currently being checked
+    current_return_type: Option<Type>,
+    /// Name of the `handle_*` method currently being checked, if any
+    current_handler: Option<String>,
+    /// Names of the events declared anywhere in the program being checked
+    current_events: HashSet<String>,
+    /// Names a `Type::Named` may refer to: every declared process and event
+    declared_types: HashSet<String>,
+}
+
+/// Event handlers react to events; there is no caller to receive a value
+fn handler_return_error(handler: &str) -> Box<dyn Diagnostic> {
+    Box::new(DiagnosticError::general(
+        &format!("Handler {} must not return a value", handler),
+        SourceLocation::dummy(),
+    ))
 }
 
 impl TypeChecker {
@@ -127,6 +188,11 @@ impl TypeChecker {
     pub fn new() -> Self {
         Self {
             errors: Vec::new(),
+            current_fields: HashMap::new(),
+            current_return_type: None,
+            current_handler: None,
+            current_events: HashSet::new(),
+            declared_types: HashSet::new(),
         }
     }
     
@@ -134,6 +200,17 @@ impl TypeChecker {
     pub fn check_program(&mut self, program: &Program) -> Result<TypedProgram, Box<dyn Diagnostic>> {
         // Clear previous errors
         self.errors.clear();
+        self.current_events = program
+            .modules
+            .iter()
+            .flat_map(|m| m.events.iter().map(|e| e.name.clone()))
+            .collect();
+        self.declared_types = program
+            .modules
+            .iter()
+            .flat_map(|m| m.processes.iter().map(|p| p.name.clone()))
+            .chain(self.current_events.iter().cloned())
+            .collect();
         
         // Type check each module
         let mut typed_modules = Vec::new();
@@ -203,12 +280,24 @@ impl TypeChecker {
             });
         }
         
+        self.current_fields = typed_fields
+            .iter()
+            .map(|field| (field.name.clone(), field.field_type.clone()))
+            .collect();
+
         // Type check methods
         let mut typed_methods = Vec::new();
         for method in &process.methods {
-            let typed_method = self.check_function_definition(method)?;
-            typed_methods.push(typed_method);
+            if method.name.starts_with("handle_") {
+                self.check_handler_signature(method)?;
+                self.current_handler = Some(method.name.clone());
+            }
+            let typed_method = self.check_function_definition(method);
+            self.current_handler = None;
+            typed_methods.push(typed_method?);
         }
+
+        self.current_fields.clear();
         
         Ok(TypedProcessDefinition {
             name: process.name.clone(),
@@ -217,6 +306,37 @@ impl TypeChecker {
         })
     }
     
+    /// Require `handle_*` methods to take exactly one parameter of a declared event
+    /// type and to declare no return type
+    fn check_handler_signature(&self, method: &FunctionDefinition) -> Result<(), Box<dyn Diagnostic>> {
+        if method.return_type.is_some() {
+            return Err(handler_return_error(&method.name));
+        }
+
+        if let [param] = method.parameters.as_slice() {
+            if let crate::ast::Type::Named(name) = &param.param_type {
+                if self.current_events.contains(name) {
+                    return Ok(());
+                }
+            }
+        }
+
+        let suffix = method.name.trim_start_matches("handle_");
+        let mut chars = suffix.chars();
+        let suggested_event = chars
+            .next()
+            .map(|first| first.to_uppercase().collect::<String>() + chars.as_str())
+            .unwrap_or_else(|| "Event".to_string());
+
+        Err(Box::new(DiagnosticError::general(
+            &format!(
+                "Handler {} must take a single event parameter, e.g. `method {}(event: {})`",
+                method.name, method.name, suggested_event
+            ),
+            SourceLocation::dummy(),
+        )))
+    }
+
     /// Type check an event definition
     fn check_event(&mut self, event: &EventDefinition) -> Result<TypedEventDefinition, Box<dyn Diagnostic>> {
         // Type check fields
@@ -255,7 +375,10 @@ impl TypeChecker {
         };
         
         // Type check body
-        let body_type = self.check_block_expression(&function.body)?;
+        self.current_return_type = Some(return_type.clone());
+        let body_type = self.check_block_expression(&function.body);
+        self.current_return_type = None;
+        let body_type = body_type?;
         
         Ok(TypedFunctionDefinition {
             name: function.name.clone(),
@@ -301,19 +424,50 @@ impl TypeChecker {
             }
             Statement::Let { pattern, value } => {
                 let typed_value = self.check_expression(value)?;
+                let (Pattern::Identifier(name) | Pattern::Field(name)) = pattern;
+                if let Some(field_type) = self.current_fields.get(name) {
+                    Self::check_assignable(
+                        &typed_value.type_,
+                        field_type,
+                        &format!("assignment to field '{}'", name),
+                    )?;
+                }
                 Ok(TypedStatement::Let {
                     pattern: pattern.clone(),
                     value: typed_value,
                 })
             }
             Statement::Return(value) => {
+                if let (Some(_), Some(handler)) = (value, &self.current_handler) {
+                    return Err(handler_return_error(handler));
+                }
                 let typed_value = if let Some(ref val) = value {
                     Some(self.check_expression(val)?)
                 } else {
                     None
                 };
+                if let (Some(typed), Some(expected)) = (&typed_value, &self.current_return_type) {
+                    Self::check_assignable(&typed.type_, expected, "return value")?;
+                }
                 Ok(TypedStatement::Return(typed_value))
             }
+            Statement::For { variable, start, end, body } => {
+                let start = self.check_expression(start)?;
+                let end = self.check_expression(end)?;
+                Self::check_assignable(&start.type_, &Type::Int, "loop range start")?;
+                Self::check_assignable(&end.type_, &Type::Int, "loop range end")?;
+
+                let body = body
+                    .iter()
+                    .map(|statement| self.check_statement(statement))
+                    .collect::<Result<_, _>>()?;
+                Ok(TypedStatement::For {
+                    variable: variable.clone(),
+                    start,
+                    end,
+                    body,
+                })
+            }
         }
     }
     
@@ -324,6 +478,10 @@ impl TypeChecker {
                 expression: expression.clone(),
                 type_: Type::Int,
             }),
+            Expression::Float(_value) => Ok(TypedExpression {
+                expression: expression.clone(),
+                type_: Type::Float,
+            }),
             Expression::Boolean(_value) => Ok(TypedExpression {
                 expression: expression.clone(),
                 type_: Type::Bool,
@@ -339,7 +497,7 @@ impl TypeChecker {
                     type_: Type::Unit,
                 })
             }
-            Expression::CoordLiteral => Ok(TypedExpression {
+            Expression::CoordLiteral { .. } => Ok(TypedExpression {
                 expression: expression.clone(),
                 type_: Type::Coord,
             }),
@@ -347,30 +505,298 @@ impl TypeChecker {
                 expression: expression.clone(),
                 type_: Type::Unit,
             }),
-            Expression::Add { .. }
-            | Expression::Subtract { .. }
-            | Expression::Multiply { .. }
-            | Expression::Divide { .. } => Ok(TypedExpression {
-                expression: expression.clone(),
-                type_: Type::Int,
-            }),
+            Expression::If { condition, then_branch, else_branch } => {
+                let condition_type = self.check_expression(condition)?.type_;
+                Self::check_assignable(&condition_type, &Type::Bool, "if condition")?;
+                for statement in then_branch.iter().chain(else_branch) {
+                    self.check_statement(statement)?;
+                }
+
+                Ok(TypedExpression {
+                    expression: expression.clone(),
+                    type_: Type::Unit,
+                })
+            }
+            Expression::Not(operand) => {
+                let operand_type = self.check_expression(operand)?.type_;
+                Self::check_assignable(&operand_type, &Type::Bool, "'!' operand")?;
+
+                Ok(TypedExpression {
+                    expression: expression.clone(),
+                    type_: Type::Bool,
+                })
+            }
+            Expression::Add { left, right }
+            | Expression::Subtract { left, right }
+            | Expression::Multiply { left, right }
+            | Expression::Divide { left, right } => {
+                let left_type = self.check_expression(left)?.type_;
+                let right_type = self.check_expression(right)?.type_;
+                Self::check_assignable(&left_type, &Type::Float, "arithmetic operand")?;
+                Self::check_assignable(&right_type, &Type::Float, "arithmetic operand")?;
+
+                let type_ = if left_type == Type::Float || right_type == Type::Float {
+                    Type::Float
+                } else {
+                    Type::Int
+                };
+
+                Ok(TypedExpression {
+                    expression: expression.clone(),
+                    type_,
+                })
+            }
+        }
+    }
+
+    /// Report a mismatch unless `actual` is assignable to `expected`.
+    ///
+    /// `Unit` stands in for types the minimal checker cannot resolve yet
+    /// (identifiers, calls), so it is accepted everywhere.
+    fn check_assignable(actual: &Type, expected: &Type, context: &str) -> Result<(), Box<dyn Diagnostic>> {
+        if *actual == Type::Unit || actual.is_assignable_to(expected) {
+            return Ok(());
         }
+
+        Err(Box::new(DiagnosticError::general(
+            &format!(
+                "Type mismatch in {}: expected {}, found {}",
+                context,
+                expected.type_name(),
+                actual.type_name()
+            ),
+            SourceLocation::dummy(),
+        )))
     }
     
     /// Convert AST type to type system type
     fn convert_ast_type(&self, ast_type: &crate::ast::Type) -> Result<Type, Box<dyn Diagnostic>> {
         match ast_type {
             crate::ast::Type::Int => Ok(Type::Int),
+            crate::ast::Type::Float => Ok(Type::Float),
             crate::ast::Type::String => Ok(Type::String),
             crate::ast::Type::Bool => Ok(Type::Bool),
             crate::ast::Type::Coord => Ok(Type::Coord),
-            crate::ast::Type::Named(name) => Ok(Type::Named(name.clone())),
+            crate::ast::Type::Named(name) if self.declared_types.contains(name) => {
+                Ok(Type::Named(name.clone()))
+            }
+            crate::ast::Type::Named(name) => Err(Box::new(DiagnosticError::general(
+                &format!("Unknown type '{}': not a declared process or event", name),
+                SourceLocation::dummy(),
+            ))),
+            crate::ast::Type::Array { length, element } => {
+                Ok(Type::Array(Box::new(self.convert_ast_type(element)?), *length))
+            }
+            crate::ast::Type::List(element) => Ok(Type::List(Box::new(self.convert_ast_type(element)?))),
         }
     }
 }
 
+/// Reject fixed-capacity array fields, which type check but have no IR
+/// representation yet, so the program would fail later during lowering.
+///
+/// Runs after O(1) validation, which still sizes arrays and checks their elements.
+pub fn check_ir_field_types(program: &TypedProgram) -> Result<(), Box<dyn Diagnostic>> {
+    let processes = program
+        .modules
+        .iter()
+        .flat_map(|m| m.processes.iter().map(|p| ("process", &p.name, &p.fields)));
+    let events = program
+        .modules
+        .iter()
+        .flat_map(|m| m.events.iter().map(|e| ("event", &e.name, &e.fields)));
+
+    for (kind, owner, fields) in processes.chain(events) {
+        if let Some(field) = fields.iter().find(|f| matches!(f.field_type, Type::Array(..))) {
+            return Err(Box::new(DiagnosticError::general(
+                &format!(
+                    "Field '{}' of {} {} has type {}: array fields are not yet supported by the IR",
+                    field.name,
+                    kind,
+                    owner,
+                    field.field_type.type_name()
+                ),
+                SourceLocation::dummy(),
+            )));
+        }
+    }
+    Ok(())
+}
+
 impl Default for TypeChecker {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_int_assignable_to_float() {
+        assert!(Type::Int.is_assignable_to(&Type::Float));
+        assert!(Type::Int.is_assignable_to(&Type::Int));
+    }
+
+    #[test]
+    fn test_float_not_assignable_to_int() {
+        assert!(!Type::Float.is_assignable_to(&Type::Int));
+    }
+
+    #[test]
+    fn test_string_not_assignable_to_int() {
+        assert!(!Type::String.is_assignable_to(&Type::Int));
+    }
+
+    #[test]
+    fn test_field_assignment_mismatch_rejected() {
+        let source = r#"
+            module M {
+                process P {
+                    count: Int,
+                    method init() {
+                        this.count = "zero";
+                    }
+                }
+            }
+        "#;
+
+        let err = crate::compile(source).unwrap_err();
+        assert!(err.message().contains("expected int, found string"));
+    }
+
+    #[test]
+    fn test_typed_program_serde_round_trip() {
+        let source = r#"
+            module M {
+                const LIMIT = 3;
+                event Tick { amount: Int }
+                process P {
+                    count: Int,
+                    method init() {
+                        this.count = LIMIT * 2;
+                    }
+                }
+            }
+        "#;
+
+        let typed = crate::compile(source).unwrap();
+        let json = serde_json::to_string(&typed).unwrap();
+        let restored: TypedProgram = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, typed);
+    }
+
+    #[test]
+    fn test_float_suffix_overrides_int_inference() {
+        let source = |value: &str| {
+            format!(
+                r#"
+                module M {{
+                    process P {{
+                        count: Int,
+                        ratio: Float,
+                        method init() {{
+                            {}
+                        }}
+                    }}
+                }}
+                "#,
+                value
+            )
+        };
+
+        assert!(crate::compile(&source("this.ratio = 2.5f; this.count = 42i64;")).is_ok());
+
+        let err = crate::compile(&source("this.count = 42f;")).unwrap_err();
+        assert!(err.message().contains("expected int, found float"));
+    }
+
+    #[test]
+    fn test_handler_requires_event_parameter() {
+        let source = |handler: &str| {
+            format!(
+                "module M {{ event Foo {{}} process P {{ count: Int, method {} {{}} }} }}",
+                handler
+            )
+        };
+
+        let err = crate::compile(&source("handle_foo()")).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "Handler handle_foo must take a single event parameter, e.g. `method handle_foo(event: Foo)`"
+        );
+        assert!(crate::compile(&source("handle_foo(event: Foo)")).is_ok());
+    }
+
+    #[test]
+    fn test_handler_must_not_return_a_value() {
+        let source = |handler: &str| {
+            format!(
+                "module M {{ event Foo {{}} process P {{ count: Int, method {} }} }}",
+                handler
+            )
+        };
+
+        for handler in [
+            "handle_foo(event: Foo) { return 5; }",
+            "handle_foo(event: Foo) -> Int { return 5; }",
+        ] {
+            let err = crate::compile(&source(handler)).unwrap_err();
+            assert_eq!(err.message(), "Handler handle_foo must not return a value");
+        }
+
+        assert!(crate::compile(&source("handle_foo(event: Foo) { this.count = 1; return; }")).is_ok());
+        assert!(crate::compile(&source("total() -> Int { return 5; }")).is_ok());
+    }
+
+    #[test]
+    fn test_named_types_must_be_declared() {
+        let source = |field_type: &str| {
+            format!(
+                "module M {{ event Drone {{}} process Hub {{ last: {}, method init() {{}} }} }}",
+                field_type
+            )
+        };
+
+        let err = crate::compile(&source("Dorne")).unwrap_err();
+        assert_eq!(err.message(), "Unknown type 'Dorne': not a declared process or event");
+
+        let typed = crate::compile(&source("Drone")).unwrap();
+        let field = &typed.modules[0].processes[0].fields[0];
+        assert_eq!(field.field_type, Type::Named("Drone".to_string()));
+        assert!(crate::compile(&source("Hub")).is_ok());
+    }
+
+    #[test]
+    fn test_array_fields_rejected_until_the_ir_supports_them() {
+        let err = crate::compile("module M { process Grid { cells: [16] of int } }").unwrap_err();
+        assert_eq!(
+            err.message(),
+            "Field 'cells' of process Grid has type [16] of int: array fields are not yet supported by the IR"
+        );
+
+        let err = crate::compile("module M { event Batch { ids: [4] of int } process P { count: Int } }").unwrap_err();
+        assert!(err.message().starts_with("Field 'ids' of event Batch"), "{}", err.message());
+    }
+
+    #[test]
+    fn test_mixed_arithmetic_type_checks() {
+        let typed = crate::compile(
+            "module M { process P { a: Int, b: Int, c: Int, d: Int, \
+             method init() { this.d = this.a - this.b * this.c / 2; } } }",
+        )
+        .unwrap();
+
+        let init = &typed.modules[0].processes[0].methods[0];
+        let TypedStatement::Let { value, .. } = &init.body.statements[0] else {
+            panic!("expected the field assignment");
+        };
+        assert_eq!(value.type_, Type::Int);
+        let Expression::Subtract { right, .. } = &value.expression else {
+            panic!("expected a subtraction, got {:?}", value.expression);
+        };
+        assert!(matches!(**right, Expression::Divide { .. }));
+    }
+}
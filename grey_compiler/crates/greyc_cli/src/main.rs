@@ -2,30 +2,69 @@
 //! 
 //! Command-line interface for the Grey programming language compiler.
 
-use clap::{Parser, Subcommand};
-use grey_lang::compile;
-use grey_ir::IrBuilder;
-use grey_backends::betti_rdl::BettiRdlBackend;
-use grey_backends::CodeGenerator;
+use clap::{Parser, Subcommand, ValueEnum};
+use grey_lang::constraints::{ConstraintViolation, O1Validator};
+use grey_lang::diagnostics::Diagnostic;
+use grey_lang::types::TypedProgram;
+use grey_lang::{
+    compile_file_traced, compile_source_at_traced, compile_with_options, CompileOptions, StageTimings,
+};
+use grey_ir::{Coord, IrBuilder, IrProgram, IrWarning};
+use grey_backends::betti_rdl::{BettiConfig, BettiRdlBackend};
+use grey_backends::c_header::CHeaderBackend;
+use grey_backends::dot_graph::DotGraphBackend;
+use grey_backends::{CodeGenOutput, CodeGenerator, ExecutionTelemetry};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
 use std::fs;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 #[derive(Parser)]
 #[command(name = "greyc")]
 #[command(about = "Grey Programming Language Compiler")]
 #[command(version = "0.1.0")]
 struct Cli {
+    /// Skip the O(1) constraint validation pass (lexing, parsing and type checking still run)
+    #[arg(long, global = true)]
+    no_validate: bool,
+
+    /// Treat warnings as errors and exit non-zero if any are reported (also `-Werror`)
+    #[arg(long, global = true)]
+    deny_warnings: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// What executes a workload for `emit-betti --run`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Engine {
+    /// The Betti RDL kernel, through FFI
+    Kernel,
+    /// The pure-Rust IR interpreter
+    Interpreter,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Check a Grey source file for errors
     Check {
-        /// Input file to check
+        /// Input file to check, or `-` to read the source from stdin
         input: PathBuf,
+
+        /// Re-run the check whenever the input file changes
+        #[arg(long)]
+        watch: bool,
+
+        /// Directory to search for files imported with `use` (repeatable)
+        #[arg(long = "include")]
+        include: Vec<PathBuf>,
+
+        /// Print how long each compile stage took
+        #[arg(long)]
+        timings: bool,
     },
     
     /// Start an interactive REPL
@@ -33,25 +72,506 @@ enum Commands {
     
     /// Emit Betti RDL executable from Grey source
     EmitBetti {
-        /// Input Grey source file
+        /// Input Grey source file, or `-` to read the source from stdin
         input: PathBuf,
         
         /// Run the generated executable
         #[arg(long)]
         run: bool,
         
-        /// Maximum events to process
-        #[arg(long, default_value = "1000")]
+        /// Maximum events to process (0 means unbounded)
+        #[arg(long, default_value = "1000", allow_negative_numbers = true)]
         max_events: i32,
 
         /// Deterministic seed used for injection patterns
         #[arg(long, default_value = "42")]
         seed: u64,
+
+        /// Directory to search for files imported with `use` (repeatable)
+        #[arg(long = "include")]
+        include: Vec<PathBuf>,
+
+        /// Run once per seed (comma-separated) and report each run's telemetry
+        #[arg(long, value_delimiter = ',')]
+        seeds: Vec<u64>,
         
         /// Enable telemetry output
         #[arg(long)]
         telemetry: bool,
+
+        /// Estimated nanoseconds per event, used for the execution time estimate
+        #[arg(long, default_value = "1000")]
+        per_event_ns: u64,
+
+        /// Estimated nanoseconds per runtime process, used for the execution time estimate
+        #[arg(long, default_value = "500")]
+        per_process_ns: u64,
+
+        /// Report the files, process count and time estimate without generating code
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Engine that executes the workload with `--run`
+        #[arg(long, value_enum, default_value = "kernel")]
+        engine: Engine,
+
+        /// JSON file of `[coord, value]` pairs to inject instead of the seeded pattern
+        #[arg(long, value_name = "FILE")]
+        injections: Option<PathBuf>,
+
+        /// Print each runtime process's coordinate and node id after code generation
+        #[arg(long)]
+        placement_report: bool,
+
+        /// Reject coordinates outside 0..=31 instead of wrapping them onto the lattice
+        #[arg(long)]
+        strict_coords: bool,
+    },
+
+    /// Compile and execute a Grey program on the default backend without writing files
+    Run {
+        /// Input Grey source file, or `-` to read the source from stdin
+        input: PathBuf,
+
+        /// Directory to search for files imported with `use` (repeatable)
+        #[arg(long = "include")]
+        include: Vec<PathBuf>,
+
+        /// Maximum events to process (0 means unbounded)
+        #[arg(long, default_value = "1000", allow_negative_numbers = true)]
+        max_events: i32,
+
+        /// Deterministic seed used for injection patterns
+        #[arg(long, default_value = "42")]
+        seed: u64,
+    },
+
+    /// Print structural metrics and the estimated execution time of a program
+    Stats {
+        /// Input Grey source file, or `-` to read the source from stdin
+        input: PathBuf,
+
+        /// Directory to search for files imported with `use` (repeatable)
+        #[arg(long = "include")]
+        include: Vec<PathBuf>,
+
+        /// Print the stats as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List the available code generation backends
+    Backends,
+
+    /// Show the configuration options of a backend
+    BackendOptions {
+        /// Backend name, as listed by `greyc backends`
+        name: String,
+    },
+
+    /// Emit a Graphviz DOT graph of the program topology next to the source
+    EmitDot {
+        /// Input Grey source file, or `-` to read the source from stdin
+        input: PathBuf,
+
+        /// Directory to search for files imported with `use` (repeatable)
+        #[arg(long = "include")]
+        include: Vec<PathBuf>,
+
+        /// Also render a PNG with `dot -Tpng` if Graphviz is installed
+        #[arg(long)]
+        png: bool,
     },
+
+    /// Emit a C header of event tags, event payloads and process state layouts next to the source
+    EmitHeader {
+        /// Input Grey source file, or `-` to read the source from stdin
+        input: PathBuf,
+
+        /// Directory to search for files imported with `use` (repeatable)
+        #[arg(long = "include")]
+        include: Vec<PathBuf>,
+    },
+}
+
+/// Describe a backend's configuration options, sorted by name
+fn format_backend_options(name: &str) -> anyhow::Result<String> {
+    let backend = grey_backends::create_backend(name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown backend '{}'. Available backends: {}",
+            name,
+            grey_backends::available_backends().join(", ")
+        )
+    })?;
+
+    let mut options: Vec<_> = backend.config_options().into_values().collect();
+    options.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if options.is_empty() {
+        return Ok(format!("Backend '{}' has no configuration options\n", name));
+    }
+
+    let mut text = String::new();
+    for option in options {
+        text.push_str(&format!("{}\n", option.name));
+        text.push_str(&format!("  {}\n", option.description));
+        text.push_str(&format!("  default: {}\n", option.default));
+        if !option.allowed_values.is_empty() {
+            text.push_str(&format!("  allowed: {}\n", option.allowed_values.join(", ")));
+        }
+    }
+
+    Ok(text)
+}
+
+/// Compile a Grey source and write its DOT topology graph next to it
+fn emit_dot(input: &SourceInput, include: &[PathBuf], options: CompileOptions) -> anyhow::Result<PathBuf> {
+    emit_next_to_source(input, include, options, "dot", |program| DotGraphBackend::new().render(program))
+}
+
+/// Compile a Grey source and write its C header next to it
+fn emit_header(input: &SourceInput, include: &[PathBuf], options: CompileOptions) -> anyhow::Result<PathBuf> {
+    emit_next_to_source(input, include, options, "h", |program| CHeaderBackend::new().render(program))
+}
+
+/// Compile a Grey source and write `render`'s output beside it with `extension`.
+/// Source read from stdin is written to the working directory as `stdin.<extension>`.
+fn emit_next_to_source(
+    input: &SourceInput,
+    include: &[PathBuf],
+    options: CompileOptions,
+    extension: &str,
+    render: impl Fn(&IrProgram) -> String,
+) -> anyhow::Result<PathBuf> {
+    let (ir_program, _) = input.compile_to_ir(include, options)?;
+
+    let output_path = input.path().with_extension(extension);
+    fs::write(&output_path, render(&ir_program))?;
+    Ok(output_path)
+}
+
+/// Where a command reads its Grey source from
+enum SourceInput {
+    File(PathBuf),
+    /// Source piped in on stdin, selected with `-` as the input path
+    Stdin(String),
+}
+
+impl SourceInput {
+    /// Stand-in path for stdin source. It names the program and makes `use`
+    /// imports resolve from the working directory.
+    const STDIN_PATH: &'static str = "stdin.grey";
+
+    /// Read stdin for `-`; otherwise check that `input` is an existing `.grey` file
+    fn open(input: PathBuf, mut stdin: impl io::Read) -> anyhow::Result<Self> {
+        if input == Path::new("-") {
+            let mut source = String::new();
+            stdin.read_to_string(&mut source)?;
+            return Ok(SourceInput::Stdin(source));
+        }
+
+        if !input.exists() {
+            anyhow::bail!("Input file '{}' does not exist", input.display());
+        }
+
+        if input.extension().is_none_or(|ext| ext != "grey") {
+            anyhow::bail!("Input file must have .grey extension");
+        }
+
+        Ok(SourceInput::File(input))
+    }
+
+    fn path(&self) -> &Path {
+        match self {
+            SourceInput::File(path) => path,
+            SourceInput::Stdin(_) => Path::new(Self::STDIN_PATH),
+        }
+    }
+
+    fn display(&self) -> String {
+        match self {
+            SourceInput::File(path) => path.display().to_string(),
+            SourceInput::Stdin(_) => "<stdin>".to_string(),
+        }
+    }
+
+    /// Name of the built program: the input file's stem
+    fn program_name(&self) -> &str {
+        self.path().file_stem().and_then(|s| s.to_str()).unwrap_or("program")
+    }
+
+    /// Compile the source and its imports and build its IR, returning the IR
+    /// builder's warnings alongside the program
+    fn compile_to_ir(
+        &self,
+        include: &[PathBuf],
+        options: CompileOptions,
+    ) -> anyhow::Result<(IrProgram, Vec<IrWarning>)> {
+        let typed_program = self.compile(include, options)
+            .map_err(|e| anyhow::anyhow!("Compilation failed: {:?}", e))?;
+
+        let mut ir_builder = IrBuilder::new();
+        let ir_program = ir_builder.build_program(self.program_name(), &typed_program)
+            .map_err(|e| anyhow::anyhow!("IR building failed: {}", e))?
+            .clone();
+        Ok((ir_program, ir_builder.warnings().to_vec()))
+    }
+
+    fn compile(&self, include: &[PathBuf], options: CompileOptions) -> Result<TypedProgram, Box<dyn Diagnostic>> {
+        self.compile_traced(include, options).map(|(typed, _)| typed)
+    }
+
+    fn compile_traced(
+        &self,
+        include: &[PathBuf],
+        options: CompileOptions,
+    ) -> Result<(TypedProgram, StageTimings), Box<dyn Diagnostic>> {
+        match self {
+            SourceInput::File(path) => compile_file_traced(path, include, options),
+            SourceInput::Stdin(source) => compile_source_at_traced(source, self.path(), include, options),
+        }
+    }
+}
+
+/// Check a Grey source and its imports, printing the result. Returns whether it
+/// compiled, and with `deny_warnings` whether it did so without warnings.
+fn check_file(
+    input: &SourceInput,
+    include: &[PathBuf],
+    options: CompileOptions,
+    timings: bool,
+    deny_warnings: bool,
+) -> anyhow::Result<bool> {
+    println!("Checking '{}'...", input.display());
+    
+    match input.compile_traced(include, options) {
+        Ok((typed, stage_timings)) => {
+            let warnings = ir_warnings(input, &typed);
+            for warning in &warnings {
+                println!("⚠️  Warning: {}", warning);
+            }
+            if deny_warnings && !warnings.is_empty() {
+                println!("❌ {} warning(s) denied by --deny-warnings", warnings.len());
+                return Ok(false);
+            }
+
+            println!("✅ No errors found. Program is valid Grey.");
+            if timings {
+                print_stage_timings(&stage_timings);
+            }
+            Ok(true)
+        }
+        Err(e) if e.constraint_violation().is_some() => {
+            // Validation stops at the first violation; collect them all to group by kind
+            let unvalidated = CompileOptions { validate: false, ..options };
+            let typed = input
+                .compile(include, unvalidated)
+                .map_err(|e| anyhow::anyhow!("Compilation failed: {:?}", e))?;
+            println!("❌ O(1) validation failed:");
+            print!("{}", format_violations(&O1Validator::new().violations(&typed)));
+            Ok(false)
+        }
+        Err(e) => {
+            println!("❌ Compilation failed:");
+            println!("{:?}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Warnings from building `typed` into IR. IR errors are left for `emit-betti` to report.
+fn ir_warnings(input: &SourceInput, typed: &TypedProgram) -> Vec<IrWarning> {
+    let mut builder = IrBuilder::new();
+    match builder.build_program(input.program_name(), typed) {
+        Ok(_) => builder.warnings().to_vec(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// `-Werror` as the `--deny-warnings` flag clap understands; other arguments unchanged
+fn expand_werror(arg: OsString) -> OsString {
+    if arg == "-Werror" {
+        OsString::from("--deny-warnings")
+    } else {
+        arg
+    }
+}
+
+/// List constraint violations grouped by kind, kinds in alphabetical order
+fn format_violations(violations: &[ConstraintViolation]) -> String {
+    let mut by_kind: BTreeMap<&str, Vec<&ConstraintViolation>> = BTreeMap::new();
+    for violation in violations {
+        by_kind.entry(violation.kind()).or_default().push(violation);
+    }
+
+    let mut text = String::new();
+    for (kind, violations) in by_kind {
+        text.push_str(&format!("  {} ({}):\n", kind, violations.len()));
+        for violation in violations {
+            text.push_str(&format!("    {}\n", violation));
+        }
+    }
+    text
+}
+
+fn print_stage_timings(timings: &StageTimings) {
+    println!("⏱️  Stage timings:");
+    for (stage, duration) in [
+        ("lex", timings.lex),
+        ("parse", timings.parse),
+        ("type check", timings.type_check),
+        ("validate", timings.validate),
+        ("total", timings.total()),
+    ] {
+        println!("  {:<11} {:?}", stage, duration);
+    }
+}
+
+/// Polls a file's modification time and length to detect edits
+struct FileWatcher {
+    path: PathBuf,
+    fingerprint: Option<(SystemTime, u64)>,
+}
+
+impl FileWatcher {
+    const POLL_INTERVAL: Duration = Duration::from_millis(250);
+    const DEBOUNCE: Duration = Duration::from_millis(100);
+
+    fn new(path: &Path) -> Self {
+        let mut watcher = Self { path: path.to_path_buf(), fingerprint: None };
+        watcher.fingerprint = watcher.read_fingerprint();
+        watcher
+    }
+
+    fn read_fingerprint(&self) -> Option<(SystemTime, u64)> {
+        let metadata = fs::metadata(&self.path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    /// Returns true if the file changed since the last poll
+    fn poll(&mut self) -> bool {
+        let current = self.read_fingerprint();
+        if current == self.fingerprint {
+            return false;
+        }
+        self.fingerprint = current;
+        true
+    }
+
+    /// Block until the file changes, then until successive writes settle
+    fn wait_for_change(&mut self) {
+        while !self.poll() {
+            std::thread::sleep(Self::POLL_INTERVAL);
+        }
+        loop {
+            std::thread::sleep(Self::DEBOUNCE);
+            if !self.poll() {
+                break;
+            }
+        }
+    }
+}
+
+/// Structural metrics of a compiled program
+#[derive(Debug, PartialEq)]
+struct ProgramStats {
+    processes: usize,
+    events: usize,
+    fields: usize,
+    transitions: usize,
+    actions: usize,
+    max_actions_per_transition: usize,
+    max_expression_depth: usize,
+    constants: usize,
+    estimated_execution_time_ns: Option<u64>,
+}
+
+impl ProgramStats {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "processes": self.processes,
+            "events": self.events,
+            "fields": self.fields,
+            "transitions": self.transitions,
+            "actions": self.actions,
+            "max_actions_per_transition": self.max_actions_per_transition,
+            "max_expression_depth": self.max_expression_depth,
+            "constants": self.constants,
+            "estimated_execution_time_ns": self.estimated_execution_time_ns,
+        })
+    }
+}
+
+/// Compile a Grey source and collect its stats, using the Betti RDL codegen
+/// metadata for the execution time estimate
+fn program_stats(input: &SourceInput, include: &[PathBuf], options: CompileOptions) -> anyhow::Result<ProgramStats> {
+    let (ir_program, _) = input.compile_to_ir(include, options)?;
+
+    let metadata = BettiRdlBackend::new_with_defaults()
+        .generate_code(&ir_program)
+        .map_err(|e| anyhow::anyhow!("Code generation failed: {}", e))?
+        .metadata;
+
+    let complexity = ir_program.complexity();
+
+    Ok(ProgramStats {
+        processes: metadata.process_count,
+        events: metadata.event_count,
+        fields: complexity.field_count,
+        transitions: complexity.total_transitions,
+        actions: ir_program.processes.iter()
+            .flat_map(|p| &p.transitions)
+            .map(|t| t.actions.len())
+            .sum(),
+        max_actions_per_transition: complexity.max_actions_per_transition,
+        max_expression_depth: complexity.max_expression_depth,
+        constants: ir_program.constants.len(),
+        estimated_execution_time_ns: metadata.expected_execution_time,
+    })
+}
+
+/// Read an injection plan: a JSON array of `[{"x":..,"y":..,"z":..}, value]` pairs
+fn load_injection_plan(path: &Path) -> anyhow::Result<Vec<(Coord, i32)>> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read injections '{}': {}", path.display(), e))?;
+    serde_json::from_str(&text)
+        .map_err(|e| anyhow::anyhow!("Invalid injections '{}': {}", path.display(), e))
+}
+
+/// Execute generated output on `engine`; the interpreter runs `program` directly
+fn execute_on(
+    engine: Engine,
+    backend: &BettiRdlBackend,
+    program: &IrProgram,
+    output: &CodeGenOutput,
+) -> anyhow::Result<ExecutionTelemetry> {
+    let telemetry = match engine {
+        Engine::Kernel => backend.execute(output),
+        Engine::Interpreter => backend.execute_interpreted(program, output),
+    };
+    telemetry.map_err(|e| anyhow::anyhow!("Execution failed: {}", e))
+}
+
+/// Compile a Grey source and execute it in memory on the Betti RDL backend
+fn run_program(
+    input: &SourceInput,
+    include: &[PathBuf],
+    max_events: i32,
+    seed: u64,
+    options: CompileOptions,
+) -> anyhow::Result<ExecutionTelemetry> {
+    let (ir_program, _) = input.compile_to_ir(include, options)?;
+
+    let backend = BettiRdlBackend::new(grey_backends::betti_rdl::BettiConfig {
+        max_events,
+        seed,
+        ..Default::default()
+    });
+    let output = backend.generate_code(&ir_program)
+        .map_err(|e| anyhow::anyhow!("Code generation failed: {}", e))?;
+
+    backend.execute(&output)
+        .map_err(|e| anyhow::anyhow!("Execution failed: {}", e))
 }
 
 fn main() -> anyhow::Result<()> {
@@ -60,77 +580,110 @@ fn main() -> anyhow::Result<()> {
         .try_init()
         .ok(); // Ignore if already initialized
     
-    let cli = Cli::parse();
+    let cli = Cli::parse_from(std::env::args_os().map(expand_werror));
+    let options = CompileOptions { validate: !cli.no_validate, ..CompileOptions::default() };
+    let deny_warnings = cli.deny_warnings;
     
     match cli.command {
-        Commands::Check { input } => {
-            if !input.exists() {
-                anyhow::bail!("Input file '{}' does not exist", input.display());
-            }
-            
-            if !input.extension().map_or(false, |ext| ext == "grey") {
-                anyhow::bail!("Input file must have .grey extension");
-            }
+        Commands::Check { input, watch, include, timings } => {
+            let input = SourceInput::open(input, io::stdin())?;
             
-            let source = fs::read_to_string(&input)?;
-            println!("Checking '{}'...", input.display());
-            
-            match compile(&source) {
-                Ok(_) => {
-                    println!("✅ No errors found. Program is valid Grey.");
-                    Ok(())
-                }
-                Err(e) => {
-                    println!("❌ Compilation failed:");
-                    println!("{:?}", e);
+            if !watch {
+                if !check_file(&input, &include, options, timings, deny_warnings)? {
                     std::process::exit(1);
                 }
-            }
-        }
-        
-        Commands::EmitBetti { input, run, max_events, seed, telemetry } => {
-            if !input.exists() {
-                anyhow::bail!("Input file '{}' does not exist", input.display());
+                return Ok(());
             }
             
-            if !input.extension().map_or(false, |ext| ext == "grey") {
-                anyhow::bail!("Input file must have .grey extension");
+            let SourceInput::File(path) = &input else {
+                anyhow::bail!("--watch needs an input file, not stdin");
+            };
+            let mut watcher = FileWatcher::new(path);
+            loop {
+                // Clear the terminal so only the latest diagnostics are shown
+                print!("\x1B[2J\x1B[H");
+                check_file(&input, &include, options, timings, deny_warnings)?;
+                println!("\n👀 Watching '{}' for changes (Ctrl+C to stop)...", input.display());
+                io::stdout().flush()?;
+                watcher.wait_for_change();
             }
+        }
+        
+        Commands::EmitBetti {
+            input,
+            run,
+            max_events,
+            seed,
+            include,
+            seeds,
+            telemetry,
+            per_event_ns,
+            per_process_ns,
+            dry_run,
+            engine,
+            injections,
+            placement_report,
+            strict_coords,
+        } => {
+            let input = SourceInput::open(input, io::stdin())?;
             
-            let source = fs::read_to_string(&input)?;
             println!("Compiling '{}' to Betti RDL...", input.display());
             
-            // Compile Grey source
-            let typed_program = compile(&source)
-                .map_err(|e| anyhow::anyhow!("Compilation failed: {:?}", e))?;
+            // Compile Grey source and its imports, then build IR
+            let (ir_program, warnings) = input.compile_to_ir(&include, options)?;
             
             println!("✅ Compilation successful");
-            
-            // Build IR
-            let program_name = input.file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("program");
-            
-            let mut ir_builder = IrBuilder::new();
-            let ir_program = ir_builder.build_program(program_name, &typed_program)
-                .map_err(|e| anyhow::anyhow!("IR building failed: {}", e))?;
-            
             println!("✅ IR built successfully: {} processes, {} events", 
                      ir_program.processes.len(), ir_program.events.len());
+
+            for warning in &warnings {
+                println!("⚠️  Warning: {}", warning);
+            }
+            if deny_warnings && !warnings.is_empty() {
+                anyhow::bail!("{} warning(s) denied by --deny-warnings", warnings.len());
+            }
             
             // Generate Betti RDL code
-            let backend = BettiRdlBackend::new(grey_backends::betti_rdl::BettiConfig {
+            let config = BettiConfig {
                 max_events,
                 seed,
-                process_placement: grey_backends::ProcessPlacement::GridLayout { spacing: 1 },
+                ordering_seed: None,
+                process_placement: grey_backends::ProcessPlacement::GridLayout { spacing: 1, columns: None },
                 telemetry_enabled: telemetry || run, // Enable telemetry if running
                 validate_coordinates: true,
-            });
+                strict_coords,
+                per_event_ns,
+                per_process_ns,
+                injection_plan: injections.as_deref().map(load_injection_plan).transpose()?,
+            };
+            let backend = BettiRdlBackend::new(config.clone());
+
+            if dry_run {
+                let plan = backend.plan(&ir_program)
+                    .map_err(|e| anyhow::anyhow!("Code generation failed: {}", e))?;
+
+                println!("📋 Dry run, nothing generated:");
+                println!("  Runtime processes: {}", plan.metadata.runtime_process_count);
+                if let Some(ns) = plan.metadata.expected_execution_time {
+                    println!("  Estimated execution time: {} ns", ns);
+                }
+                for path in &plan.files {
+                    println!("  Would generate: {}", path.display());
+                }
+                return Ok(());
+            }
             
-            let output = backend.generate_code(ir_program)
+            let output = backend.generate_code(&ir_program)
                 .map_err(|e| anyhow::anyhow!("Code generation failed: {}", e))?;
             
             println!("✅ Betti RDL code generated");
+            println!("🔑 Output hash: {}", grey_backends::utils::hash_output(&output));
+
+            if placement_report {
+                println!("\n📍 Placement:");
+                print!("{}", backend.placement_report(&output));
+                println!();
+            }
             
             // Write generated files
             if let Some((path, content)) = output.files.iter().find(|(path, _)| {
@@ -141,12 +694,31 @@ fn main() -> anyhow::Result<()> {
             }
             
             // Run if requested
-            if run {
+            if run && !seeds.is_empty() {
+                println!("🚀 Running Betti RDL executable for {} seed(s)...", seeds.len());
+                println!("⚙️  Engine: {:?}", engine);
+                
+                let sweep = seeds
+                    .iter()
+                    .map(|&seed| {
+                        let backend = BettiRdlBackend::new(BettiConfig { seed, ..config.clone() });
+                        execute_on(engine, &backend, &ir_program, &output).map(|t| (seed, t))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                
+                println!("\n📊 Seed Sweep Telemetry:");
+                for (seed, telemetry_result) in &sweep {
+                    println!(
+                        "  Seed {}: {} events processed, current time {}",
+                        seed, telemetry_result.events_processed, telemetry_result.current_time
+                    );
+                }
+            } else if run {
                 println!("🚀 Running Betti RDL executable...");
+                println!("⚙️  Engine: {:?}", engine);
                 
                 let start_time = std::time::Instant::now();
-                let telemetry_result = backend.execute(&output)
-                    .map_err(|e| anyhow::anyhow!("Execution failed: {}", e))?;
+                let telemetry_result = execute_on(engine, &backend, &ir_program, &output)?;
                 let execution_time = start_time.elapsed();
                 
                 println!("✅ Execution completed in {:?}", execution_time);
@@ -157,6 +729,7 @@ fn main() -> anyhow::Result<()> {
                 println!("  Current time: {}", telemetry_result.current_time);
                 println!("  Execution time: {:.3}ms", execution_time.as_secs_f64() * 1000.0);
                 println!("  Total processes: {}", telemetry_result.process_states.len());
+                println!("  Quiescent: {}", telemetry_result.quiescent);
                 
                 if telemetry {
                     println!("\n📋 Detailed Metrics:");
@@ -184,6 +757,90 @@ fn main() -> anyhow::Result<()> {
             Ok(())
         }
         
+        Commands::Run { input, include, max_events, seed } => {
+            let input = SourceInput::open(input, io::stdin())?;
+            
+            println!("🚀 Running '{}'...", input.display());
+            let telemetry = run_program(&input, &include, max_events, seed, options)?;
+            
+            println!("\n📊 Execution Telemetry:");
+            println!("  Events processed: {}", telemetry.events_processed);
+            println!("  Current time: {}", telemetry.current_time);
+            println!("  Total processes: {}", telemetry.process_states.len());
+            println!("  Quiescent: {}", telemetry.quiescent);
+            Ok(())
+        }
+        
+        Commands::Stats { input, include, json } => {
+            let input = SourceInput::open(input, io::stdin())?;
+            
+            let stats = program_stats(&input, &include, options)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats.to_json())?);
+            } else {
+                println!("📊 Stats for '{}':", input.display());
+                println!("  Processes: {}", stats.processes);
+                println!("  Events: {}", stats.events);
+                println!("  Fields: {}", stats.fields);
+                println!("  Transitions: {}", stats.transitions);
+                println!("  Actions: {}", stats.actions);
+                println!("  Max actions per transition: {}", stats.max_actions_per_transition);
+                println!("  Max expression depth: {}", stats.max_expression_depth);
+                println!("  Constants: {}", stats.constants);
+                match stats.estimated_execution_time_ns {
+                    Some(ns) => println!("  Estimated execution time: {} ns", ns),
+                    None => println!("  Estimated execution time: unknown"),
+                }
+            }
+            Ok(())
+        }
+        
+        Commands::Backends => {
+            for name in grey_backends::available_backends() {
+                println!("{}", name);
+            }
+            Ok(())
+        }
+        
+        Commands::BackendOptions { name } => {
+            print!("{}", format_backend_options(&name)?);
+            Ok(())
+        }
+        
+        Commands::EmitDot { input, include, png } => {
+            let input = SourceInput::open(input, io::stdin())?;
+            
+            let dot_path = emit_dot(&input, &include, options)?;
+            println!("📝 Generated file: {}", dot_path.display());
+            
+            if png {
+                let png_path = dot_path.with_extension("png");
+                match std::process::Command::new("dot")
+                    .arg("-Tpng")
+                    .arg(&dot_path)
+                    .arg("-o")
+                    .arg(&png_path)
+                    .status()
+                {
+                    Ok(status) if status.success() => {
+                        println!("🖼️  Rendered image: {}", png_path.display());
+                    }
+                    Ok(status) => println!("⚠️  dot exited with {}", status),
+                    Err(_) => println!("⚠️  Graphviz 'dot' not found; skipping PNG rendering"),
+                }
+            }
+            
+            Ok(())
+        }
+
+        Commands::EmitHeader { input, include } => {
+            let input = SourceInput::open(input, io::stdin())?;
+            
+            let header_path = emit_header(&input, &include, options)?;
+            println!("📝 Generated file: {}", header_path.display());
+            Ok(())
+        }
+        
         Commands::Repl => {
             println!("Grey Programming Language REPL v0.1.0");
             println!("Type 'exit' to quit.");
@@ -212,7 +869,7 @@ fn main() -> anyhow::Result<()> {
                         }
                         
                         // Try to compile the input
-                        match compile(input) {
+                        match compile_with_options(input, options) {
                             Ok(_) => println!("✅ Valid expression"),
                             Err(e) => println!("❌ Error: {}", e),
                         }
@@ -228,4 +885,253 @@ fn main() -> anyhow::Result<()> {
             Ok(())
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_betti_backend_options_listed_with_defaults() {
+        let text = format_backend_options("betti_rdl").unwrap();
+
+        assert!(text.contains("max_events\n  Maximum events to process\n  default: 1000\n"));
+        assert!(text.contains("seed\n"));
+        assert!(text.contains("  default: 42\n"));
+        assert!(text.contains("  default: GridLayout\n"));
+    }
+
+    #[test]
+    fn test_unknown_backend_options_error() {
+        let err = format_backend_options("nope").unwrap_err();
+        assert!(err.to_string().contains("betti_rdl, dot"));
+    }
+
+    #[test]
+    fn test_emit_dot_writes_graph_with_each_process() {
+        let dir = std::env::temp_dir().join(format!("greyc_emit_dot_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("topology.grey");
+        fs::write(
+            &input,
+            r#"
+            module Topology {
+                event Tick {}
+                event Item {}
+                process Sender {
+                    count: Int,
+                    method handle_tick(event: Tick) {
+                        this.count = this.count + 1;
+                    }
+                }
+                process Receiver {
+                    total: Int,
+                    method handle_item(event: Item) {
+                        this.total = this.total + 1;
+                    }
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let input = SourceInput::open(input, io::empty()).unwrap();
+        let dot_path = emit_dot(&input, &[], CompileOptions::default()).unwrap();
+        let dot = fs::read_to_string(&dot_path).unwrap();
+        let header_path = emit_header(&input, &[], CompileOptions::default()).unwrap();
+        let header = fs::read_to_string(&header_path).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(dot_path.extension().unwrap(), "dot");
+        assert!(dot.contains("Sender"));
+        assert!(dot.contains("Receiver"));
+        assert_eq!(header_path.extension().unwrap(), "h");
+        assert!(header.contains("} topology_Receiver_state_t;"));
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_run_executes_demo_in_memory() {
+        let demo = SourceInput::File(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/sir_demo.grey"));
+        let telemetry = run_program(&demo, &[], 1000, 42, CompileOptions::default()).unwrap();
+
+        assert!(telemetry.events_processed > 0);
+    }
+
+    #[test]
+    fn test_run_rejects_negative_max_events() {
+        let demo = SourceInput::File(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/sir_demo.grey"));
+        let cli = Cli::try_parse_from(["greyc", "run", "demo.grey", "--max-events", "-5"]).unwrap();
+        let Commands::Run { max_events, .. } = cli.command else { panic!("expected run command") };
+
+        let err = run_program(&demo, &[], max_events, 42, CompileOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("max_events must be non-negative"), "{err}");
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_run_with_zero_max_events_is_unbounded() {
+        let demo = SourceInput::File(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/sir_demo.grey"));
+        let unbounded = run_program(&demo, &[], 0, 42, CompileOptions::default()).unwrap();
+        assert!(unbounded.quiescent);
+    }
+
+    #[test]
+    fn test_file_watcher_detects_modification() {
+        let dir = std::env::temp_dir().join(format!("greyc_watch_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("watched.grey");
+        fs::write(&input, "module Watched {}").unwrap();
+
+        let mut watcher = FileWatcher::new(&input);
+        assert!(!watcher.poll());
+
+        fs::write(&input, "module Watched { event Tick {} }").unwrap();
+        let changed = watcher.poll();
+        let settled = !watcher.poll();
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(changed);
+        assert!(settled);
+    }
+
+    #[test]
+    fn test_stats_match_logistics_demo() {
+        let demo = SourceInput::File(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/logistics.grey"));
+        let stats = program_stats(&demo, &[], CompileOptions::default()).unwrap();
+
+        assert_eq!(stats.processes, 1);
+        assert_eq!(stats.events, 2);
+        assert_eq!(stats.fields, 7);
+        assert_eq!(stats.transitions, 2);
+        assert_eq!(stats.actions, 3);
+        assert_eq!(stats.max_actions_per_transition, 2);
+        assert_eq!(stats.max_expression_depth, 2);
+        assert_eq!(stats.constants, 2);
+        assert!(stats.estimated_execution_time_ns.is_some());
+        assert_eq!(stats.to_json()["transitions"], 2);
+    }
+
+    #[test]
+    fn test_check_resolves_use_from_include_dir() {
+        let dir = std::env::temp_dir().join(format!("greyc_include_{}", std::process::id()));
+        let lib = dir.join("lib");
+        fs::create_dir_all(&lib).unwrap();
+        fs::write(lib.join("events.grey"), "module Events { event Tick {} }").unwrap();
+        let input = dir.join("main.grey");
+        fs::write(
+            &input,
+            "use events;\nmodule Main { process Clock { ticks: Int, method handle_tick(event: Tick) {} } }",
+        )
+        .unwrap();
+
+        let input = SourceInput::File(input);
+        let without_include = check_file(&input, &[], CompileOptions::default(), false, false).unwrap();
+        let with_include = check_file(&input, &[lib], CompileOptions::default(), false, false).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(!without_include);
+        assert!(with_include);
+    }
+
+    #[test]
+    fn test_stats_read_stdin_with_include_dir() {
+        let dir = std::env::temp_dir().join(format!("greyc_stats_stdin_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("events.grey"), "module Events { event Tick {} }").unwrap();
+        let piped: &[u8] = b"use events;\nmodule Main { process Clock { ticks: Int, method handle_tick(event: Tick) {} } }";
+
+        let input = SourceInput::open(PathBuf::from("-"), piped).unwrap();
+        let without_include = program_stats(&input, &[], CompileOptions::default());
+        let stats = program_stats(&input, std::slice::from_ref(&dir), CompileOptions::default());
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(without_include.is_err());
+        let stats = stats.unwrap();
+        assert_eq!(stats.processes, 1);
+        assert_eq!(stats.events, 1);
+
+        for command in ["run", "stats", "emit-dot", "emit-header"] {
+            let cli = Cli::try_parse_from(["greyc", command, "-", "--include", "lib"]);
+            assert!(cli.is_ok(), "{command}");
+        }
+    }
+
+    #[test]
+    fn test_no_validate_skips_o1_validation() {
+        let input = std::env::temp_dir().join(format!("greyc_no_validate_{}.grey", std::process::id()));
+        fs::write(
+            &input,
+            "module M { process Node { count: Int, method tick() { this.tick(); } } }",
+        )
+        .unwrap();
+
+        let source = SourceInput::File(input.clone());
+        let validated = check_file(&source, &[], CompileOptions::default(), false, false).unwrap();
+        let unvalidated = check_file(&source, &[], CompileOptions { validate: false, ..CompileOptions::default() }, false, false).unwrap();
+        fs::remove_file(&input).ok();
+
+        assert!(!validated);
+        assert!(unvalidated);
+
+        let source = "module M { process Node { log: [] of int, queue: [] of int, method tick() { this.tick(); } } }";
+        let program = grey_lang::parse_source(source).unwrap();
+        let typed = grey_lang::type_check_program(&program).unwrap();
+        assert_eq!(
+            format_violations(&O1Validator::new().violations(&typed)),
+            "  recursion (1):\n    recursive method calls in process Node: tick -> tick\n\
+             \x20 unbounded collection (2):\n\
+             \x20   process Node field log is a growable collection with no fixed capacity\n\
+             \x20   process Node field queue is a growable collection with no fixed capacity\n"
+        );
+
+        let cli = Cli::try_parse_from(["greyc", "check", "main.grey", "--no-validate"]).unwrap();
+        assert!(cli.no_validate);
+    }
+
+    #[test]
+    fn test_check_reads_source_from_stdin() {
+        let piped: &[u8] = b"module Piped { event Tick {} process Clock { ticks: Int, method handle_tick(event: Tick) { this.ticks = this.ticks + 1; } } }";
+        let input = SourceInput::open(PathBuf::from("-"), piped).unwrap();
+
+        assert_eq!(input.display(), "<stdin>");
+        assert_eq!(input.path().file_stem().unwrap(), "stdin");
+        assert!(check_file(&input, &[], CompileOptions::default(), false, false).unwrap());
+
+        let broken = SourceInput::open(PathBuf::from("-"), &b"module Broken {"[..]).unwrap();
+        assert!(!check_file(&broken, &[], CompileOptions::default(), false, false).unwrap());
+    }
+
+    #[test]
+    fn test_deny_warnings_fails_check_on_warnings() {
+        // Node handles no events, which is a warning but not an error
+        let source: &[u8] = b"module Idle { process Node { count: Int } }";
+        let input = SourceInput::open(PathBuf::from("-"), source).unwrap();
+
+        assert!(check_file(&input, &[], CompileOptions::default(), false, false).unwrap());
+        assert!(!check_file(&input, &[], CompileOptions::default(), false, true).unwrap());
+
+        let args = ["greyc", "-Werror", "check", "main.grey"].map(OsString::from);
+        assert!(Cli::try_parse_from(args.into_iter().map(expand_werror)).unwrap().deny_warnings);
+        assert!(Cli::try_parse_from(["greyc", "check", "main.grey", "--deny-warnings"]).unwrap().deny_warnings);
+    }
+
+    #[test]
+    #[cfg(feature = "betti-runtime")]
+    fn test_kernel_and_interpreter_engines_agree_on_event_count() {
+        let demo = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/sir_demo.grey");
+        let typed = compile_with_options(&fs::read_to_string(demo).unwrap(), CompileOptions::default()).unwrap();
+        let program = IrBuilder::new().build_program("sir_demo", &typed).unwrap().clone();
+
+        // The demo's handlers send no events, so the interpreter processes exactly the
+        // four seeded injections; capping both engines there makes the counts comparable.
+        let backend = BettiRdlBackend::new(BettiConfig { max_events: 4, ..Default::default() });
+        let output = backend.generate_code(&program).unwrap();
+
+        let kernel = execute_on(Engine::Kernel, &backend, &program, &output).unwrap();
+        let interpreted = execute_on(Engine::Interpreter, &backend, &program, &output).unwrap();
+        assert_eq!(interpreted.events_processed, 4);
+        assert_eq!(kernel.events_processed, interpreted.events_processed);
+        assert!(interpreted.quiescent);
+    }
+}
@@ -106,7 +106,7 @@ mod tests {
         // Step 3: Generate Betti RDL code
         let backend = BettiRdlBackend::new(BettiConfig {
             max_events: 100,
-            process_placement: grey_backends::ProcessPlacement::GridLayout { spacing: 1 },
+            process_placement: grey_backends::ProcessPlacement::GridLayout { spacing: 1, columns: None },
             telemetry_enabled: true,
             validate_coordinates: true,
         });
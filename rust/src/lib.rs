@@ -1,6 +1,9 @@
 // Rust FFI wrapper - links to C API
 
+use std::borrow::{Borrow, BorrowMut};
+use std::ops::{Deref, DerefMut};
 use std::os::raw::c_int;
+use std::sync::{Condvar, Mutex, PoisonError};
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default)]
@@ -31,23 +34,65 @@ extern "C" {
         value: c_int,
     );
     fn betti_rdl_run(kernel: *mut std::ffi::c_void, max_events: c_int) -> c_int;
+    fn betti_rdl_run_until_time(kernel: *mut std::ffi::c_void, target_time: u64) -> u64;
     fn betti_rdl_get_events_processed(kernel: *const std::ffi::c_void) -> u64;
     fn betti_rdl_get_current_time(kernel: *const std::ffi::c_void) -> u64;
     fn betti_rdl_get_process_count(kernel: *const std::ffi::c_void) -> usize;
+    fn betti_rdl_get_pending_events(kernel: *const std::ffi::c_void) -> usize;
     fn betti_rdl_get_process_state(kernel: *const std::ffi::c_void, pid: c_int) -> c_int;
+    fn betti_rdl_get_all_process_states(
+        kernel: *const std::ffi::c_void,
+        states: *mut c_int,
+        len: usize,
+    ) -> usize;
     fn betti_rdl_get_telemetry(kernel: *const std::ffi::c_void) -> BettiRDLTelemetry;
 }
 
+/// Error returned when the C API cannot provide a kernel instance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KernelError {
+    /// `betti_rdl_create` returned a null pointer.
+    CreationFailed,
+}
+
+impl std::fmt::Display for KernelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KernelError::CreationFailed => write!(f, "Failed to create Betti-RDL kernel"),
+        }
+    }
+}
+
+impl std::error::Error for KernelError {}
+
 pub struct Kernel {
     inner: *mut std::ffi::c_void,
 }
 
 impl Kernel {
+    /// Create a kernel, panicking if the C API fails to allocate one.
     pub fn new() -> Self {
-        unsafe {
-            let ptr = betti_rdl_create();
-            assert!(!ptr.is_null(), "Failed to create Betti-RDL kernel");
-            Kernel { inner: ptr }
+        Self::try_new().expect("Failed to create Betti-RDL kernel")
+    }
+
+    /// Create a kernel, reporting allocation failure as an error instead of panicking.
+    pub fn try_new() -> Result<Self, KernelError> {
+        Self::from_raw(unsafe { betti_rdl_create() })
+    }
+
+    /// Return the kernel to its freshly created state: no processes, no queued
+    /// events, time zero. The C API has no in-place reset, so this swaps in a new
+    /// kernel; on failure the current one is left untouched.
+    pub fn reset(&mut self) -> Result<(), KernelError> {
+        *self = Self::try_new()?;
+        Ok(())
+    }
+
+    fn from_raw(ptr: *mut std::ffi::c_void) -> Result<Self, KernelError> {
+        if ptr.is_null() {
+            Err(KernelError::CreationFailed)
+        } else {
+            Ok(Kernel { inner: ptr })
         }
     }
 
@@ -68,6 +113,15 @@ impl Kernel {
         unsafe { betti_rdl_run(self.inner, max_events) }
     }
 
+    /// Process events until `current_time()` reaches `target_time` and return how
+    /// many ran. Stops early, short of the target, if the event queue drains.
+    ///
+    /// The event that reaches the target is processed in full, so when none lands
+    /// on it exactly `current_time()` ends past `target_time`.
+    pub fn step_until_time(&mut self, target_time: u64) -> u64 {
+        unsafe { betti_rdl_run_until_time(self.inner, target_time) }
+    }
+
     pub fn events_processed(&self) -> u64 {
         unsafe { betti_rdl_get_events_processed(self.inner) }
     }
@@ -80,10 +134,34 @@ impl Kernel {
         unsafe { betti_rdl_get_process_count(self.inner) }
     }
 
+    /// Number of events still queued. Zero after a run means the workload drained
+    /// rather than stopping at the `max_events` cap.
+    pub fn events_pending(&self) -> usize {
+        unsafe { betti_rdl_get_pending_events(self.inner) }
+    }
+
+    /// True when no events are queued.
+    pub fn is_quiescent(&self) -> bool {
+        self.events_pending() == 0
+    }
+
+    /// State of the process at node `pid`, where `pid = x * 1024 + y * 32 + z` with
+    /// each component wrapped modulo 32 (`grey_ir::Coord::to_node_id` on the Grey side).
     pub fn process_state(&self, pid: i32) -> i32 {
         unsafe { betti_rdl_get_process_state(self.inner, pid) }
     }
 
+    /// States of the processes at `pids`, in order, fetched in a single call
+    /// (`betti_rdl_get_all_process_states` in `betti_rdl_c_api_ext.cpp`).
+    pub fn process_states_bulk(&self, pids: &[usize]) -> Vec<i32> {
+        let mut states: Vec<c_int> = pids.iter().map(|&pid| pid as c_int).collect();
+        let filled = unsafe {
+            betti_rdl_get_all_process_states(self.inner, states.as_mut_ptr(), states.len())
+        };
+        debug_assert_eq!(filled, states.len());
+        states
+    }
+
     pub fn get_telemetry(&self) -> Telemetry {
         unsafe {
             let c_telemetry = betti_rdl_get_telemetry(self.inner);
@@ -106,4 +184,224 @@ impl Drop for Kernel {
 }
 
 unsafe impl Send for Kernel {}
-unsafe impl Sync for Kernel {}
+
+/// A bounded set of reusable kernels shared between threads.
+///
+/// The C kernel is not known to be safe for concurrent use, so the pool never
+/// shares one: `checkout` hands a kernel to a single caller and blocks while all
+/// `capacity` kernels are out. Kernels are reset before being handed out again,
+/// so no processes, events or time carry over between uses.
+pub struct KernelPool {
+    state: Mutex<PoolState>,
+    returned: Condvar,
+}
+
+struct PoolState {
+    idle: Vec<Kernel>,
+    created: usize,
+    capacity: usize,
+}
+
+impl KernelPool {
+    /// Create a pool that holds at most `capacity` kernels (at least one).
+    /// Kernels are created lazily on first checkout.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(PoolState {
+                idle: Vec::new(),
+                created: 0,
+                capacity: capacity.max(1),
+            }),
+            returned: Condvar::new(),
+        }
+    }
+
+    /// Maximum number of kernels the pool will create.
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity
+    }
+
+    /// Take a fresh kernel, waiting for one to be returned if all are in use.
+    /// The kernel goes back to the pool when the guard is dropped.
+    pub fn checkout(&self) -> Result<PooledKernel<'_>, KernelError> {
+        let mut state = self.lock();
+        loop {
+            if let Some(mut kernel) = state.idle.pop() {
+                drop(state);
+                if let Err(err) = kernel.reset() {
+                    self.release(kernel);
+                    return Err(err);
+                }
+                return Ok(PooledKernel { pool: self, kernel: Some(kernel) });
+            }
+
+            if state.created < state.capacity {
+                state.created += 1;
+                drop(state);
+                return match Kernel::try_new() {
+                    Ok(kernel) => Ok(PooledKernel { pool: self, kernel: Some(kernel) }),
+                    Err(err) => {
+                        self.lock().created -= 1;
+                        self.returned.notify_one();
+                        Err(err)
+                    }
+                };
+            }
+
+            state = self.returned.wait(state).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    fn release(&self, kernel: Kernel) {
+        self.lock().idle.push(kernel);
+        self.returned.notify_one();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PoolState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A kernel checked out of a [`KernelPool`], returned to it on drop.
+pub struct PooledKernel<'a> {
+    pool: &'a KernelPool,
+    kernel: Option<Kernel>,
+}
+
+impl Deref for PooledKernel<'_> {
+    type Target = Kernel;
+
+    fn deref(&self) -> &Kernel {
+        self.kernel.as_ref().expect("kernel is present until drop")
+    }
+}
+
+impl DerefMut for PooledKernel<'_> {
+    fn deref_mut(&mut self) -> &mut Kernel {
+        self.kernel.as_mut().expect("kernel is present until drop")
+    }
+}
+
+impl Borrow<Kernel> for PooledKernel<'_> {
+    fn borrow(&self) -> &Kernel {
+        self
+    }
+}
+
+impl BorrowMut<Kernel> for PooledKernel<'_> {
+    fn borrow_mut(&mut self) -> &mut Kernel {
+        self
+    }
+}
+
+impl Drop for PooledKernel<'_> {
+    fn drop(&mut self) {
+        if let Some(kernel) = self.kernel.take() {
+            self.pool.release(kernel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn null_kernel_pointer_is_an_error() {
+        let result = Kernel::from_raw(std::ptr::null_mut());
+        assert_eq!(result.err(), Some(KernelError::CreationFailed));
+    }
+
+    #[test]
+    fn pooled_kernels_are_reset_between_uses() {
+        let pool = KernelPool::new(1);
+
+        {
+            let mut kernel = pool.checkout().unwrap();
+            kernel.spawn_process(0, 0, 0);
+            kernel.inject_event(0, 0, 0, 3);
+            kernel.run(1);
+            assert_eq!(kernel.events_processed(), 1);
+        }
+
+        let kernel = pool.checkout().unwrap();
+        assert_eq!(kernel.events_processed(), 0);
+        assert_eq!(kernel.process_count(), 0);
+        assert!(kernel.is_quiescent());
+    }
+
+    #[test]
+    fn quiescent_only_after_queue_drains() {
+        let mut kernel = Kernel::new();
+        for x in 0..4 {
+            kernel.spawn_process(x, 0, 0);
+        }
+        assert!(kernel.is_quiescent());
+
+        kernel.inject_event(0, 0, 0, 4);
+        assert!(!kernel.is_quiescent());
+        kernel.run(1);
+        assert!(!kernel.is_quiescent());
+        kernel.run(1000);
+        assert!(kernel.is_quiescent());
+    }
+
+    #[test]
+    fn pending_events_drop_to_zero_once_drained() {
+        let mut kernel = Kernel::new();
+        for x in 0..8 {
+            kernel.spawn_process(x, 0, 0);
+        }
+        kernel.inject_event(0, 0, 0, 6);
+        kernel.inject_event(4, 0, 0, 3);
+        assert_eq!(kernel.events_pending(), 2);
+
+        kernel.run(1);
+        assert!(kernel.events_pending() > 0);
+
+        kernel.run(1000);
+        assert_eq!(kernel.events_pending(), 0);
+    }
+
+    #[test]
+    fn run_that_drains_exactly_at_its_cap_is_quiescent() {
+        let mut kernel = Kernel::new();
+        kernel.spawn_process(0, 0, 0);
+        kernel.inject_event(0, 0, 0, 1);
+
+        assert_eq!(kernel.run(1), 1);
+        assert!(kernel.is_quiescent());
+    }
+
+    #[test]
+    fn stepping_until_time_reaches_target() {
+        let mut kernel = Kernel::new();
+        for x in 0..8 {
+            kernel.spawn_process(x, 0, 0);
+        }
+        kernel.inject_event(0, 0, 0, 8);
+
+        let processed = kernel.step_until_time(3);
+        assert!(kernel.current_time() >= 3);
+        assert_eq!(processed, kernel.events_processed());
+        assert!(!kernel.is_quiescent());
+
+        assert_eq!(kernel.step_until_time(2), 0);
+    }
+
+    #[test]
+    fn bulk_process_states_match_per_pid_reads() {
+        let mut kernel = Kernel::new();
+        for x in 0..4 {
+            kernel.spawn_process(x, 0, 0);
+        }
+        kernel.inject_event(0, 0, 0, 3);
+        kernel.inject_event(2, 0, 0, 5);
+        kernel.run(100);
+
+        let pids: Vec<usize> = (0..4).map(|x| x * 1024).collect();
+        let per_pid: Vec<i32> = pids.iter().map(|&pid| kernel.process_state(pid as i32)).collect();
+        assert_eq!(kernel.process_states_bulk(&pids), per_pid);
+        assert!(kernel.process_states_bulk(&[]).is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+// Betti-RDL C API extensions, built only on the core C API entry points so
+// they do not depend on kernel internals.
+
+#include "betti_rdl_c_api_ext.h"
+
+#if defined(_WIN32)
+#define BETTI_RDL_EXT_API __declspec(dllexport)
+#else
+#define BETTI_RDL_EXT_API __attribute__((visibility("default")))
+#endif
+
+extern "C" {
+
+int betti_rdl_run(void* kernel, int max_events);
+uint64_t betti_rdl_get_current_time(const void* kernel);
+int betti_rdl_get_process_state(const void* kernel, int pid);
+
+BETTI_RDL_EXT_API size_t betti_rdl_get_all_process_states(const void* kernel, int* states,
+                                                          size_t len) {
+    if (!kernel || !states) {
+        return 0;
+    }
+    for (size_t i = 0; i < len; ++i) {
+        states[i] = betti_rdl_get_process_state(kernel, states[i]);
+    }
+    return len;
+}
+
+BETTI_RDL_EXT_API uint64_t betti_rdl_run_until_time(void* kernel, uint64_t target_time) {
+    if (!kernel) {
+        return 0;
+    }
+    uint64_t processed = 0;
+    while (betti_rdl_get_current_time(kernel) < target_time && betti_rdl_run(kernel, 1) == 1) {
+        ++processed;
+    }
+    return processed;
+}
+
+}  // extern "C"
@@ -0,0 +1,39 @@
+/*
+ * Betti-RDL C API extensions.
+ *
+ * Convenience entry points layered on the core C API in betti_rdl_c_api.cpp.
+ * They are exported from the same betti_rdl_c library.
+ */
+#ifndef BETTI_RDL_C_API_EXT_H
+#define BETTI_RDL_C_API_EXT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Read the states of several processes in one call.
+ *
+ * `states` holds `len` pids on entry; each is overwritten with the state of
+ * that process, as betti_rdl_get_process_state would report it. Returns the
+ * number of entries filled, which is `len` unless `kernel` or `states` is null.
+ */
+size_t betti_rdl_get_all_process_states(const void* kernel, int* states, size_t len);
+
+/*
+ * Process events one at a time until the kernel's current time reaches
+ * `target_time` or the queue drains. Returns the number of events processed.
+ *
+ * The event that brings the time to the target is processed in full, so the
+ * kernel can end past `target_time` when no event lands on it exactly.
+ */
+uint64_t betti_rdl_run_until_time(void* kernel, uint64_t target_time);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* BETTI_RDL_C_API_EXT_H */
@@ -0,0 +1,82 @@
+/* Tests for the C API extensions in betti_rdl_c_api_ext.cpp. */
+#include <stdio.h>
+
+#include "betti_rdl_c_api_ext.h"
+
+void* betti_rdl_create(void);
+void betti_rdl_destroy(void* kernel);
+void betti_rdl_spawn_process(void* kernel, int x, int y, int z);
+void betti_rdl_inject_event(void* kernel, int x, int y, int z, int value);
+int betti_rdl_run(void* kernel, int max_events);
+int betti_rdl_get_process_state(const void* kernel, int pid);
+uint64_t betti_rdl_get_events_processed(const void* kernel);
+uint64_t betti_rdl_get_current_time(const void* kernel);
+
+static int failures = 0;
+
+#define CHECK(cond)                                                     \
+    do {                                                                \
+        if (!(cond)) {                                                  \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,      \
+                    __LINE__, #cond);                                   \
+            failures++;                                                 \
+        }                                                               \
+    } while (0)
+
+static void test_bulk_states_match_per_pid(void) {
+    void* kernel = betti_rdl_create();
+    int pids[4];
+    int states[4];
+    int i;
+
+    for (i = 0; i < 4; i++) {
+        betti_rdl_spawn_process(kernel, i, 0, 0);
+        pids[i] = i * 1024;
+        states[i] = pids[i];
+    }
+    betti_rdl_inject_event(kernel, 0, 0, 0, 3);
+    betti_rdl_inject_event(kernel, 2, 0, 0, 5);
+    betti_rdl_run(kernel, 100);
+
+    CHECK(betti_rdl_get_all_process_states(kernel, states, 4) == 4);
+    for (i = 0; i < 4; i++) {
+        CHECK(states[i] == betti_rdl_get_process_state(kernel, pids[i]));
+    }
+
+    CHECK(betti_rdl_get_all_process_states(kernel, states, 0) == 0);
+    CHECK(betti_rdl_get_all_process_states(NULL, states, 4) == 0);
+
+    betti_rdl_destroy(kernel);
+}
+
+static void test_run_until_time_reaches_target(void) {
+    void* kernel = betti_rdl_create();
+    uint64_t processed;
+    int i;
+
+    for (i = 0; i < 8; i++) {
+        betti_rdl_spawn_process(kernel, i, 0, 0);
+    }
+    betti_rdl_inject_event(kernel, 0, 0, 0, 8);
+
+    processed = betti_rdl_run_until_time(kernel, 3);
+    CHECK(betti_rdl_get_current_time(kernel) >= 3);
+    CHECK(processed == betti_rdl_get_events_processed(kernel));
+
+    CHECK(betti_rdl_run_until_time(kernel, 2) == 0);
+    CHECK(betti_rdl_run_until_time(NULL, 5) == 0);
+
+    betti_rdl_destroy(kernel);
+}
+
+int main(void) {
+    test_bulk_states_match_per_pid();
+    test_run_until_time_reaches_target();
+
+    if (failures) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("c_api_ext_test passed\n");
+    return 0;
+}